@@ -0,0 +1,9 @@
+-- Add down migration script here
+
+ALTER TABLE barang DROP FOREIGN KEY barang_ibfk_1, DROP FOREIGN KEY barang_ibfk_2;
+DROP INDEX barang_organization_id_idx ON barang;
+ALTER TABLE barang DROP COLUMN store_id, DROP COLUMN organization_id;
+
+DROP TABLE IF EXISTS organization_members;
+DROP TABLE IF EXISTS stores;
+DROP TABLE IF EXISTS organizations;
@@ -0,0 +1,53 @@
+-- Add up migration script here
+
+CREATE TABLE organizations (
+    id CHAR(36) PRIMARY KEY NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+);
+
+CREATE TABLE stores (
+    id CHAR(36) PRIMARY KEY NOT NULL,
+    organization_id CHAR(36) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    address VARCHAR(255),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (organization_id) REFERENCES organizations (id) ON DELETE CASCADE
+);
+
+CREATE INDEX stores_organization_id_idx ON stores (organization_id);
+
+CREATE TABLE organization_members (
+    organization_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    role ENUM('owner', 'admin', 'member') NOT NULL DEFAULT 'member',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (organization_id, user_id),
+    FOREIGN KEY (organization_id) REFERENCES organizations (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX organization_members_user_id_idx ON organization_members (user_id);
+
+-- Existing data is moved into a default organization so it stays reachable.
+INSERT INTO organizations (id, name) VALUES (UUID(), 'Default');
+
+INSERT INTO organization_members (organization_id, user_id, role)
+SELECT o.id, u.id, IF(u.role = 'admin', 'owner', 'member')
+FROM users u CROSS JOIN organizations o;
+
+ALTER TABLE barang
+    ADD COLUMN organization_id CHAR(36) NULL AFTER id,
+    ADD COLUMN store_id CHAR(36) NULL AFTER organization_id;
+
+UPDATE barang SET organization_id = (SELECT id FROM organizations LIMIT 1);
+
+ALTER TABLE barang
+    MODIFY organization_id CHAR(36) NOT NULL,
+    ADD FOREIGN KEY (organization_id) REFERENCES organizations (id) ON DELETE CASCADE,
+    ADD FOREIGN KEY (store_id) REFERENCES stores (id) ON DELETE SET NULL;
+
+CREATE INDEX barang_organization_id_idx ON barang (organization_id, store_id);
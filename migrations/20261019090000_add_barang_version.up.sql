@@ -0,0 +1,3 @@
+-- Add up migration script here
+
+ALTER TABLE barang ADD COLUMN version INT NOT NULL DEFAULT 1 AFTER expired_at;
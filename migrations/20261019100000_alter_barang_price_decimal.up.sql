@@ -0,0 +1,3 @@
+-- Add up migration script here
+
+ALTER TABLE barang MODIFY price DECIMAL(19, 2) NOT NULL;
@@ -0,0 +1,26 @@
+-- Add up migration script here
+
+CREATE TABLE stock_movements (
+    id CHAR(36) PRIMARY KEY NOT NULL,
+    organization_id CHAR(36) NOT NULL,
+    barang_id CHAR(36) NOT NULL,
+    movement_type ENUM('in', 'out') NOT NULL,
+    quantity INT NOT NULL,
+    unit_cost DECIMAL(19, 2),
+    unit_price DECIMAL(19, 2),
+    note VARCHAR(255),
+    created_by CHAR(36),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (organization_id) REFERENCES organizations (id) ON DELETE CASCADE,
+    FOREIGN KEY (barang_id) REFERENCES barang (id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users (id) ON DELETE SET NULL
+);
+
+CREATE INDEX stock_movements_barang_idx ON stock_movements (organization_id, barang_id, created_at);
+
+-- Stock that existed before movements were tracked is opened at the current
+-- price, the best cost estimate available.
+INSERT INTO stock_movements (id, organization_id, barang_id, movement_type, quantity, unit_cost, note, created_at)
+SELECT UUID(), organization_id, id, 'in', stock, price, 'Opening stock', COALESCE(created_at, CURRENT_TIMESTAMP)
+FROM barang
+WHERE stock > 0;
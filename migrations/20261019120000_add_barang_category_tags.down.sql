@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP INDEX barang_category_idx ON barang;
+ALTER TABLE barang DROP COLUMN tags, DROP COLUMN category;
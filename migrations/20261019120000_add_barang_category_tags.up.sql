@@ -0,0 +1,7 @@
+-- Add up migration script here
+
+ALTER TABLE barang
+    ADD COLUMN category VARCHAR(100) NULL AFTER name,
+    ADD COLUMN tags JSON NULL AFTER category;
+
+CREATE INDEX barang_category_idx ON barang (organization_id, category);
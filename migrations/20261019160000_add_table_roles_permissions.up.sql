@@ -0,0 +1,49 @@
+-- Add up migration script here
+
+CREATE TABLE roles (
+    name VARCHAR(50) PRIMARY KEY NOT NULL,
+    description VARCHAR(255) NULL,
+    built_in BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+);
+
+CREATE TABLE role_permissions (
+    role VARCHAR(50) NOT NULL,
+    permission VARCHAR(100) NOT NULL,
+    PRIMARY KEY (role, permission),
+    FOREIGN KEY (role) REFERENCES roles (name) ON DELETE CASCADE
+);
+
+CREATE TABLE user_roles (
+    user_id CHAR(36) NOT NULL,
+    role VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, role),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (role) REFERENCES roles (name) ON DELETE CASCADE
+);
+
+-- One built-in role per value of users.role, granting what the routes allowed so far.
+INSERT INTO roles (name, description, built_in) VALUES
+    ('admin', 'Everything, including user and role administration', TRUE),
+    ('moderator', 'Inventory work and reports', TRUE),
+    ('user', 'Inventory work', TRUE);
+
+INSERT INTO role_permissions (role, permission) VALUES
+    ('admin', '*'),
+    ('moderator', 'barang.read'),
+    ('moderator', 'barang.write'),
+    ('moderator', 'barang.delete'),
+    ('moderator', 'inventory.write'),
+    ('moderator', 'dashboard.read'),
+    ('moderator', 'organization.read'),
+    ('moderator', 'organization.write'),
+    ('moderator', 'report.read'),
+    ('user', 'barang.read'),
+    ('user', 'barang.write'),
+    ('user', 'barang.delete'),
+    ('user', 'inventory.write'),
+    ('user', 'dashboard.read'),
+    ('user', 'organization.read'),
+    ('user', 'organization.write');
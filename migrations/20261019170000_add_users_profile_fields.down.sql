@@ -0,0 +1,6 @@
+-- Add down migration script here
+
+ALTER TABLE users
+    DROP COLUMN preferred_language,
+    DROP COLUMN address,
+    DROP COLUMN phone;
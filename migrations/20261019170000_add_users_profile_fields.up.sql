@@ -0,0 +1,6 @@
+-- Add up migration script here
+
+ALTER TABLE users
+    ADD COLUMN phone VARCHAR(30) NULL AFTER photo,
+    ADD COLUMN address VARCHAR(255) NULL AFTER phone,
+    ADD COLUMN preferred_language VARCHAR(16) NULL AFTER address;
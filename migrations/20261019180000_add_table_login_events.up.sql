@@ -0,0 +1,19 @@
+-- Add up migration script here
+
+CREATE TABLE login_events (
+    id CHAR(36) PRIMARY KEY NOT NULL,
+    user_id CHAR(36) NULL,
+    email VARCHAR(255) NOT NULL,
+    outcome ENUM('success', 'failure') NOT NULL,
+    reason VARCHAR(50) NULL,
+    ip_address VARCHAR(45) NOT NULL,
+    user_agent VARCHAR(512) NOT NULL,
+    device_name VARCHAR(100) NOT NULL,
+    fingerprint CHAR(64) NOT NULL,
+    new_device BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX login_events_user_id_created_at_idx ON login_events (user_id, created_at);
+CREATE INDEX login_events_user_id_fingerprint_idx ON login_events (user_id, fingerprint);
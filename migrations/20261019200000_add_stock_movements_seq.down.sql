@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+ALTER TABLE stock_movements
+    DROP COLUMN seq;
@@ -0,0 +1,17 @@
+-- Add up migration script here
+
+-- created_at only has second precision, seq keeps the order movements were
+-- recorded in. Existing rows of the same second replay ins before outs.
+ALTER TABLE stock_movements
+    ADD COLUMN seq BIGINT UNSIGNED NULL AFTER id;
+
+UPDATE stock_movements m
+INNER JOIN (
+    SELECT id, ROW_NUMBER() OVER (ORDER BY created_at, movement_type = 'out', id) AS seq
+    FROM stock_movements
+) ordered ON ordered.id = m.id
+SET m.seq = ordered.seq;
+
+ALTER TABLE stock_movements
+    MODIFY COLUMN seq BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+    ADD UNIQUE KEY stock_movements_seq_idx (seq);
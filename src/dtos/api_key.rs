@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::api_key::{ApiKeyModel, ApiKeyScope};
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ApiKeyDto {
+    pub id: String,
+    pub name: String,
+    /// Start of the key, e.g. `rfa_1a2b3c4d`, to recognise it by.
+    pub prefix: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ApiKeyDto {
+    pub fn filter(api_key: &ApiKeyModel) -> Self {
+        ApiKeyDto {
+            id: api_key.id.clone(),
+            name: api_key.name.clone(),
+            prefix: api_key.prefix.clone(),
+            scopes: api_key.scopes(),
+            expires_at: api_key.expires_at,
+            last_used_at: api_key.last_used_at,
+            created_at: api_key.created_at,
+        }
+    }
+
+    pub fn filter_iter(api_keys: &[ApiKeyModel]) -> Vec<ApiKeyDto> {
+        api_keys.iter().map(ApiKeyDto::filter).collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeysResponseDto {
+    pub status: String,
+    pub data: ApiKeysData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeysData {
+    pub api_keys: Vec<ApiKeyDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreatedApiKeyResponseDto {
+    pub status: String,
+    pub data: CreatedApiKeyData,
+}
+
+/// The plain `key` is only part of this response, it can not be read again.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreatedApiKeyData {
+    pub api_key: ApiKeyDto,
+    pub key: String,
+}
@@ -1,17 +1,29 @@
+use actix_web::http::header::EntityTag;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::models::barang::BarangModel;
+use crate::{
+    models::barang::{tags_from_json, BarangModel},
+    utils::money::Money,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct BarangDto {
     pub id: String,
+    pub organization_id: String,
+    pub store_id: Option<String>,
     pub name: String,
-    pub price: i32,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    #[schema(value_type = String, example = "11000.00")]
+    pub price: Money,
+    /// Price formatted for display, e.g. `Rp 11.000`.
+    pub price_display: String,
     pub stock: i32,
     // #[serde(rename = "expiredAt")]
     pub expired_at: Option<NaiveDate>,
+    pub version: i32,
     // #[serde(rename = "createdAt")]
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     // #[serde(rename = "updatedAt")]
@@ -22,10 +34,15 @@ impl Into<BarangModel> for BarangDto {
     fn into(self) -> BarangModel {
         BarangModel {
             id: self.id,
+            organization_id: self.organization_id,
+            store_id: self.store_id,
             name: self.name,
-            price: self.price,
+            category: self.category,
+            tags: Some(serde_json::json!(self.tags)),
+            price: self.price.amount(),
             stock: self.stock,
             expired_at: self.expired_at,
+            version: self.version,
             created_at: self.created_at,
             updated_at: self.updated_at,
         }
@@ -36,10 +53,16 @@ impl BarangDto {
     pub fn filter(barang: &BarangModel) -> Self {
         BarangDto {
             id: barang.id.clone(),
+            organization_id: barang.organization_id.clone(),
+            store_id: barang.store_id.clone(),
             name: barang.name.clone(),
-            price: barang.price.clone(),
+            category: barang.category.clone(),
+            tags: tags_from_json(barang.tags.as_ref()),
+            price: Money::from(barang.price),
+            price_display: Money::from(barang.price).to_rupiah(),
             stock: barang.stock.clone(),
             expired_at: barang.expired_at,
+            version: barang.version,
             created_at: barang.created_at,
             updated_at: barang.updated_at,
         }
@@ -48,6 +71,11 @@ impl BarangDto {
     pub fn filter_iter(barangs: &[BarangModel]) -> Vec<BarangDto> {
         barangs.iter().map(BarangDto::filter).collect()
     }
+
+    /// Strong entity tag for this revision, compared against `If-Match`.
+    pub fn etag(&self) -> EntityTag {
+        EntityTag::new_strong(self.version.to_string())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -71,3 +99,54 @@ pub struct BarangResponseDto {
 pub struct BarangData {
     pub barang: BarangDto,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncConflictStatus {
+    /// Changed on the server, `barang` holds the current copy.
+    Modified,
+    /// Deleted on the server, the app should drop its copy.
+    Deleted,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BarangConflictDto {
+    pub id: String,
+    pub status: SyncConflictStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BarangConflictsData {
+    /// Current server copies of the modified barang.
+    pub barang: Vec<BarangDto>,
+    /// Every update of the sync that was not saved.
+    pub conflicts: Vec<BarangConflictDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BarangConflictsResponseDto {
+    pub status: String,
+    pub message: String,
+    pub data: BarangConflictsData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkBarangChangeDto {
+    pub before: BarangDto,
+    /// `null` when the barang is deleted.
+    pub after: Option<BarangDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkBarangResponseDto {
+    pub status: String,
+    pub data: BulkBarangData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BulkBarangData {
+    /// `true` when nothing was saved.
+    pub preview: bool,
+    pub affected: usize,
+    pub barang: Vec<BulkBarangChangeDto>,
+}
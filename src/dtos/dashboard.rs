@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    models::{dashboard::ActivityModel, inventory::MovementType},
+    utils::money::Money,
+};
+
+/// Aggregates shown on the home screen, cached in Redis.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct DashboardSummaryDto {
+    pub item_count: i64,
+    #[schema(value_type = String)]
+    pub total_stock_value: Money,
+    pub total_stock_value_display: String,
+    pub low_stock_count: i64,
+    pub expiring_soon_count: i64,
+    pub today_sales_count: i64,
+    #[schema(value_type = String)]
+    pub today_sales: Money,
+    pub today_sales_display: String,
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ActivityDto {
+    pub id: String,
+    pub barang_id: String,
+    pub barang_name: String,
+    pub movement_type: MovementType,
+    pub quantity: i32,
+    #[schema(value_type = Option<String>)]
+    pub unit_cost: Option<Money>,
+    #[schema(value_type = Option<String>)]
+    pub unit_price: Option<Money>,
+    pub note: Option<String>,
+    pub user_name: Option<String>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ActivityDto {
+    pub fn filter(activity: &ActivityModel) -> Self {
+        ActivityDto {
+            id: activity.id.clone(),
+            barang_id: activity.barang_id.clone(),
+            barang_name: activity.barang_name.clone(),
+            movement_type: activity.movement_type,
+            quantity: activity.quantity,
+            unit_cost: activity.unit_cost.map(Money::from),
+            unit_price: activity.unit_price.map(Money::from),
+            note: activity.note.clone(),
+            user_name: activity.user_name.clone(),
+            created_at: activity.created_at,
+        }
+    }
+
+    pub fn filter_iter(activity: &[ActivityModel]) -> Vec<ActivityDto> {
+        activity.iter().map(ActivityDto::filter).collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DashboardResponseDto {
+    pub status: String,
+    pub data: DashboardData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DashboardData {
+    pub summary: DashboardSummaryDto,
+    pub recent_activity: Vec<ActivityDto>,
+}
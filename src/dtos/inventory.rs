@@ -0,0 +1,47 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{models::inventory::ValuationMethod, utils::money::Money};
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ItemValuationDto {
+    pub barang_id: String,
+    pub name: String,
+    pub quantity: i64,
+    /// Average cost of the units still on hand.
+    #[schema(value_type = String)]
+    pub unit_cost: Money,
+    #[schema(value_type = String)]
+    pub value: Money,
+    pub value_display: String,
+    #[schema(value_type = String)]
+    pub cogs: Money,
+    pub cogs_display: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct InventoryValuationDto {
+    pub method: ValuationMethod,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub items: Vec<ItemValuationDto>,
+    pub total_quantity: i64,
+    #[schema(value_type = String)]
+    pub total_value: Money,
+    pub total_value_display: String,
+    #[schema(value_type = String)]
+    pub total_cogs: Money,
+    pub total_cogs_display: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InventoryValuationResponseDto {
+    pub status: String,
+    pub data: InventoryValuationData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InventoryValuationData {
+    pub valuation: InventoryValuationDto,
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::login_event::{LoginEventModel, LoginOutcome};
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct LoginEventDto {
+    pub id: String,
+    pub outcome: LoginOutcome,
+    /// Why a failed attempt was refused, e.g. `wrong_password`.
+    pub reason: Option<String>,
+    pub ip_address: String,
+    pub user_agent: String,
+    pub device_name: String,
+    /// `true` when the account never logged in from this device before.
+    pub new_device: bool,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl LoginEventDto {
+    pub fn filter(login_event: &LoginEventModel) -> Self {
+        LoginEventDto {
+            id: login_event.id.clone(),
+            outcome: login_event.outcome,
+            reason: login_event.reason.clone(),
+            ip_address: login_event.ip_address.clone(),
+            user_agent: login_event.user_agent.clone(),
+            device_name: login_event.device_name.clone(),
+            new_device: login_event.is_new_device(),
+            created_at: login_event.created_at,
+        }
+    }
+
+    pub fn filter_iter(login_events: &[LoginEventModel]) -> Vec<LoginEventDto> {
+        login_events.iter().map(LoginEventDto::filter).collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LoginEventsResponseDto {
+    pub status: String,
+    pub data: LoginEventsData,
+}
+
+/// One page of login attempts, `total` counts them across all pages.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LoginEventsData {
+    pub login_events: Vec<LoginEventDto>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+}
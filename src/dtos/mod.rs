@@ -1,4 +1,12 @@
+pub mod api_key;
 pub mod barang;
+pub mod dashboard;
 pub mod global;
+pub mod inventory;
+pub mod login_event;
+pub mod organization;
+pub mod role;
+pub mod session;
 pub mod token;
+pub mod two_factor;
 pub mod user;
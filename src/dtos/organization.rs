@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::organization::{
+    OrganizationMemberModel, OrganizationRole, StoreModel, UserOrganizationModel,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct OrganizationDto {
+    pub id: String,
+    pub name: String,
+    pub role: OrganizationRole,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl OrganizationDto {
+    pub fn filter(organization: &UserOrganizationModel) -> Self {
+        OrganizationDto {
+            id: organization.id.clone(),
+            name: organization.name.clone(),
+            role: organization.role,
+            created_at: organization.created_at,
+            updated_at: organization.updated_at,
+        }
+    }
+
+    pub fn filter_iter(organizations: &[UserOrganizationModel]) -> Vec<OrganizationDto> {
+        organizations.iter().map(OrganizationDto::filter).collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct StoreDto {
+    pub id: String,
+    pub organization_id: String,
+    pub name: String,
+    pub address: Option<String>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl StoreDto {
+    pub fn filter(store: &StoreModel) -> Self {
+        StoreDto {
+            id: store.id.clone(),
+            organization_id: store.organization_id.clone(),
+            name: store.name.clone(),
+            address: store.address.clone(),
+            created_at: store.created_at,
+            updated_at: store.updated_at,
+        }
+    }
+
+    pub fn filter_iter(stores: &[StoreModel]) -> Vec<StoreDto> {
+        stores.iter().map(StoreDto::filter).collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct MemberDto {
+    pub user_id: String,
+    pub role: OrganizationRole,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl MemberDto {
+    pub fn filter(member: &OrganizationMemberModel) -> Self {
+        MemberDto {
+            user_id: member.user_id.clone(),
+            role: member.role,
+            created_at: member.created_at,
+        }
+    }
+
+    pub fn filter_iter(members: &[OrganizationMemberModel]) -> Vec<MemberDto> {
+        members.iter().map(MemberDto::filter).collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationsResponseDto {
+    pub status: String,
+    pub data: OrganizationsData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationsData {
+    pub organizations: Vec<OrganizationDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationResponseDto {
+    pub status: String,
+    pub data: OrganizationData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationData {
+    pub organization: OrganizationDto,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StoresResponseDto {
+    pub status: String,
+    pub data: StoresData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StoresData {
+    pub stores: Vec<StoreDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StoreResponseDto {
+    pub status: String,
+    pub data: StoreData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StoreData {
+    pub store: StoreDto,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MembersResponseDto {
+    pub status: String,
+    pub data: MembersData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MembersData {
+    pub members: Vec<MemberDto>,
+}
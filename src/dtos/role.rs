@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::permission::RoleModel;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RoleDto {
+    pub name: String,
+    pub description: Option<String>,
+    /// Built-in roles match `users.role` and can not be deleted.
+    pub built_in: bool,
+    pub permissions: Vec<String>,
+}
+
+impl RoleDto {
+    pub fn filter(role: &RoleModel, permissions: Vec<String>) -> Self {
+        RoleDto {
+            name: role.name.clone(),
+            description: role.description.clone(),
+            built_in: role.is_built_in(),
+            permissions,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RolesResponseDto {
+    pub status: String,
+    pub data: RolesData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RolesData {
+    pub roles: Vec<RoleDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RoleResponseDto {
+    pub status: String,
+    pub data: RoleData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RoleData {
+    pub role: RoleDto,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PermissionsResponseDto {
+    pub status: String,
+    pub data: PermissionsData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PermissionsData {
+    pub permissions: Vec<String>,
+}
+
+/// What a user may do and where it comes from.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserPermissionsDto {
+    pub role: String,
+    /// Roles granted on top of `role`.
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserPermissionsResponseDto {
+    pub status: String,
+    pub data: UserPermissionsDto,
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::session::SessionModel;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct SessionDto {
+    pub id: String,
+    pub device_name: String,
+    pub ip_address: String,
+    pub user_agent: String,
+    /// `true` for the session making the request.
+    pub current: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub last_used_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl SessionDto {
+    pub fn filter(session: &SessionModel, current_session_id: &str) -> Self {
+        SessionDto {
+            id: session.id.clone(),
+            device_name: session.device_name.clone(),
+            ip_address: session.ip_address.clone(),
+            user_agent: session.user_agent.clone(),
+            current: session.id == current_session_id,
+            created_at: session.created_at,
+            last_used_at: session.last_used_at,
+        }
+    }
+
+    pub fn filter_iter(sessions: &[SessionModel], current_session_id: &str) -> Vec<SessionDto> {
+        sessions
+            .iter()
+            .map(|session| SessionDto::filter(session, current_session_id))
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SessionsResponseDto {
+    pub status: String,
+    pub data: SessionsData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SessionsData {
+    pub sessions: Vec<SessionDto>,
+}
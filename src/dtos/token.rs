@@ -13,3 +13,33 @@ pub struct RefreshTokenResponseDto {
     pub status: String,
     pub data: TokenData,
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CsrfTokenData {
+    pub csrf_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CsrfTokenResponseDto {
+    pub status: String,
+    pub data: CsrfTokenData,
+}
+
+/// Public half of a signing key, see RFC 7517.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct JwkDto {
+    pub kty: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    pub alg: String,
+    pub kid: String,
+    /// Modulus, base64url without padding.
+    pub n: String,
+    /// Exponent, base64url without padding.
+    pub e: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct JwksDto {
+    pub keys: Vec<JwkDto>,
+}
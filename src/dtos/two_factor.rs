@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorStatusDto {
+    pub enabled: bool,
+    pub required: bool,
+    pub recovery_codes_left: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorStatusResponseDto {
+    pub status: String,
+    pub data: TwoFactorStatusDto,
+}
+
+/// Shown once while enrolling. Apps render `otpauth_url` as QR code, the
+/// secret is there for typing it in by hand.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorSetupDto {
+    pub secret: String,
+    pub otpauth_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorSetupResponseDto {
+    pub status: String,
+    pub data: TwoFactorSetupDto,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RecoveryCodesData {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RecoveryCodesResponseDto {
+    pub status: String,
+    pub data: RecoveryCodesData,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorChallengeData {
+    pub challenge: String,
+    pub expires_in: u64,
+}
+
+/// Login answer when the password was right but a second factor is needed.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorChallengeResponseDto {
+    pub status: String,
+    pub data: TwoFactorChallengeData,
+}
@@ -12,7 +12,13 @@ pub struct UserDto {
     pub email: String,
     pub role: UserRole,
     pub photo: String,
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    #[serde(rename = "preferredLanguage")]
+    pub preferred_language: Option<String>,
     pub verified: bool,
+    #[serde(rename = "disabledAt")]
+    pub disabled_at: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(rename = "createdAt")]
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(rename = "updatedAt")]
@@ -28,7 +34,11 @@ impl Into<UserModel> for UserDto {
             password: "".to_string(),
             role: self.role,
             photo: self.photo,
+            phone: self.phone,
+            address: self.address,
+            preferred_language: self.preferred_language,
             verified: if self.verified { 1 } else { 0 },
+            disabled_at: self.disabled_at,
             created_at: self.created_at,
             updated_at: self.updated_at,
         }
@@ -43,7 +53,11 @@ impl UserDto {
             email: user.email.clone(),
             role: user.role,
             photo: user.photo.clone(),
+            phone: user.phone.clone(),
+            address: user.address.clone(),
+            preferred_language: user.preferred_language.clone(),
             verified: user.verified != 0,
+            disabled_at: user.disabled_at,
             created_at: user.created_at,
             updated_at: user.updated_at,
         }
@@ -65,6 +79,21 @@ pub struct UserData {
     pub user: UserDto,
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UsersResponseDto {
+    pub status: String,
+    pub data: UsersData,
+}
+
+/// One page of users, `total` counts every match across all pages.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UsersData {
+    pub users: Vec<UserDto>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UserRegisterResponseDto {
     pub status: String,
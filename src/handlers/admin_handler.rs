@@ -0,0 +1,506 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde_json::json;
+use validator::Validate;
+
+use crate::{
+    dtos::{
+        global::Response,
+        login_event::LoginEventsResponseDto,
+        role::UserPermissionsResponseDto,
+        user::{UserData, UserDto, UserResponseDto, UsersData, UsersResponseDto},
+    },
+    handlers::{
+        login_event_handler::login_events_response,
+        role_handler::{permission_not_held, user_permissions_response},
+    },
+    models::{
+        permission,
+        user::{UserModel, UserRole},
+    },
+    schemas::{
+        admin::{
+            ListUsersSchema, SetUserDisabledSchema, SetUserVerifiedSchema, UpdateUserRoleSchema,
+        },
+        role::SetUserRolesSchema,
+        user::ListLoginEventsSchema,
+    },
+    services::{
+        login_guard_service::LoginGuardService,
+        password_reset_service::PasswordResetService,
+        permission_service::{PermissionService, RoleChange},
+        session_service,
+        user_services::UserService,
+    },
+    utils::{error::ErrorMessage, extractor::Authenticated},
+    AppState,
+};
+
+/// Load the user an admin endpoint works on, or the response to send back.
+async fn find_user(data: &AppState, user_id: &str) -> Result<UserModel, HttpResponse> {
+    match UserService::new(data.db.clone())
+        .get_user(Some(user_id), None, None)
+        .await
+    {
+        Ok(Some(user)) => Ok(user),
+        Ok(None) => Err(HttpResponse::NotFound().json(Response {
+            status: "fail",
+            message: "User not found".to_string(),
+        })),
+        Err(e) => Err(HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        }))),
+    }
+}
+
+/// Answer with the user as stored after a change.
+async fn updated_user_response(data: &AppState, user_id: &str) -> HttpResponse {
+    match find_user(data, user_id).await {
+        Ok(user) => HttpResponse::Ok().json(UserResponseDto {
+            status: "success".to_string(),
+            data: UserData {
+                user: UserDto::filter(&user),
+            },
+        }),
+        Err(response) => response,
+    }
+}
+
+/// Admins can not demote or disable themselves, so there is always one left.
+fn own_account(admin: &Authenticated, user_id: &str) -> Option<HttpResponse> {
+    (admin.id == user_id).then(|| {
+        HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message: "You can not change this on your own account".to_string(),
+        })
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/users",
+    tag = "Admin Endpoint",
+    params(ListUsersSchema),
+    responses(
+        (status=200, description= "One page of users, newest first", body= UsersResponseDto ),
+        (status=400, description= "Validation Errors", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_users_handler(
+    query: web::Query<ListUsersSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status": "fail",
+            "message": e,
+        }));
+    }
+
+    let page = query.page.unwrap_or(1);
+    let per_page = query.per_page.unwrap_or(20);
+
+    match UserService::new(data.db.clone())
+        .list_users(&query, page, per_page)
+        .await
+    {
+        Ok((users, total)) => HttpResponse::Ok().json(UsersResponseDto {
+            status: "success".to_string(),
+            data: UsersData {
+                users: UserDto::filter_iter(&users),
+                page,
+                per_page,
+                total,
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/users/{id}",
+    tag = "Admin Endpoint",
+    params(
+        ("id" = String, Path, description = "User id"),
+    ),
+    responses(
+        (status=200, description= "User detail", body= UserResponseDto ),
+        (status=404, description= "User not found", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_user_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    updated_user_response(&data, &path).await
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/admin/users/{id}/role",
+    tag = "Admin Endpoint",
+    params(
+        ("id" = String, Path, description = "User id"),
+    ),
+    request_body(content = UpdateUserRoleSchema, description = "New role of the user", example = json!({"role": "Moderator"})),
+    responses(
+        (status=200, description= "Role changed", body= UserResponseDto ),
+        (status=400, description= "Tried to change the own role", body= Response),
+        (status=403, description= "Only holders of every permission may grant or revoke the Admin role", body= Response),
+        (status=404, description= "User not found", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn update_user_role_handler(
+    admin: Authenticated,
+    path: web::Path<String>,
+    body: web::Json<UpdateUserRoleSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Some(response) = own_account(&admin, &path) {
+        return response;
+    }
+
+    let user = match find_user(&data, &path).await {
+        Ok(user) => user,
+        Err(response) => return response,
+    };
+
+    // Admin carries every permission, so only someone who already holds all
+    // of them may hand it out or take it away.
+    if body.role == UserRole::Admin || user.role == UserRole::Admin {
+        match PermissionService::new(data.db.clone())
+            .has_permission(&admin, permission::ALL)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                return HttpResponse::Forbidden().json(Response {
+                    status: "fail",
+                    message: ErrorMessage::PermissionDenied.to_string(),
+                })
+            }
+            Err(e) => {
+                return HttpResponse::InternalServerError().json(json!({
+                    "status": "error",
+                    "message": format!("{:?}", e)
+                }))
+            }
+        }
+    }
+
+    match UserService::new(data.db.clone())
+        .set_role(&user.id, body.role)
+        .await
+    {
+        Ok(()) => updated_user_response(&data, &user.id).await,
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/admin/users/{id}/verified",
+    tag = "Admin Endpoint",
+    params(
+        ("id" = String, Path, description = "User id"),
+    ),
+    request_body(content = SetUserVerifiedSchema, description = "Whether the email counts as verified", example = json!({"verified": true})),
+    responses(
+        (status=200, description= "Verification changed", body= UserResponseDto ),
+        (status=404, description= "User not found", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn set_user_verified_handler(
+    path: web::Path<String>,
+    body: web::Json<SetUserVerifiedSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user = match find_user(&data, &path).await {
+        Ok(user) => user,
+        Err(response) => return response,
+    };
+
+    match UserService::new(data.db.clone())
+        .set_verified(&user.id, body.verified)
+        .await
+    {
+        Ok(()) => updated_user_response(&data, &user.id).await,
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/admin/users/{id}/disabled",
+    tag = "Admin Endpoint",
+    params(
+        ("id" = String, Path, description = "User id"),
+    ),
+    request_body(content = SetUserDisabledSchema, description = "Disable or enable the account", example = json!({"disabled": true})),
+    responses(
+        (status=200, description= "Account disabled or enabled, disabling logs the user out everywhere", body= UserResponseDto ),
+        (status=400, description= "Tried to disable the own account", body= Response),
+        (status=404, description= "User not found", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn set_user_disabled_handler(
+    admin: Authenticated,
+    path: web::Path<String>,
+    body: web::Json<SetUserDisabledSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Some(response) = own_account(&admin, &path) {
+        return response;
+    }
+
+    let user = match find_user(&data, &path).await {
+        Ok(user) => user,
+        Err(response) => return response,
+    };
+
+    let result = async {
+        UserService::new(data.db.clone())
+            .set_disabled(&user.id, body.disabled)
+            .await?;
+
+        if body.disabled {
+            session_service::revoke_all_sessions(&data.redis, &user.id).await?;
+        }
+
+        Ok::<_, String>(())
+    }
+    .await;
+
+    match result {
+        Ok(()) => updated_user_response(&data, &user.id).await,
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/password-reset",
+    tag = "Admin Endpoint",
+    params(
+        ("id" = String, Path, description = "User id"),
+    ),
+    responses(
+        (status=200, description= "Password invalidated, the user is logged out and gets a reset link by email", body= Response ),
+        (status=404, description= "User not found", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn reset_user_password_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user = match find_user(&data, &path).await {
+        Ok(user) => user,
+        Err(response) => return response,
+    };
+
+    let result = async {
+        UserService::new(data.db.clone())
+            .scramble_password(&user.id, &data.config)
+            .await?;
+        session_service::revoke_all_sessions(&data.redis, &user.id).await?;
+
+        PasswordResetService::new(data.db.clone(), data.redis.clone(), data.mailer.clone())
+            .send_reset(&user, &data.config)
+            .await
+    }
+    .await;
+
+    match result {
+        Ok(()) => HttpResponse::Ok().json(Response {
+            status: "success",
+            message: "Password reset, a link to choose a new one was sent to the user".to_string(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/users/{id}/lockout",
+    tag = "Admin Endpoint",
+    params(
+        ("id" = String, Path, description = "User id"),
+    ),
+    responses(
+        (status=200, description= "Failed logins of the user forgotten, they can log in again", body= Response ),
+        (status=404, description= "User not found", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn unlock_user_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user = match find_user(&data, &path).await {
+        Ok(user) => user,
+        Err(response) => return response,
+    };
+
+    match LoginGuardService::new(data.redis.clone())
+        .unlock(&user.email)
+        .await
+    {
+        Ok(()) => HttpResponse::Ok().json(Response {
+            status: "success",
+            message: "User unlocked".to_string(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/users/{id}/permissions",
+    tag = "Admin Endpoint",
+    params(
+        ("id" = String, Path, description = "User id"),
+    ),
+    responses(
+        (status=200, description= "Roles and permissions of the user", body= UserPermissionsResponseDto ),
+        (status=404, description= "User not found", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_user_permissions_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    match find_user(&data, &path).await {
+        Ok(user) => user_permissions_response(&data, &user).await,
+        Err(response) => response,
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/admin/users/{id}/roles",
+    tag = "Admin Endpoint",
+    params(
+        ("id" = String, Path, description = "User id"),
+    ),
+    request_body(content = SetUserRolesSchema, description = "Roles granted on top of the base role", example = json!({"roles": ["stock-clerk"]})),
+    responses(
+        (status=200, description= "Roles changed", body= UserPermissionsResponseDto ),
+        (status=400, description= "Unknown role or the own account", body= Response),
+        (status=403, description= "A role grants a permission the caller does not hold", body= Response),
+        (status=404, description= "User not found", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn set_user_roles_handler(
+    admin: Authenticated,
+    path: web::Path<String>,
+    body: web::Json<SetUserRolesSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Some(response) = own_account(&admin, &path) {
+        return response;
+    }
+
+    let user = match find_user(&data, &path).await {
+        Ok(user) => user,
+        Err(response) => return response,
+    };
+
+    match PermissionService::new(data.db.clone())
+        .set_user_roles(&admin, &user.id, &body.roles)
+        .await
+    {
+        Ok(RoleChange::NotHeld(permission)) => permission_not_held(&permission),
+        Ok(RoleChange::UnknownRole(role)) => HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message: format!("Role {} does not exist", role),
+        }),
+        Ok(_) => user_permissions_response(&data, &user).await,
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/users/{id}/logins",
+    tag = "Admin Endpoint",
+    params(
+        ("id" = String, Path, description = "User id"),
+        ListLoginEventsSchema,
+    ),
+    responses(
+        (status=200, description= "Successful and failed logins of the user, newest first", body= LoginEventsResponseDto ),
+        (status=400, description= "Validation Errors", body= Response),
+        (status=404, description= "User not found", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_user_logins_handler(
+    path: web::Path<String>,
+    query: web::Query<ListLoginEventsSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    match find_user(&data, &path).await {
+        Ok(user) => login_events_response(&data, &user.id, &query).await,
+        Err(response) => response,
+    }
+}
@@ -0,0 +1,133 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde_json::json;
+use validator::Validate;
+
+use crate::{
+    dtos::{
+        api_key::{
+            ApiKeyDto, ApiKeysData, ApiKeysResponseDto, CreatedApiKeyData, CreatedApiKeyResponseDto,
+        },
+        global::Response,
+    },
+    schemas::api_key::CreateApiKeySchema,
+    services::api_key_service::ApiKeyService,
+    utils::extractor::Authenticated,
+    AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/users/me/api-keys",
+    tag = "Users Endpoint",
+    responses(
+        (status=200, description= "API keys of the account", body= ApiKeysResponseDto ),
+        (status=403, description= "Called with an API key", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_api_keys_handler(
+    user: Authenticated,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    match ApiKeyService::new(data.db.clone()).list(&user.id).await {
+        Ok(api_keys) => HttpResponse::Ok().json(ApiKeysResponseDto {
+            status: "success".to_string(),
+            data: ApiKeysData {
+                api_keys: ApiKeyDto::filter_iter(&api_keys),
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/users/me/api-keys",
+    tag = "Users Endpoint",
+    request_body(content = CreateApiKeySchema, description = "Name, scopes and optional expiry of the key", example = json!({"name": "Nightly stock import","scopes": ["read", "write"],"expires_in_days": 90})),
+    responses(
+        (status=201, description= "API key created, the key itself is only shown in this response", body= CreatedApiKeyResponseDto ),
+        (status=400, description= "Validation Errors", body= Response),
+        (status=403, description= "Called with an API key", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn create_api_key_handler(
+    user: Authenticated,
+    body: web::Json<CreateApiKeySchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    match ApiKeyService::new(data.db.clone())
+        .create(&user.id, &body)
+        .await
+    {
+        Ok((api_key, key)) => HttpResponse::Created().json(CreatedApiKeyResponseDto {
+            status: "success".to_string(),
+            data: CreatedApiKeyData {
+                api_key: ApiKeyDto::filter(&api_key),
+                key,
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/users/me/api-keys/{id}",
+    tag = "Users Endpoint",
+    params(
+        ("id" = String, Path, description = "API key id"),
+    ),
+    responses(
+        (status=200, description= "API key revoked", body= Response ),
+        (status=403, description= "Called with an API key", body= Response ),
+        (status=404, description= "API key not found", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn revoke_api_key_handler(
+    user: Authenticated,
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    match ApiKeyService::new(data.db.clone())
+        .revoke(&user.id, &path)
+        .await
+    {
+        Ok(true) => HttpResponse::Ok().json(Response {
+            status: "success",
+            message: "API key revoked".to_string(),
+        }),
+        Ok(false) => HttpResponse::NotFound().json(Response {
+            status: "fail",
+            message: "API key not found".to_string(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
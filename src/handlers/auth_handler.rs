@@ -1,6 +1,7 @@
 use actix_web::{
     cookie::{time::Duration as ActixWebDuration, Cookie},
-    web, HttpRequest, HttpResponse, Responder,
+    http::header::{self, ContentType},
+    web, Either, HttpRequest, HttpResponse, HttpResponseBuilder, Responder,
 };
 use serde_json::json;
 use validator::Validate;
@@ -8,39 +9,245 @@ use validator::Validate;
 use crate::{
     dtos::{
         global::Response,
-        token::{RefreshTokenResponseDto, TokenData},
+        token::{CsrfTokenData, CsrfTokenResponseDto, TokenData},
+        two_factor::{TwoFactorChallengeData, TwoFactorChallengeResponseDto},
         user::UserLoginResponseDto,
     },
-    schemas::auth::{LoginUserSchema, RefreshTokenSchema, RegisterUserSchema},
-    services::{auth_service::AuthService, user_services::UserService},
-    utils::{extractor::Authenticated, password, token},
+    models::{
+        login_event::{LoginFailureReason, LoginOutcome},
+        token::TokenDetails,
+    },
+    schemas::auth::{
+        ForgotPasswordSchema, LoginUserSchema, RefreshTokenSchema, RegisterUserSchema,
+        ResendVerificationSchema, ResetPasswordSchema, TwoFactorLoginSchema, VerifyEmailSchema,
+    },
+    services::{
+        auth_service::AuthService,
+        login_guard_service::LoginGuardService,
+        login_history_service::LoginHistoryService,
+        password_reset_service::PasswordResetService,
+        session_service::{self, RefreshClaim, SessionMeta},
+        two_factor_service::{ChallengeOutcome, TwoFactorService},
+        user_services::UserService,
+        verification_service::{ResendOutcome, VerificationService},
+    },
+    utils::{csrf, extractor::Authenticated, password, token},
     AppState,
 };
-use redis::AsyncCommands;
+use redis::{aio::ConnectionManager, AsyncCommands};
+
+/// Access and refresh token of a freshly started session.
+pub struct SessionTokens {
+    pub access: TokenDetails,
+    pub refresh: TokenDetails,
+}
+
+/// Open a session for `user_id` on the device described by `meta` and issue
+/// its access and refresh token. Every way of signing in goes through here.
+pub async fn start_session(
+    data: &AppState,
+    user_id: &str,
+    meta: &SessionMeta,
+) -> Result<SessionTokens, HttpResponse> {
+    let mut redis = data.redis.clone();
+
+    let session_id = match session_service::create_session(
+        &mut redis,
+        user_id,
+        meta,
+        (data.config.refresh_token_max_age * 60) as u64,
+    )
+    .await
+    {
+        Ok(session_id) => session_id,
+        Err(e) => {
+            return Err(HttpResponse::UnprocessableEntity()
+                .json(serde_json::json!({"status": "error", "message": format_args!("{}", e)})));
+        }
+    };
+
+    issue_session_tokens(data, &mut redis, user_id, &session_id).await
+}
+
+/// Issue a new access and refresh token within an existing session. The new
+/// refresh token becomes the only one of the session that can be redeemed.
+async fn issue_session_tokens(
+    data: &AppState,
+    redis: &mut ConnectionManager,
+    user_id: &str,
+    session_id: &str,
+) -> Result<SessionTokens, HttpResponse> {
+    let session_max_age = (data.config.refresh_token_max_age * 60) as u64;
+
+    let access_token_details = match token::generate_jwt_token(
+        user_id.to_string(),
+        session_id.to_string(),
+        data.config.access_token_max_age,
+        data.config.access_token_private_key.to_owned(),
+        data.config.access_token_public_key.to_owned(),
+    ) {
+        Ok(token_details) => token_details,
+        Err(e) => {
+            return Err(HttpResponse::BadGateway()
+                .json(serde_json::json!({"status": "fail", "message": format_args!("{}", e)})));
+        }
+    };
+
+    let refresh_token_details = match token::generate_jwt_token(
+        user_id.to_string(),
+        session_id.to_string(),
+        data.config.refresh_token_max_age,
+        data.config.refresh_token_private_key.to_owned(),
+        data.config.refresh_token_public_key.to_owned(),
+    ) {
+        Ok(token_details) => token_details,
+        Err(e) => {
+            return Err(HttpResponse::BadGateway()
+                .json(serde_json::json!({"status": "fail", "message": format_args!("{}", e)})));
+        }
+    };
+
+    let result: redis::RedisResult<()> = redis::pipe()
+        .set_ex(
+            access_token_details.token_uuid.to_string(),
+            user_id,
+            (data.config.access_token_max_age * 60) as u64,
+        )
+        .ignore()
+        .set_ex(
+            refresh_token_details.token_uuid.to_string(),
+            user_id,
+            session_max_age,
+        )
+        .ignore()
+        .query_async(&mut *redis)
+        .await;
+
+    if let Err(e) = result {
+        return Err(HttpResponse::UnprocessableEntity()
+            .json(serde_json::json!({"status": "error", "message": format_args!("{}", e)})));
+    }
+
+    let result = session_service::set_refresh_token(
+        redis,
+        session_id,
+        &refresh_token_details.token_uuid.to_string(),
+    )
+    .await;
+
+    if let Err(e) = result {
+        return Err(HttpResponse::UnprocessableEntity()
+            .json(serde_json::json!({"status": "error", "message": format_args!("{}", e)})));
+    }
+
+    if let Err(e) = session_service::track_tokens(
+        redis,
+        user_id,
+        session_id,
+        &[
+            access_token_details.token_uuid.to_string(),
+            refresh_token_details.token_uuid.to_string(),
+        ],
+        session_max_age,
+    )
+    .await
+    {
+        return Err(HttpResponse::UnprocessableEntity()
+            .json(serde_json::json!({"status": "error", "message": format_args!("{}", e)})));
+    }
+
+    Ok(SessionTokens {
+        access: access_token_details,
+        refresh: refresh_token_details,
+    })
+}
+
+/// Answer a successful sign in with the token cookies and body.
+pub fn session_tokens_response(
+    mut response: HttpResponseBuilder,
+    data: &AppState,
+    tokens: SessionTokens,
+) -> HttpResponse {
+    let access_cookie = Cookie::build("access_token", tokens.access.token.clone().unwrap())
+        .path("/")
+        .max_age(ActixWebDuration::new(
+            data.config.access_token_max_age * 60,
+            0,
+        ))
+        .http_only(true)
+        .finish();
+    let refresh_cookie = Cookie::build("refresh_token", tokens.refresh.token.clone().unwrap())
+        .path("/")
+        .max_age(ActixWebDuration::new(
+            data.config.refresh_token_max_age * 60,
+            0,
+        ))
+        .http_only(true)
+        .finish();
+    let logged_in_cookie = Cookie::build("logged_in", "true")
+        .path("/")
+        .max_age(ActixWebDuration::new(
+            data.config.access_token_max_age * 60,
+            0,
+        ))
+        .http_only(false)
+        .finish();
+
+    let token_response = UserLoginResponseDto {
+        status: "success".to_string(),
+        data: TokenData {
+            access_token: tokens.access.token.unwrap(),
+            refresh_token: tokens.refresh.token,
+            refresh_token_expired: tokens.refresh.expires_in,
+        },
+    };
+
+    // Every new token pair comes with a new CSRF token for cookie requests.
+    let csrf_token = csrf::generate_token();
+
+    response
+        .cookie(access_cookie)
+        .cookie(refresh_cookie)
+        .cookie(logged_in_cookie)
+        .cookie(csrf::csrf_cookie(&csrf_token, &data.config))
+        .insert_header((csrf::CSRF_HEADER, csrf_token))
+        .json(json!(token_response))
+}
 
 #[utoipa::path(
     post,
     path = "/auth/register",
     tag = "Authentication Endpoint",
     request_body(content = RegisterUserSchema, description = "Credentials to create account", example = json!({"email": "user1@mail.com","name": "User Name","password": "user1","passwordConfirm": "user1"})),
+    params(
+        ("X-Device-Name" = Option<String>, Header, description = "Name of the device shown in the session list"),
+    ),
     responses(
-        (status=201, description= "Account created successfully", body= UserRegisterResponseDto ),
+        (status=201, description= "Account created successfully. The body holds the tokens, or only a message when the email has to be verified before logging in", body= UserRegisterResponseDto ),
         (status=400, description= "Validation Errors", body= Response),
         (status=409, description= "User with email already exists", body= Response),
         (status=500, description= "Internal Server Error", body= Response ),
     )
 )]
 pub async fn register_user_handler(
+    req: HttpRequest,
     body: web::Json<RegisterUserSchema>,
     data: web::Data<AppState>,
 ) -> impl Responder {
     match body.validate() {
         Ok(()) => {
+            if let Err(message) = password::check_policy(&body.password, &data.config) {
+                return HttpResponse::BadRequest().json(Response {
+                    status: "fail",
+                    message,
+                });
+            }
+
             let auth_service = AuthService::new(data.db.clone());
 
             let user_id = uuid::Uuid::new_v4().to_string();
 
-            if let Err(err) = auth_service.create_user(&user_id, body).await {
+            if let Err(err) = auth_service.create_user(&user_id, body, &data.config).await {
                 if err.contains("Duplicate entry") {
                     return HttpResponse::BadRequest().json(json!({
                         "status": "fail",
@@ -54,112 +261,36 @@ pub async fn register_user_handler(
                 }));
             }
 
-            let access_token_details = match token::generate_jwt_token(
-                user_id.clone(),
-                data.config.access_token_max_age,
-                data.config.access_token_private_key.to_owned(),
-            ) {
-                Ok(token_details) => token_details,
-                Err(e) => {
-                    return HttpResponse::BadGateway().json(
-                        serde_json::json!({"status": "fail", "message": format_args!("{}", e)}),
-                    );
-                }
-            };
-
-            let refresh_token_details = match token::generate_jwt_token(
-                user_id.clone(),
-                data.config.refresh_token_max_age,
-                data.config.refresh_token_private_key.to_owned(),
-            ) {
-                Ok(token_details) => token_details,
-                Err(e) => {
-                    return HttpResponse::BadGateway().json(
-                        serde_json::json!({"status": "fail", "message": format_args!("{}", e)}),
-                    );
-                }
-            };
+            // The account exists at this point, a failed mail can be resent later.
+            let user_service = UserService::new(data.db.clone());
+            if let Ok(Some(user)) = user_service.get_user(Some(&user_id), None, None).await {
+                let verification_service = VerificationService::new(
+                    data.db.clone(),
+                    data.redis.clone(),
+                    data.mailer.clone(),
+                );
 
-            let mut redis_client = match data.redis_client.get_async_connection().await {
-                Ok(redis_client) => redis_client,
-                Err(e) => {
-                    return HttpResponse::InternalServerError().json(
-                        serde_json::json!({"status": "fail", "message": format_args!("{}", e)}),
-                    );
+                if let Err(e) = verification_service
+                    .send_verification(&user, &data.config)
+                    .await
+                {
+                    eprintln!("🔥 Failed to send verification email: {}", e);
                 }
-            };
-
-            let access_result: redis::RedisResult<()> = redis_client
-                .set_ex(
-                    access_token_details.token_uuid.to_string(),
-                    user_id.to_string(),
-                    (data.config.access_token_max_age * 60) as u64,
-                )
-                .await;
-
-            if let Err(e) = access_result {
-                return HttpResponse::UnprocessableEntity().json(
-                    serde_json::json!({"status": "error", "message": format_args!("{}", e)}),
-                );
             }
 
-            let refresh_result: redis::RedisResult<()> = redis_client
-                .set_ex(
-                    refresh_token_details.token_uuid.to_string(),
-                    user_id.clone().to_string(),
-                    (data.config.refresh_token_max_age * 60) as u64,
-                )
-                .await;
-
-            if let Err(e) = refresh_result {
-                return HttpResponse::UnprocessableEntity().json(
-                    serde_json::json!({"status": "error", "message": format_args!("{}", e)}),
-                );
+            // Logging in is blocked until the email is verified, so no session either.
+            if data.config.require_verified_email {
+                return HttpResponse::Created().json(Response {
+                    status: "success",
+                    message: "Account created, please verify your email before logging in"
+                        .to_string(),
+                });
             }
 
-            let access_cookie =
-                Cookie::build("access_token", access_token_details.token.clone().unwrap())
-                    .path("/")
-                    .max_age(ActixWebDuration::new(
-                        data.config.access_token_max_age * 60,
-                        0,
-                    ))
-                    .http_only(true)
-                    .finish();
-            let refresh_cookie = Cookie::build(
-                "refresh_token",
-                refresh_token_details.token.clone().unwrap(),
-            )
-            .path("/")
-            .max_age(ActixWebDuration::new(
-                data.config.refresh_token_max_age * 60,
-                0,
-            ))
-            .http_only(true)
-            .finish();
-            let logged_in_cookie = Cookie::build("logged_in", "true")
-                .path("/")
-                .max_age(ActixWebDuration::new(
-                    data.config.access_token_max_age * 60,
-                    0,
-                ))
-                .http_only(false)
-                .finish();
-
-            let token_response = UserLoginResponseDto {
-                status: "success".to_string(),
-                data: TokenData {
-                    access_token: access_token_details.token.unwrap(),
-                    refresh_token: refresh_token_details.token,
-                    refresh_token_expired: refresh_token_details.expires_in,
-                },
-            };
-
-            return HttpResponse::Created()
-                .cookie(access_cookie)
-                .cookie(refresh_cookie)
-                .cookie(logged_in_cookie)
-                .json(json!(token_response));
+            match start_session(&data, &user_id, &SessionMeta::from_request(&req)).await {
+                Ok(tokens) => session_tokens_response(HttpResponse::Created(), &data, tokens),
+                Err(response) => response,
+            }
         }
         Err(e) => HttpResponse::BadRequest().json(json!({
             "status":"fail",
@@ -168,24 +299,130 @@ pub async fn register_user_handler(
     }
 }
 
+/// Count a failed login. Errors are only logged, the caller answers with
+/// the wrong credentials response either way.
+async fn record_login_failure(
+    login_guard: &LoginGuardService,
+    email: &str,
+    meta: &SessionMeta,
+    data: &AppState,
+) {
+    if let Err(e) = login_guard
+        .record_failure(email, &meta.ip_address, &data.config)
+        .await
+    {
+        eprintln!("🔥 Failed to record login attempt: {}", e);
+    }
+}
+
+/// Add an attempt to the login history. Errors are only logged, the login
+/// itself goes on regardless.
+pub(crate) async fn record_login_event(
+    data: &AppState,
+    email: &str,
+    user_id: Option<&str>,
+    meta: &SessionMeta,
+    reason: Option<LoginFailureReason>,
+) {
+    let outcome = match reason {
+        Some(_) => LoginOutcome::Failure,
+        None => LoginOutcome::Success,
+    };
+
+    if let Err(e) = LoginHistoryService::new(data.db.clone())
+        .record(email, user_id, meta, outcome, reason)
+        .await
+    {
+        eprintln!("🔥 Failed to record login event: {}", e);
+    }
+}
+
+/// Hold back the tokens of `user_id` until the second factor is answered at
+/// `/auth/login/2fa`.
+pub(crate) async fn two_factor_challenge_response(
+    two_factor_service: &TwoFactorService,
+    user_id: &str,
+    data: &AppState,
+) -> HttpResponse {
+    match two_factor_service
+        .create_challenge(user_id, &data.config)
+        .await
+    {
+        Ok(challenge) => HttpResponse::Accepted().json(TwoFactorChallengeResponseDto {
+            status: "two_factor_required".to_string(),
+            data: TwoFactorChallengeData {
+                challenge,
+                expires_in: data.config.two_factor_challenge_max_age,
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": e,
+        })),
+    }
+}
+
+fn too_many_login_attempts(retry_after: u64) -> HttpResponse {
+    HttpResponse::TooManyRequests()
+        .insert_header((header::RETRY_AFTER, retry_after.to_string()))
+        .json(Response {
+            status: "fail",
+            message: format!(
+                "Too many failed logins, try again in {} seconds",
+                retry_after
+            ),
+        })
+}
+
 #[utoipa::path(
     post,
     path = "/auth/login",
     tag = "Authentication Endpoint",
     request_body(content = LoginUserSchema, description = "Credentials to login", example = json!({"email": "user1@mail.com","password": "user1"})),
+    params(
+        ("X-Device-Name" = Option<String>, Header, description = "Name of the device shown in the session list"),
+    ),
     responses(
         (status=201, description= "Login successfully", body= UserLoginResponseDto ),
+        (status=202, description= "Password is right, answer the challenge at /auth/login/2fa to get the tokens", body= TwoFactorChallengeResponseDto ),
         (status=400, description= "Validation Errors", body= Response),
         (status=500, description= "User not found!", body= Response),
         (status=401, description= "Email or password is wrong", body= Response),
+        (status=403, description= "Email not verified yet or account disabled", body= Response),
+        (status=429, description= "Too many failed logins, the Retry-After header holds the seconds to wait", body= Response),
     )
 )]
 pub async fn login_user_handler(
+    req: HttpRequest,
     data: web::Data<AppState>,
     body: web::Json<LoginUserSchema>,
 ) -> impl Responder {
     match body.validate() {
         Ok(()) => {
+            let meta = SessionMeta::from_request(&req);
+            let login_guard = LoginGuardService::new(data.redis.clone());
+
+            match login_guard.retry_after(&body.email, &meta.ip_address).await {
+                Ok(Some(retry_after)) => {
+                    record_login_event(
+                        &data,
+                        &body.email,
+                        None,
+                        &meta,
+                        Some(LoginFailureReason::RateLimited),
+                    )
+                    .await;
+                    return too_many_login_attempts(retry_after);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    return HttpResponse::InternalServerError().json(json!({
+                        "status":"error",
+                        "message": e,
+                    }))
+                }
+            }
+
             let user_service = UserService::new(data.db.clone());
 
             match user_service.get_user(None, None, Some(&body.email)).await {
@@ -193,127 +430,128 @@ pub async fn login_user_handler(
                     let user = match result {
                         Some(user) => user,
                         None => {
+                            record_login_failure(&login_guard, &body.email, &meta, &data).await;
+                            record_login_event(
+                                &data,
+                                &body.email,
+                                None,
+                                &meta,
+                                Some(LoginFailureReason::UnknownEmail),
+                            )
+                            .await;
                             return HttpResponse::InternalServerError().json(json!({
                                 "status":"fail",
                                 "message":"User not found!",
-                            }))
+                            }));
                         }
                     };
 
-                    let password_matches = password::compare(&body.password, &user.password)
-                        .map_err(|_| {
-                            HttpResponse::Unauthorized().json(json!({
+                    // Passwords the hasher refuses, e.g. too long, can not match.
+                    let password_matches = match password::compare(&body.password, &user.password) {
+                        Ok(password_matches) => password_matches,
+                        Err(_) => {
+                            record_login_failure(&login_guard, &body.email, &meta, &data).await;
+                            record_login_event(
+                                &data,
+                                &body.email,
+                                Some(&user.id),
+                                &meta,
+                                Some(LoginFailureReason::WrongPassword),
+                            )
+                            .await;
+                            return HttpResponse::Unauthorized().json(json!({
                                 "status":"fail",
                                 "message":"Email or password is wrong",
-                            }))
-                        })
-                        .unwrap();
+                            }));
+                        }
+                    };
 
-                    if password_matches {
-                        let access_token_details = match token::generate_jwt_token(
-                            user.id.clone(),
-                            data.config.access_token_max_age,
-                            data.config.access_token_private_key.to_owned(),
-                        ) {
-                            Ok(token_details) => token_details,
-                            Err(e) => {
-                                return HttpResponse::BadGateway()
-                                    .json(serde_json::json!({"status": "fail", "message": format_args!("{}", e)}));
-                            }
-                        };
-
-                        let refresh_token_details = match token::generate_jwt_token(
-                            user.id.clone(),
-                            data.config.refresh_token_max_age,
-                            data.config.refresh_token_private_key.to_owned(),
-                        ) {
-                            Ok(token_details) => token_details,
-                            Err(e) => {
-                                return HttpResponse::BadGateway()
-                                    .json(serde_json::json!({"status": "fail", "message": format_args!("{}", e)}));
-                            }
-                        };
+                    if !password_matches {
+                        record_login_failure(&login_guard, &body.email, &meta, &data).await;
+                        record_login_event(
+                            &data,
+                            &body.email,
+                            Some(&user.id),
+                            &meta,
+                            Some(LoginFailureReason::WrongPassword),
+                        )
+                        .await;
+                    }
 
-                        let mut redis_client = match data.redis_client.get_async_connection().await
-                        {
-                            Ok(redis_client) => redis_client,
-                            Err(e) => {
-                                return HttpResponse::InternalServerError()
-                                    .json(serde_json::json!({"status": "fail", "message": format_args!("{}", e)}));
-                            }
-                        };
+                    if password_matches && user.is_disabled() {
+                        record_login_event(
+                            &data,
+                            &body.email,
+                            Some(&user.id),
+                            &meta,
+                            Some(LoginFailureReason::AccountDisabled),
+                        )
+                        .await;
+                        return HttpResponse::Forbidden().json(json!({
+                            "status": "fail",
+                            "message": "This account has been disabled",
+                        }));
+                    }
 
-                        let access_result: redis::RedisResult<()> = redis_client
-                            .set_ex(
-                                access_token_details.token_uuid.to_string(),
-                                user.id.to_string(),
-                                (data.config.access_token_max_age * 60) as u64,
-                            )
-                            .await;
+                    if password_matches && data.config.require_verified_email && user.verified == 0
+                    {
+                        record_login_event(
+                            &data,
+                            &body.email,
+                            Some(&user.id),
+                            &meta,
+                            Some(LoginFailureReason::EmailNotVerified),
+                        )
+                        .await;
+                        return HttpResponse::Forbidden().json(json!({
+                            "status": "fail",
+                            "message": "Please verify your email before logging in",
+                        }));
+                    }
 
-                        if let Err(e) = access_result {
-                            return HttpResponse::UnprocessableEntity()
-                                .json(serde_json::json!({"status": "error", "message": format_args!("{}", e)}));
+                    if password_matches {
+                        // The plain password is at hand only now, move it to the current settings.
+                        if let Err(e) = user_service
+                            .rehash_password_if_needed(&user, &body.password, &data.config)
+                            .await
+                        {
+                            eprintln!("🔥 Failed to rehash password: {}", e);
                         }
 
-                        let refresh_result: redis::RedisResult<()> = redis_client
-                            .set_ex(
-                                refresh_token_details.token_uuid.to_string(),
-                                user.id.clone().to_string(),
-                                (data.config.refresh_token_max_age * 60) as u64,
-                            )
-                            .await;
+                        let two_factor_service =
+                            TwoFactorService::new(data.db.clone(), data.redis.clone());
 
-                        if let Err(e) = refresh_result {
-                            return HttpResponse::UnprocessableEntity()
-                                .json(serde_json::json!({"status": "error", "message": format_args!("{}", e)}));
+                        // Failures are only forgotten once the second factor passed too.
+                        match two_factor_service.is_enabled(&user.id).await {
+                            Ok(true) => {
+                                return two_factor_challenge_response(
+                                    &two_factor_service,
+                                    &user.id,
+                                    &data,
+                                )
+                                .await;
+                            }
+                            Ok(false) => {}
+                            Err(e) => {
+                                return HttpResponse::InternalServerError().json(json!({
+                                    "status": "error",
+                                    "message": e,
+                                }))
+                            }
                         }
 
-                        let access_cookie = Cookie::build(
-                            "access_token",
-                            access_token_details.token.clone().unwrap(),
-                        )
-                        .path("/")
-                        .max_age(ActixWebDuration::new(
-                            data.config.access_token_max_age * 60,
-                            0,
-                        ))
-                        .http_only(true)
-                        .finish();
-                        let refresh_cookie = Cookie::build(
-                            "refresh_token",
-                            refresh_token_details.token.clone().unwrap(),
-                        )
-                        .path("/")
-                        .max_age(ActixWebDuration::new(
-                            data.config.refresh_token_max_age * 60,
-                            0,
-                        ))
-                        .http_only(true)
-                        .finish();
-                        let logged_in_cookie = Cookie::build("logged_in", "true")
-                            .path("/")
-                            .max_age(ActixWebDuration::new(
-                                data.config.access_token_max_age * 60,
-                                0,
-                            ))
-                            .http_only(false)
-                            .finish();
-
-                        let token_response = UserLoginResponseDto {
-                            status: "success".to_string(),
-                            data: TokenData {
-                                access_token: access_token_details.token.unwrap(),
-                                refresh_token: refresh_token_details.token,
-                                refresh_token_expired: refresh_token_details.expires_in,
-                            },
-                        };
-
-                        return HttpResponse::Created()
-                            .cookie(access_cookie)
-                            .cookie(refresh_cookie)
-                            .cookie(logged_in_cookie)
-                            .json(json!(token_response));
+                        if let Err(e) = login_guard.record_success(&body.email).await {
+                            eprintln!("🔥 Failed to reset login attempts: {}", e);
+                        }
+
+                        match start_session(&data, &user.id, &meta).await {
+                            Ok(tokens) => {
+                                record_login_event(&data, &body.email, Some(&user.id), &meta, None)
+                                    .await;
+                                session_tokens_response(HttpResponse::Created(), &data, tokens)
+                            }
+                            Err(response) => response,
+                        }
                     } else {
                         return HttpResponse::InternalServerError().json(json!({
                             "status": "error",
@@ -336,56 +574,157 @@ pub async fn login_user_handler(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/auth/login/2fa",
+    tag = "Authentication Endpoint",
+    request_body(content = TwoFactorLoginSchema, description = "Challenge from /auth/login with a TOTP or recovery code", example = json!({"challenge": "0f8e6a1c2b3d4e5f60718293a4b5c6d7","code": "123456"})),
+    params(
+        ("X-Device-Name" = Option<String>, Header, description = "Name of the device shown in the session list"),
+    ),
+    responses(
+        (status=201, description= "Login successfully", body= UserLoginResponseDto ),
+        (status=400, description= "Validation Errors", body= Response),
+        (status=401, description= "Code is wrong, or the challenge expired and the login has to start over", body= Response),
+        (status=429, description= "Too many failed logins, the Retry-After header holds the seconds to wait", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    )
+)]
+pub async fn login_two_factor_handler(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    body: web::Json<TwoFactorLoginSchema>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    let meta = SessionMeta::from_request(&req);
+    let login_guard = LoginGuardService::new(data.redis.clone());
+    let two_factor_service = TwoFactorService::new(data.db.clone(), data.redis.clone());
+
+    let user = match two_factor_service.challenge_user(&body.challenge).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return HttpResponse::Unauthorized().json(Response {
+                status: "fail",
+                message: "Login challenge expired, please log in again".to_string(),
+            })
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": e,
+            }))
+        }
+    };
+
+    match login_guard.retry_after(&user.email, &meta.ip_address).await {
+        Ok(Some(retry_after)) => {
+            record_login_event(
+                &data,
+                &user.email,
+                Some(&user.id),
+                &meta,
+                Some(LoginFailureReason::RateLimited),
+            )
+            .await;
+            return too_many_login_attempts(retry_after);
+        }
+        Ok(None) => {}
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": e,
+            }))
+        }
+    }
+
+    match two_factor_service
+        .answer_challenge(&body.challenge, &user, &body.code, &data.config)
+        .await
+    {
+        Ok(ChallengeOutcome::Passed) => {
+            if let Err(e) = login_guard.record_success(&user.email).await {
+                eprintln!("🔥 Failed to reset login attempts: {}", e);
+            }
+
+            match start_session(&data, &user.id, &meta).await {
+                Ok(tokens) => {
+                    record_login_event(&data, &user.email, Some(&user.id), &meta, None).await;
+                    session_tokens_response(HttpResponse::Created(), &data, tokens)
+                }
+                Err(response) => response,
+            }
+        }
+        Ok(ChallengeOutcome::Failed) => {
+            record_login_failure(&login_guard, &user.email, &meta, &data).await;
+            record_login_event(
+                &data,
+                &user.email,
+                Some(&user.id),
+                &meta,
+                Some(LoginFailureReason::WrongTwoFactorCode),
+            )
+            .await;
+            HttpResponse::Unauthorized().json(Response {
+                status: "fail",
+                message: "Code is wrong".to_string(),
+            })
+        }
+        Ok(ChallengeOutcome::Expired) => {
+            record_login_failure(&login_guard, &user.email, &meta, &data).await;
+            record_login_event(
+                &data,
+                &user.email,
+                Some(&user.id),
+                &meta,
+                Some(LoginFailureReason::TwoFactorExpired),
+            )
+            .await;
+            HttpResponse::Unauthorized().json(Response {
+                status: "fail",
+                message: "Login challenge expired, please log in again".to_string(),
+            })
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": e,
+        })),
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/auth/logout",
     tag = "Authentication Endpoint",
     request_body(content = (), description = "Credentials to logout"),
     responses(
-        (status=200, description= "Account logout successfully", body= Response ),
+        (status=200, description= "Current device logged out, other sessions stay signed in", body= Response ),
     ),
     security(
        ("token" = [])
    )
 )]
 pub async fn logout_user_handler(
-    req: HttpRequest,
     data: web::Data<AppState>,
     auth_guard: Authenticated,
 ) -> impl Responder {
-    let message = "Token is invalid or session has expired";
-
-    let refresh_token = match req.cookie("refresh_token") {
-        Some(c) => c.value().to_string(),
-        None => {
-            return HttpResponse::Forbidden()
-                .json(serde_json::json!({"status": "fail", "message": message}));
-        }
+    let result = if auth_guard.session_id.is_empty() {
+        // Token issued before sessions were tracked, only the token itself is known.
+        session_service::revoke_token(&data.redis, &auth_guard.access_token_uuid.to_string()).await
+    } else {
+        session_service::revoke_session(&data.redis, &auth_guard.id, &auth_guard.session_id)
+            .await
+            .map(|_| ())
     };
 
-    let refresh_token_details = match token::verify_jwt_token(
-        data.config.refresh_token_public_key.to_owned(),
-        &refresh_token,
-    ) {
-        Ok(token_details) => token_details,
-        Err(e) => {
-            return HttpResponse::Forbidden()
-                .json(serde_json::json!({"status": "fail", "message": format_args!("{:?}", e)}));
-        }
-    };
-
-    let mut redis_client = data.redis_client.get_async_connection().await.unwrap();
-    let redis_result: redis::RedisResult<usize> = redis_client
-        .del(&[
-            refresh_token_details.token_uuid.to_string(),
-            auth_guard.access_token_uuid.to_string(),
-        ])
-        .await;
-
-    if redis_result.is_err() {
-        return HttpResponse::UnprocessableEntity().json(
-            serde_json::json!({"status": "error", "message": format_args!("{:?}", redis_result.unwrap_err())}),
-        );
+    if let Err(e) = result {
+        return HttpResponse::UnprocessableEntity()
+            .json(serde_json::json!({"status": "error", "message": format_args!("{:?}", e)}));
     }
 
     let access_cookie = Cookie::build("access_token", "")
@@ -408,27 +747,30 @@ pub async fn logout_user_handler(
         .cookie(access_cookie)
         .cookie(refresh_cookie)
         .cookie(logged_in_cookie)
+        .cookie(csrf::expired_csrf_cookie())
         .json(Response {
             status: "success",
             message: "Account logout successfully".to_string(),
         })
 }
 
+#[utoipa::path(
+    post,
+    path = "/auth/refresh",
+    tag = "Authentication Endpoint",
+    request_body(content = RefreshTokenSchema, description = "Refresh token of the session", example = json!({"refresh_token": "eyJ0eXAiOiJKV1QiLCJhbGciOiJSUzI1NiJ9..."})),
+    responses(
+        (status=200, description= "New access token and a new refresh token, the presented one can not be used again", body= UserLoginResponseDto ),
+        (status=401, description= "Refresh token was already used, the whole session is revoked", body= Response),
+        (status=403, description= "Refresh token is invalid, the session has expired, or the account is disabled or not verified", body= Response),
+    )
+)]
 pub async fn refresh_token_handler(
-    // req: HttpRequest,
     data: web::Data<AppState>,
     body: web::Json<RefreshTokenSchema>,
 ) -> impl Responder {
     let message = "could not refresh access token";
 
-    // let refresh_token = match req.cookie("refresh_token") {
-    //     Some(c) => c.value().to_string(),
-    //     None => {
-    //         return HttpResponse::Forbidden()
-    //             .json(serde_json::json!({"status": "fail", "message": message}))
-    //     }
-    // };
-
     let refresh_token = match body.validate() {
         Ok(()) => body.refresh_token.to_owned(),
         Err(message) => {
@@ -437,107 +779,389 @@ pub async fn refresh_token_handler(
         }
     };
 
-    let refresh_token_details = match token::verify_jwt_token(
-        data.config.refresh_token_public_key.to_owned(),
-        &refresh_token,
-    ) {
-        Ok(token_details) => token_details,
-        Err(e) => {
+    let refresh_token_details =
+        match token::verify_jwt_token(&data.config.refresh_token_public_keys(), &refresh_token) {
+            Ok(token_details) => token_details,
+            Err(e) => {
+                return HttpResponse::Forbidden().json(
+                    serde_json::json!({"status": "fail", "message": format_args!("{:?}", e)}),
+                );
+            }
+        };
+
+    // Tokens issued before sessions existed can not be rotated.
+    if refresh_token_details.session_id.is_empty() {
+        return HttpResponse::Forbidden().json(
+            serde_json::json!({"status": "fail", "message": "Session has expired, please log in again"}),
+        );
+    }
+
+    let mut redis = data.redis.clone();
+
+    let session_id = refresh_token_details.session_id.clone();
+    let refresh_token_uuid = refresh_token_details.token_uuid.to_string();
+
+    match session_service::claim_refresh_token(&mut redis, &session_id, &refresh_token_uuid).await {
+        Ok(RefreshClaim::Claimed) => {}
+        Ok(RefreshClaim::Expired) => {
             return HttpResponse::Forbidden()
-                .json(serde_json::json!({"status": "fail", "message": format_args!("{:?}", e)}));
+                .json(serde_json::json!({"status": "fail", "message": message}));
         }
-    };
+        Ok(RefreshClaim::Reused) => {
+            // An old refresh token came back, someone else may hold a copy of
+            // it. Kill the whole token family so both parties have to log in.
+            if let Err(e) = session_service::revoke_session(
+                &data.redis,
+                &refresh_token_details.user_id,
+                &session_id,
+            )
+            .await
+            {
+                eprintln!("🔥 Failed to revoke reused session: {}", e);
+            }
 
-    let result = data.redis_client.get_async_connection().await;
-    let mut redis_client = match result {
-        Ok(redis_client) => redis_client,
+            return HttpResponse::Unauthorized().json(serde_json::json!({
+                "status": "fail",
+                "message": "Refresh token was already used, the session has been logged out for safety. Please log in again"
+            }));
+        }
         Err(e) => {
-            return HttpResponse::Forbidden().json(
-                serde_json::json!({"status": "fail", "message": format!("Could not connect to Redis: {}", e)}),
-            );
+            return HttpResponse::UnprocessableEntity()
+                .json(serde_json::json!({"status": "error", "message": format_args!("{:?}", e)}));
+        }
+    }
+
+    let user_service = UserService::new(data.db.clone());
+
+    let query_result = match user_service
+        .get_user(Some(&refresh_token_details.user_id), None, None)
+        .await
+    {
+        Ok(query_result) => query_result,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(serde_json::json!({"status": "error", "message": format_args!("{:?}", e)}));
         }
     };
-    let redis_result: redis::RedisResult<String> = redis_client
-        .get(refresh_token_details.token_uuid.to_string())
-        .await;
 
-    let user_id = match redis_result {
-        Ok(value) => value,
-        Err(_) => {
+    let user = match query_result {
+        Some(user) if user.is_disabled() => {
+            return HttpResponse::Forbidden().json(
+                serde_json::json!({"status": "fail", "message": "This account has been disabled"}),
+            );
+        }
+        Some(user) if data.config.require_verified_email && user.verified == 0 => {
+            return HttpResponse::Forbidden().json(
+                serde_json::json!({"status": "fail", "message": "Please verify your email before logging in"}),
+            );
+        }
+        Some(user) => user,
+        None => {
             return HttpResponse::Forbidden()
-                .json(serde_json::json!({"status": "fail", "message": message}));
+                .json(serde_json::json!({"status": "fail", "message": "the user belonging to this token no logger exists"}));
         }
     };
 
-    let user_service = UserService::new(data.db.clone());
+    let redis_result: redis::RedisResult<usize> = redis.del(&refresh_token_uuid).await;
+    if let Err(e) = redis_result {
+        return HttpResponse::UnprocessableEntity()
+            .json(serde_json::json!({"status": "error", "message": format_args!("{:?}", e)}));
+    }
 
-    let query_result = user_service
-        .get_user(Some(&user_id), None, None)
-        .await
-        .unwrap();
+    let tokens = match issue_session_tokens(&data, &mut redis, &user.id, &session_id).await {
+        Ok(tokens) => tokens,
+        Err(response) => return response,
+    };
 
-    if query_result.is_none() {
-        return HttpResponse::Forbidden()
-            .json(serde_json::json!({"status": "fail", "message": "the user belonging to this token no logger exists"}));
-    }
+    session_service::touch_session(&data.redis, &session_id).await;
 
-    let user = query_result.unwrap();
+    session_tokens_response(HttpResponse::Ok(), &data, tokens)
+}
 
-    let access_token_details = match token::generate_jwt_token(
-        user.id.clone(),
-        data.config.access_token_max_age,
-        data.config.access_token_private_key.to_owned(),
-    ) {
-        Ok(token_details) => token_details,
-        Err(e) => {
-            return HttpResponse::BadGateway()
-                .json(serde_json::json!({"status": "fail", "message": format_args!("{:?}", e)}));
-        }
+/// Minimal page shown to people opening the verification link in a browser.
+fn verification_page(mut response: HttpResponseBuilder, content: &str) -> HttpResponse {
+    response
+        .content_type(ContentType::html())
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .insert_header((header::REFERRER_POLICY, "no-referrer"))
+        .body(format!(
+            "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><meta name=\"viewport\" content=\"width=device-width, initial-scale=1\"><title>Verify your email</title></head><body>{}</body></html>",
+            content
+        ))
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/verify-email",
+    tag = "Authentication Endpoint",
+    params(VerifyEmailSchema),
+    responses(
+        (status=200, description= "Page asking to confirm, its form posts the token to /auth/verify-email. Opening it does not verify anything"),
+    )
+)]
+pub async fn verify_email_page_handler(query: web::Query<VerifyEmailSchema>) -> impl Responder {
+    verification_page(
+        HttpResponse::Ok(),
+        &format!(
+            "<h1>Verify your email</h1><form method=\"post\" action=\"verify-email\"><input type=\"hidden\" name=\"token\" value=\"{}\"><button type=\"submit\">Verify my email</button></form>",
+            escape_html(&query.token)
+        ),
+    )
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/verify-email",
+    tag = "Authentication Endpoint",
+    request_body(content = VerifyEmailSchema, description = "Token from the verification email, as JSON or as the form of the confirm page", example = json!({"token": "3f1c9a0e8b7d4c2a9e6f5b4a3c2d1e0f"})),
+    responses(
+        (status=200, description= "Email verified", body= Response ),
+        (status=400, description= "Token is invalid or expired", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    )
+)]
+pub async fn verify_email_handler(
+    body: Either<web::Json<VerifyEmailSchema>, web::Form<VerifyEmailSchema>>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let (body, from_page) = match body {
+        Either::Left(body) => (body.into_inner(), false),
+        Either::Right(body) => (body.into_inner(), true),
     };
 
-    let redis_result: redis::RedisResult<()> = redis_client
-        .set_ex(
-            access_token_details.token_uuid.to_string(),
-            user.id.to_string(),
-            (data.config.access_token_max_age * 60) as u64,
-        )
-        .await;
+    if let Err(e) = body.validate() {
+        if from_page {
+            return verification_page(
+                HttpResponse::BadRequest(),
+                "<h1>Verification link is invalid</h1>",
+            );
+        }
 
-    if redis_result.is_err() {
-        return HttpResponse::UnprocessableEntity().json(
-            serde_json::json!({"status": "error", "message": format_args!("{:?}", redis_result.unwrap_err())}),
-        );
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
     }
 
-    let access_cookie = Cookie::build("access_token", access_token_details.token.clone().unwrap())
-        .path("/")
-        .max_age(ActixWebDuration::new(
-            data.config.access_token_max_age * 60,
-            0,
-        ))
-        .http_only(true)
-        .finish();
+    let verification_service =
+        VerificationService::new(data.db.clone(), data.redis.clone(), data.mailer.clone());
 
-    let logged_in_cookie = Cookie::build("logged_in", "true")
-        .path("/")
-        .max_age(ActixWebDuration::new(
-            data.config.access_token_max_age * 60,
-            0,
-        ))
-        .http_only(false)
-        .finish();
+    let verified = verification_service.verify(&body.token).await;
 
-    let token_response = RefreshTokenResponseDto {
-        status: "success".to_string(),
-        data: TokenData {
-            access_token: access_token_details.token.unwrap(),
-            refresh_token: None,
-            refresh_token_expired: None,
-        },
-    };
+    if from_page {
+        return match verified {
+            Ok(true) => verification_page(
+                HttpResponse::Ok(),
+                "<h1>Email verified</h1><p>You can go back to the app and log in.</p>",
+            ),
+            Ok(false) => verification_page(
+                HttpResponse::BadRequest(),
+                "<h1>Verification link is invalid or has expired</h1><p>Ask the app for a new one.</p>",
+            ),
+            Err(e) => {
+                eprintln!("🔥 Failed to verify email: {}", e);
+                verification_page(
+                    HttpResponse::InternalServerError(),
+                    "<h1>Something went wrong</h1><p>Please try again later.</p>",
+                )
+            }
+        };
+    }
+
+    match verified {
+        Ok(true) => HttpResponse::Ok().json(Response {
+            status: "success",
+            message: "Email verified".to_string(),
+        }),
+        Ok(false) => HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message: "Verification link is invalid or has expired".to_string(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/verify-email/resend",
+    tag = "Authentication Endpoint",
+    request_body(content = ResendVerificationSchema, description = "Email to send a new verification link to", example = json!({"email": "user1@mail.com"})),
+    responses(
+        (status=200, description= "A new link is sent when the account exists and is not verified yet", body= Response ),
+        (status=400, description= "Validation Errors", body= Response),
+        (status=429, description= "Asked too soon, the Retry-After header holds the seconds to wait", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    )
+)]
+pub async fn resend_verification_handler(
+    body: web::Json<ResendVerificationSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    let verification_service =
+        VerificationService::new(data.db.clone(), data.redis.clone(), data.mailer.clone());
+
+    match verification_service.resend(&body.email, &data.config).await {
+        Ok(ResendOutcome::Sent) => HttpResponse::Ok().json(Response {
+            status: "success",
+            message: "If the account needs verification, a new link has been sent".to_string(),
+        }),
+        Ok(ResendOutcome::Throttled(retry_after)) => HttpResponse::TooManyRequests()
+            .insert_header((header::RETRY_AFTER, retry_after.to_string()))
+            .json(Response {
+                status: "fail",
+                message: format!(
+                    "Please wait {} seconds before asking for another link",
+                    retry_after
+                ),
+            }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/forgot-password",
+    tag = "Authentication Endpoint",
+    request_body(content = ForgotPasswordSchema, description = "Email of the account to reset", example = json!({"email": "user1@mail.com"})),
+    responses(
+        (status=200, description= "A reset token is emailed when the account exists", body= Response ),
+        (status=400, description= "Validation Errors", body= Response),
+        (status=429, description= "Asked too soon, the Retry-After header holds the seconds to wait", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    )
+)]
+pub async fn forgot_password_handler(
+    body: web::Json<ForgotPasswordSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    let password_reset_service =
+        PasswordResetService::new(data.db.clone(), data.redis.clone(), data.mailer.clone());
+
+    match password_reset_service
+        .request_reset(&body.email, &data.config)
+        .await
+    {
+        Ok(ResendOutcome::Sent) => HttpResponse::Ok().json(Response {
+            status: "success",
+            message: "If an account uses this email, a reset link has been sent".to_string(),
+        }),
+        Ok(ResendOutcome::Throttled(retry_after)) => HttpResponse::TooManyRequests()
+            .insert_header((header::RETRY_AFTER, retry_after.to_string()))
+            .json(Response {
+                status: "fail",
+                message: format!(
+                    "Please wait {} seconds before asking for another link",
+                    retry_after
+                ),
+            }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/reset-password",
+    tag = "Authentication Endpoint",
+    request_body(content = ResetPasswordSchema, description = "Reset token and the new password", example = json!({"token": "0f8fad5bd9cb469fa16570867728950e","password": "new-secret","passwordConfirm": "new-secret"})),
+    responses(
+        (status=200, description= "Password changed, every session is logged out", body= Response ),
+        (status=400, description= "Validation Errors or the token is invalid, used or expired", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    )
+)]
+pub async fn reset_password_handler(
+    body: web::Json<ResetPasswordSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    if let Err(message) = password::check_policy(&body.password, &data.config) {
+        return HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message,
+        });
+    }
+
+    let password_reset_service =
+        PasswordResetService::new(data.db.clone(), data.redis.clone(), data.mailer.clone());
+
+    match password_reset_service
+        .reset_password(&body.token, &body.password, &data.config)
+        .await
+    {
+        Ok(true) => HttpResponse::Ok().json(Response {
+            status: "success",
+            message: "Password changed, please log in again".to_string(),
+        }),
+        Ok(false) => HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message: "Reset token is invalid or has expired".to_string(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/csrf",
+    tag = "Authentication Endpoint",
+    responses(
+        (status=200, description= "Token to send in the X-CSRF-Token header of POST, PATCH and DELETE requests authenticated by the access_token cookie", body= CsrfTokenResponseDto ),
+    )
+)]
+pub async fn get_csrf_token_handler(req: HttpRequest, data: web::Data<AppState>) -> impl Responder {
+    // Keep the current token so other open tabs stay valid.
+    let csrf_token = req
+        .cookie(csrf::CSRF_COOKIE)
+        .map(|c| c.value().to_string())
+        .filter(|token| !token.is_empty())
+        .unwrap_or_else(csrf::generate_token);
 
     HttpResponse::Ok()
-        .cookie(access_cookie)
-        .cookie(logged_in_cookie)
-        .json(serde_json::json!(token_response))
+        .cookie(csrf::csrf_cookie(&csrf_token, &data.config))
+        .insert_header((csrf::CSRF_HEADER, csrf_token.clone()))
+        .json(CsrfTokenResponseDto {
+            status: "success".to_string(),
+            data: CsrfTokenData { csrf_token },
+        })
 }
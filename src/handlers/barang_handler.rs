@@ -1,28 +1,121 @@
 use actix_web::{
+    http::header::{self, Header, IfMatch},
     web::{self, Json},
-    HttpResponse, Responder,
+    HttpRequest, HttpResponse, Responder,
 };
 use serde_json::json;
 use validator::Validate;
 
 use crate::{
     dtos::{
-        barang::{BarangData, BarangDto, BarangResponseDto, BarangsData, BarangsResponseDto},
+        barang::{
+            BarangConflictDto, BarangConflictsData, BarangConflictsResponseDto, BarangData,
+            BarangDto, BarangResponseDto, BarangsData, BarangsResponseDto, BulkBarangChangeDto,
+            BulkBarangData, BulkBarangResponseDto, SyncConflictStatus,
+        },
         global::Response,
     },
     models::barang::BarangModel,
-    schemas::barang::{GetBarangSchema, InsertBarangSchema, SyncBarangSchema},
-    services::barang_service::BarangService,
+    schemas::barang::{
+        BulkCategorySchema, BulkDeleteSchema, BulkPriceSchema, GetBarangSchema, InsertBarangSchema,
+        SyncBarangSchema, UpdateBarangSchema,
+    },
+    services::{
+        barang_service::{BarangService, BulkOutcome, VersionedWrite},
+        dashboard_service::invalidate_dashboard,
+    },
+    utils::{error::ErrorMessage, extractor::Tenant},
     AppState,
 };
 
+/// Version the client expects from the `If-Match` header, `None` for `*`.
+fn expected_version(req: &HttpRequest) -> Result<Option<i32>, HttpResponse> {
+    let tags = match IfMatch::parse(req) {
+        Ok(IfMatch::Any) => return Ok(None),
+        Ok(IfMatch::Items(tags)) => tags,
+        Err(_) => vec![],
+    };
+
+    match tags.iter().find_map(|tag| tag.tag().parse::<i32>().ok()) {
+        Some(version) => Ok(Some(version)),
+        None => Err(HttpResponse::PreconditionRequired().json(Response {
+            status: "fail",
+            message: "If-Match header with the barang ETag is required".to_string(),
+        })),
+    }
+}
+
+fn version_conflict_response(current: BarangModel) -> HttpResponse {
+    let barang: BarangDto = BarangModel::into(current);
+
+    HttpResponse::PreconditionFailed()
+        .insert_header(header::ETag(barang.etag()))
+        .json(json!({
+            "status": "fail",
+            "message": "Barang was changed by someone else, please review the current version",
+            "data": BarangData { barang },
+        }))
+}
+
+/// Deleting and bulk changes are for owners and admins of the organization.
+fn require_organization_manager(tenant: &Tenant) -> Result<(), HttpResponse> {
+    if tenant.role.can_manage() {
+        return Ok(());
+    }
+
+    Err(HttpResponse::Forbidden().json(Response {
+        status: "fail",
+        message: ErrorMessage::OrganizationManagerRequired.to_string(),
+    }))
+}
+
+async fn bulk_response(
+    data: &web::Data<AppState>,
+    tenant: &Tenant,
+    preview: bool,
+    outcome: Result<BulkOutcome, String>,
+) -> HttpResponse {
+    match outcome {
+        Ok(BulkOutcome::Applied(changes)) => {
+            if !preview && !changes.is_empty() {
+                invalidate_dashboard(&data.redis, &tenant.organization_id).await;
+            }
+
+            let barang: Vec<BulkBarangChangeDto> = changes
+                .into_iter()
+                .map(|change| BulkBarangChangeDto {
+                    before: BarangModel::into(change.before),
+                    after: change.after.map(BarangModel::into),
+                })
+                .collect();
+
+            HttpResponse::Ok().json(BulkBarangResponseDto {
+                status: "success".to_string(),
+                data: BulkBarangData {
+                    preview,
+                    affected: barang.len(),
+                    barang,
+                },
+            })
+        }
+        Ok(BulkOutcome::Rejected(message)) => HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message,
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/api/barang",
     tag = "Barang Endpoint",
     request_body(content = (), description = "Insert new barang", example = json!({"name":"Barang 1", "price": 11000, "stock": 100, "expired_at": "2024-02-05"})),
     responses(
-        (status=200, description= "Success insert new barang", body= BarangResponseDto ),
+        (status=200, description= "Success insert new barang, the ETag header holds its version", body= BarangResponseDto ),
         (status=500, description= "Failed insert barang", body= Response ),
     ),
     security(
@@ -30,6 +123,7 @@ use crate::{
    )
 )]
 pub async fn insert_barang_handler(
+    tenant: Tenant,
     body: web::Json<InsertBarangSchema>,
     data: web::Data<AppState>,
 ) -> impl Responder {
@@ -39,23 +133,31 @@ pub async fn insert_barang_handler(
 
             let barang_id = uuid::Uuid::new_v4().to_string();
 
-            if let Err(err) = barang_service.insert_barang(&barang_id, body).await {
+            if let Err(err) = barang_service
+                .insert_barang(&tenant, &barang_id, body)
+                .await
+            {
                 return HttpResponse::InternalServerError().json(json!({
                     "status":"error",
                     "message": format!("{:?}", err)
                 }));
             }
 
-            match barang_service.get_barang_by_id(&barang_id).await {
+            invalidate_dashboard(&data.redis, &tenant.organization_id).await;
+
+            match barang_service.get_barang_by_id(&tenant, &barang_id).await {
                 Ok(barang) => {
+                    let barang: BarangDto = BarangModel::into(barang);
+                    let etag = barang.etag();
+
                     let response = BarangResponseDto {
                         status: "success".to_string(),
-                        data: BarangData {
-                            barang: BarangModel::into(barang),
-                        },
+                        data: BarangData { barang },
                     };
 
-                    HttpResponse::Ok().json(response)
+                    HttpResponse::Ok()
+                        .insert_header(header::ETag(etag))
+                        .json(response)
                 }
                 Err(e) => HttpResponse::InternalServerError().json(json!({
                     "status": "error",
@@ -76,6 +178,8 @@ pub async fn insert_barang_handler(
     tag = "Barang Endpoint",
     params(
         GetBarangSchema,
+        ("X-Organization-Id" = Option<String>, Header, description = "Organization to read from"),
+        ("X-Store-Id" = Option<String>, Header, description = "Store to read from"),
     ),
     responses(
         (status=200, description= "Success get barang", body= BarangsResponseDto ),
@@ -86,6 +190,7 @@ pub async fn insert_barang_handler(
    )
 )]
 pub async fn get_barang_handler(
+    tenant: Tenant,
     query: web::Query<GetBarangSchema>,
     data: web::Data<AppState>,
 ) -> impl Responder {
@@ -94,7 +199,7 @@ pub async fn get_barang_handler(
     let barang_service = BarangService::new(data.db.clone());
 
     match barang_service
-        .get_barang_by_name(query_params.name.as_deref())
+        .get_barang_by_name(&tenant, query_params.name.as_deref())
         .await
     {
         Ok(barang) => {
@@ -116,6 +221,193 @@ pub async fn get_barang_handler(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/barang/{id}",
+    tag = "Barang Endpoint",
+    params(
+        ("id" = String, Path, description = "Barang id"),
+    ),
+    responses(
+        (status=200, description= "Success get barang, the ETag header holds its version", body= BarangResponseDto ),
+        (status=404, description= "Barang not found", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_barang_by_id_handler(
+    tenant: Tenant,
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let barang_service = BarangService::new(data.db.clone());
+
+    match barang_service.get_barang_by_id(&tenant, &path).await {
+        Ok(barang) => {
+            let barang: BarangDto = BarangModel::into(barang);
+            let etag = barang.etag();
+
+            HttpResponse::Ok()
+                .insert_header(header::ETag(etag))
+                .json(BarangResponseDto {
+                    status: "success".to_string(),
+                    data: BarangData { barang },
+                })
+        }
+        Err(sqlx::Error::RowNotFound) => HttpResponse::NotFound().json(Response {
+            status: "fail",
+            message: "Barang not found".to_string(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/barang/{id}",
+    tag = "Barang Endpoint",
+    params(
+        ("id" = String, Path, description = "Barang id"),
+        ("If-Match" = String, Header, description = "ETag of the barang being edited"),
+    ),
+    request_body(content = UpdateBarangSchema, description = "Fields to change, stock only changes through /stock-in and /stock-out", example = json!({"price": 12000})),
+    responses(
+        (status=200, description= "Success update barang", body= BarangResponseDto ),
+        (status=404, description= "Barang not found", body= Response ),
+        (status=412, description= "Version mismatch, body holds the current server copy", body= BarangResponseDto ),
+        (status=428, description= "If-Match header missing", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn update_barang_handler(
+    req: HttpRequest,
+    tenant: Tenant,
+    path: web::Path<String>,
+    body: web::Json<UpdateBarangSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    let expected_version = match expected_version(&req) {
+        Ok(version) => version,
+        Err(response) => return response,
+    };
+
+    let barang_id = path.into_inner();
+    let barang_service = BarangService::new(data.db.clone());
+
+    match barang_service
+        .update_barang(&tenant, &barang_id, expected_version, &body)
+        .await
+    {
+        Ok(VersionedWrite::Applied) => {
+            invalidate_dashboard(&data.redis, &tenant.organization_id).await;
+        }
+        Ok(VersionedWrite::Conflict(current)) => return version_conflict_response(current),
+        Ok(VersionedWrite::NotFound) => {
+            return HttpResponse::NotFound().json(Response {
+                status: "fail",
+                message: "Barang not found".to_string(),
+            })
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": format!("{:?}", e)
+            }))
+        }
+    }
+
+    match barang_service.get_barang_by_id(&tenant, &barang_id).await {
+        Ok(barang) => {
+            let barang: BarangDto = BarangModel::into(barang);
+            let etag = barang.etag();
+
+            HttpResponse::Ok()
+                .insert_header(header::ETag(etag))
+                .json(BarangResponseDto {
+                    status: "success".to_string(),
+                    data: BarangData { barang },
+                })
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/barang/{id}",
+    tag = "Barang Endpoint",
+    params(
+        ("id" = String, Path, description = "Barang id"),
+        ("If-Match" = String, Header, description = "ETag of the barang being deleted"),
+    ),
+    responses(
+        (status=200, description= "Success delete barang", body= Response ),
+        (status=403, description= "Only owners and admins of the organization can delete barang", body= Response ),
+        (status=404, description= "Barang not found", body= Response ),
+        (status=412, description= "Version mismatch, body holds the current server copy", body= BarangResponseDto ),
+        (status=428, description= "If-Match header missing", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn delete_barang_handler(
+    req: HttpRequest,
+    tenant: Tenant,
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(response) = require_organization_manager(&tenant) {
+        return response;
+    }
+
+    let expected_version = match expected_version(&req) {
+        Ok(version) => version,
+        Err(response) => return response,
+    };
+
+    let barang_service = BarangService::new(data.db.clone());
+
+    match barang_service
+        .delete_barang(&tenant, &path, expected_version)
+        .await
+    {
+        Ok(VersionedWrite::Applied) => {
+            invalidate_dashboard(&data.redis, &tenant.organization_id).await;
+
+            HttpResponse::Ok().json(Response {
+                status: "success",
+                message: "Barang deleted".to_string(),
+            })
+        }
+        Ok(VersionedWrite::Conflict(current)) => version_conflict_response(current),
+        Ok(VersionedWrite::NotFound) => HttpResponse::NotFound().json(Response {
+            status: "fail",
+            message: "Barang not found".to_string(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/api/barang/sync",
@@ -123,10 +415,11 @@ pub async fn get_barang_handler(
     params(
         SyncBarangSchema,
     ),
-    request_body(content = (), description = "Insert new barang", 
-    example = json!({"barang": [{"name":"Barang 1", "price": 11000, "stock": 100, "expired_at": "2024-02-05"}, {"name":"Barang 2", "price": 22000, "stock": 200, "expired_at": "2024-06-05"},]})),
+    request_body(content = (), description = "Insert new barang, or update existing ones when `id` and `version` are given. `stock` is only used as opening stock of new barang", 
+    example = json!({"barang": [{"name":"Barang 1", "price": 11000, "stock": 100, "expired_at": "2024-02-05"}, {"id": "4b0c1f0e-3c8a-4a4e-9f61-6c7c1c9b2d11", "version": 3, "name":"Barang 2", "price": 22000, "expired_at": "2024-06-05"},]})),
     responses(
         (status=200, description= "Success sync barang", body= Response ),
+        (status=409, description= "Some barang changed or were deleted on the server, others were saved", body= BarangConflictsResponseDto ),
         (status=500, description= "Failed sync barang", body= Response ),
     ),
     security(
@@ -134,6 +427,7 @@ pub async fn get_barang_handler(
    )
 )]
 pub async fn sync_barang_handler(
+    tenant: Tenant,
     body: web::Json<SyncBarangSchema>,
     data: web::Data<AppState>,
 ) -> impl Responder {
@@ -145,20 +439,79 @@ pub async fn sync_barang_handler(
         let _ = barang_val.validate().map_err(|e| {
             validation_errors.push(e.to_string());
         });
+
+        if barang_val.id.is_some() && barang_val.version.is_none() {
+            validation_errors.push(format!(
+                "version is required to update barang {}",
+                barang_val.id.unwrap()
+            ));
+        }
     }
 
     if validation_errors.is_empty() {
-        for barang in body.barang.clone() {
-            let barang_id = uuid::Uuid::new_v4().to_string();
+        let mut modified = vec![];
+        let mut conflicts = vec![];
 
-            if let Err(err) = barang_service.insert_barang(&barang_id, Json(barang)).await {
-                return HttpResponse::InternalServerError().json(json!({
-                    "status":"error",
-                    "message": format!("{:?}", err)
-                }));
+        for item in body.barang.clone() {
+            let barang_id = match item.id {
+                Some(barang_id) => barang_id,
+                None => {
+                    let barang_id = uuid::Uuid::new_v4().to_string();
+
+                    if let Err(err) = barang_service
+                        .insert_barang(&tenant, &barang_id, Json(item.barang))
+                        .await
+                    {
+                        return HttpResponse::InternalServerError().json(json!({
+                            "status":"error",
+                            "message": format!("{:?}", err)
+                        }));
+                    }
+
+                    continue;
+                }
+            };
+
+            match barang_service
+                .update_barang(&tenant, &barang_id, item.version, &item.barang.into())
+                .await
+            {
+                Ok(VersionedWrite::Conflict(current)) => {
+                    conflicts.push(BarangConflictDto {
+                        id: barang_id,
+                        status: SyncConflictStatus::Modified,
+                    });
+                    modified.push(current);
+                }
+                Ok(VersionedWrite::NotFound) => conflicts.push(BarangConflictDto {
+                    id: barang_id,
+                    status: SyncConflictStatus::Deleted,
+                }),
+                Ok(VersionedWrite::Applied) => {}
+                Err(err) => {
+                    return HttpResponse::InternalServerError().json(json!({
+                        "status":"error",
+                        "message": format!("{:?}", err)
+                    }));
+                }
             }
         }
 
+        invalidate_dashboard(&data.redis, &tenant.organization_id).await;
+
+        if !conflicts.is_empty() {
+            return HttpResponse::Conflict().json(BarangConflictsResponseDto {
+                status: "fail".to_string(),
+                message:
+                    "Some barang were changed or deleted on the server, other changes were saved"
+                        .to_string(),
+                data: BarangConflictsData {
+                    barang: BarangDto::filter_iter(&modified),
+                    conflicts,
+                },
+            });
+        }
+
         HttpResponse::Ok().json(Response {
             status: "success",
             message: "Sync barang success".to_owned(),
@@ -170,3 +523,117 @@ pub async fn sync_barang_handler(
         }))
     }
 }
+
+#[utoipa::path(
+    post,
+    path = "/api/barang/bulk/price",
+    tag = "Barang Endpoint",
+    request_body(content = BulkPriceSchema, description = "Adjust the price of the selected barang by a percentage or a fixed amount",
+    example = json!({"selector": {"category": "Minuman"}, "mode": "percentage", "value": "7.5", "rounding": {"mode": "up", "step": "500"}, "preview": true})),
+    responses(
+        (status=200, description= "Affected barang before and after the change", body= BulkBarangResponseDto ),
+        (status=400, description= "Invalid selector or a price would become negative or too large, nothing was saved", body= Response ),
+        (status=403, description= "Only owners and admins of the organization can change barang in bulk", body= Response ),
+        (status=500, description= "Failed adjust price, nothing was saved", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn bulk_price_barang_handler(
+    tenant: Tenant,
+    body: web::Json<BulkPriceSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(response) = require_organization_manager(&tenant) {
+        return response;
+    }
+
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    let barang_service = BarangService::new(data.db.clone());
+    let outcome = barang_service.bulk_adjust_price(&tenant, &body).await;
+
+    bulk_response(&data, &tenant, body.preview, outcome).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/barang/bulk/category",
+    tag = "Barang Endpoint",
+    request_body(content = BulkCategorySchema, description = "Set the category and tags of the selected barang",
+    example = json!({"selector": {"ids": ["4b0c1f0e-3c8a-4a4e-9f61-6c7c1c9b2d11"]}, "category": "Minuman", "tags": ["promo"], "tags_mode": "add"})),
+    responses(
+        (status=200, description= "Affected barang before and after the change", body= BulkBarangResponseDto ),
+        (status=400, description= "Invalid selector, nothing was saved", body= Response ),
+        (status=403, description= "Only owners and admins of the organization can change barang in bulk", body= Response ),
+        (status=500, description= "Failed set category, nothing was saved", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn bulk_category_barang_handler(
+    tenant: Tenant,
+    body: web::Json<BulkCategorySchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(response) = require_organization_manager(&tenant) {
+        return response;
+    }
+
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    let barang_service = BarangService::new(data.db.clone());
+    let outcome = barang_service.bulk_set_category(&tenant, &body).await;
+
+    bulk_response(&data, &tenant, body.preview, outcome).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/barang/bulk/delete",
+    tag = "Barang Endpoint",
+    request_body(content = BulkDeleteSchema, description = "Delete the selected barang",
+    example = json!({"selector": {"tag": "discontinued"}, "preview": true})),
+    responses(
+        (status=200, description= "Deleted barang", body= BulkBarangResponseDto ),
+        (status=400, description= "Invalid selector, nothing was deleted", body= Response ),
+        (status=403, description= "Only owners and admins of the organization can change barang in bulk", body= Response ),
+        (status=500, description= "Failed delete barang, nothing was deleted", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn bulk_delete_barang_handler(
+    tenant: Tenant,
+    body: web::Json<BulkDeleteSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(response) = require_organization_manager(&tenant) {
+        return response;
+    }
+
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    let barang_service = BarangService::new(data.db.clone());
+    let outcome = barang_service.bulk_delete(&tenant, &body).await;
+
+    bulk_response(&data, &tenant, body.preview, outcome).await
+}
@@ -0,0 +1,74 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde_json::json;
+use validator::Validate;
+
+use crate::{
+    dtos::{
+        dashboard::{ActivityDto, DashboardData, DashboardResponseDto},
+        global::Response,
+    },
+    schemas::dashboard::GetDashboardSchema,
+    services::dashboard_service::DashboardService,
+    utils::extractor::Tenant,
+    AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/dashboard",
+    tag = "Dashboard Endpoint",
+    params(
+        GetDashboardSchema,
+        ("X-Organization-Id" = Option<String>, Header, description = "Organization to summarize"),
+        ("X-Store-Id" = Option<String>, Header, description = "Store to summarize"),
+    ),
+    responses(
+        (status=200, description= "Home screen summary", body= DashboardResponseDto ),
+        (status=400, description= "Validation Errors", body= Response ),
+        (status=500, description= "Failed get dashboard", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_dashboard_handler(
+    tenant: Tenant,
+    query: web::Query<GetDashboardSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status": "fail",
+            "message": e,
+        }));
+    }
+
+    let dashboard_service = DashboardService::new(data.db.clone(), data.redis.clone());
+
+    let summary = match dashboard_service.summary(&tenant, &data.config).await {
+        Ok(summary) => summary,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": format!("{:?}", e)
+            }))
+        }
+    };
+
+    match dashboard_service
+        .recent_activity(&tenant, query.activity_limit.unwrap_or(10))
+        .await
+    {
+        Ok(activity) => HttpResponse::Ok().json(DashboardResponseDto {
+            status: "success".to_string(),
+            data: DashboardData {
+                summary,
+                recent_activity: ActivityDto::filter_iter(&activity),
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
@@ -0,0 +1,287 @@
+use actix_web::{
+    http::header::{self, ContentDisposition},
+    web, HttpResponse, Responder,
+};
+use serde_json::json;
+use validator::Validate;
+
+use crate::{
+    dtos::{
+        barang::{BarangData, BarangDto, BarangResponseDto},
+        global::Response,
+        inventory::{InventoryValuationData, InventoryValuationResponseDto},
+    },
+    models::{
+        barang::BarangModel,
+        inventory::{MovementOutcome, ValuationMethod},
+    },
+    schemas::inventory::{InventoryValuationSchema, StockInSchema, StockOutSchema},
+    services::{
+        barang_service::BarangService, dashboard_service::invalidate_dashboard,
+        inventory_service::InventoryService, organization_service::OrganizationService,
+        pdf_service::PdfService,
+    },
+    utils::extractor::Tenant,
+    AppState,
+};
+
+async fn updated_barang_response(
+    data: &web::Data<AppState>,
+    tenant: &Tenant,
+    barang_id: &str,
+) -> HttpResponse {
+    let barang_service = BarangService::new(data.db.clone());
+
+    match barang_service.get_barang_by_id(tenant, barang_id).await {
+        Ok(barang) => {
+            let barang: BarangDto = BarangModel::into(barang);
+            let etag = barang.etag();
+
+            HttpResponse::Ok()
+                .insert_header(header::ETag(etag))
+                .json(BarangResponseDto {
+                    status: "success".to_string(),
+                    data: BarangData { barang },
+                })
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/barang/{id}/stock-in",
+    tag = "Inventory Endpoint",
+    params(
+        ("id" = String, Path, description = "Barang id"),
+    ),
+    request_body(content = StockInSchema, description = "Receive purchased stock", example = json!({"quantity": 20, "unit_cost": "9500.00", "note": "PO-2024-001"})),
+    responses(
+        (status=200, description= "Stock received, body holds the updated barang", body= BarangResponseDto ),
+        (status=400, description= "Validation Errors", body= Response ),
+        (status=404, description= "Barang not found", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn stock_in_handler(
+    tenant: Tenant,
+    path: web::Path<String>,
+    body: web::Json<StockInSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status": "fail",
+            "message": e,
+        }));
+    }
+
+    let barang_id = path.into_inner();
+    let inventory_service = InventoryService::new(data.db.clone());
+
+    match inventory_service
+        .stock_in(
+            &tenant,
+            &barang_id,
+            body.quantity,
+            body.unit_cost,
+            body.note.as_deref(),
+        )
+        .await
+    {
+        Ok(MovementOutcome::NotFound) => HttpResponse::NotFound().json(Response {
+            status: "fail",
+            message: "Barang not found".to_string(),
+        }),
+        Ok(_) => {
+            invalidate_dashboard(&data.redis, &tenant.organization_id).await;
+
+            updated_barang_response(&data, &tenant, &barang_id).await
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/barang/{id}/stock-out",
+    tag = "Inventory Endpoint",
+    params(
+        ("id" = String, Path, description = "Barang id"),
+    ),
+    request_body(content = StockOutSchema, description = "Take stock out, e.g. a sale", example = json!({"quantity": 2, "unit_price": "11000.00"})),
+    responses(
+        (status=200, description= "Stock taken out, body holds the updated barang", body= BarangResponseDto ),
+        (status=400, description= "Validation Errors", body= Response ),
+        (status=404, description= "Barang not found", body= Response ),
+        (status=409, description= "Less than the quantity is in stock, nothing was taken out", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn stock_out_handler(
+    tenant: Tenant,
+    path: web::Path<String>,
+    body: web::Json<StockOutSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status": "fail",
+            "message": e,
+        }));
+    }
+
+    let barang_id = path.into_inner();
+    let inventory_service = InventoryService::new(data.db.clone());
+
+    match inventory_service
+        .stock_out(
+            &tenant,
+            &barang_id,
+            body.quantity,
+            body.unit_price,
+            body.note.as_deref(),
+        )
+        .await
+    {
+        Ok(MovementOutcome::NotFound) => HttpResponse::NotFound().json(Response {
+            status: "fail",
+            message: "Barang not found".to_string(),
+        }),
+        Ok(MovementOutcome::InsufficientStock) => HttpResponse::Conflict().json(Response {
+            status: "fail",
+            message: "Not enough stock to take out this quantity".to_string(),
+        }),
+        Ok(MovementOutcome::Recorded) => {
+            invalidate_dashboard(&data.redis, &tenant.organization_id).await;
+
+            updated_barang_response(&data, &tenant, &barang_id).await
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+fn valuation_method(
+    query: &InventoryValuationSchema,
+    data: &web::Data<AppState>,
+) -> ValuationMethod {
+    match &query.method {
+        Some(method) => ValuationMethod::from(method.to_owned()),
+        None => data.config.inventory_valuation_method,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/reports/inventory-valuation",
+    tag = "Inventory Endpoint",
+    params(
+        InventoryValuationSchema,
+    ),
+    responses(
+        (status=200, description= "Value of stock on hand and cost of goods sold", body= InventoryValuationResponseDto ),
+        (status=500, description= "Failed compute valuation", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_inventory_valuation_handler(
+    tenant: Tenant,
+    query: web::Query<InventoryValuationSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let method = valuation_method(&query, &data);
+    let inventory_service = InventoryService::new(data.db.clone());
+
+    match inventory_service
+        .valuation(&tenant, method, query.from, query.to)
+        .await
+    {
+        Ok(valuation) => HttpResponse::Ok().json(InventoryValuationResponseDto {
+            status: "success".to_string(),
+            data: InventoryValuationData { valuation },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/reports/inventory-valuation/pdf",
+    tag = "Inventory Endpoint",
+    params(
+        InventoryValuationSchema,
+    ),
+    responses(
+        (status=200, description= "Inventory valuation report as PDF", content_type = "application/pdf"),
+        (status=500, description= "Failed render report", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_inventory_valuation_pdf_handler(
+    tenant: Tenant,
+    query: web::Query<InventoryValuationSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let method = valuation_method(&query, &data);
+    let inventory_service = InventoryService::new(data.db.clone());
+
+    let valuation = match inventory_service
+        .valuation(&tenant, method, query.from, query.to)
+        .await
+    {
+        Ok(valuation) => valuation,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": format!("{:?}", e)
+            }))
+        }
+    };
+
+    let organization_name = match OrganizationService::new(data.db.clone())
+        .get_organization_by_id(&tenant.organization_id)
+        .await
+    {
+        Ok(Some(organization)) => organization.name,
+        Ok(None) => String::new(),
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": format!("{:?}", e)
+            }))
+        }
+    };
+
+    let pdf_service = PdfService::new(data.db.clone());
+
+    match pdf_service.inventory_valuation_pdf(&organization_name, &valuation) {
+        Ok(buffer) => HttpResponse::Ok()
+            .content_type("application/pdf")
+            .insert_header(ContentDisposition::attachment("inventory-valuation.pdf"))
+            .body(buffer),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": e,
+        })),
+    }
+}
@@ -0,0 +1,36 @@
+use actix_web::{http::header, web, HttpResponse, Responder};
+use serde_json::json;
+
+use crate::{
+    dtos::{global::Response, token::JwksDto},
+    utils::token,
+    AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    tag = "Authentication Endpoint",
+    responses(
+        (status=200, description= "Public keys access tokens are signed with, the current one first", body= JwksDto ),
+        (status=500, description= "A configured key is not an RSA public key", body= Response ),
+    ),
+)]
+pub async fn get_jwks_handler(data: web::Data<AppState>) -> impl Responder {
+    let keys: Result<Vec<_>, String> = data
+        .config
+        .access_token_public_keys()
+        .iter()
+        .map(|public_key| token::public_jwk(public_key))
+        .collect();
+
+    match keys {
+        Ok(keys) => HttpResponse::Ok()
+            .insert_header((header::CACHE_CONTROL, "public, max-age=300"))
+            .json(JwksDto { keys }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
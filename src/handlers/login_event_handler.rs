@@ -0,0 +1,72 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde_json::json;
+use validator::Validate;
+
+use crate::{
+    dtos::{
+        global::Response,
+        login_event::{LoginEventDto, LoginEventsData, LoginEventsResponseDto},
+    },
+    schemas::user::ListLoginEventsSchema,
+    services::login_history_service::LoginHistoryService,
+    utils::extractor::Authenticated,
+    AppState,
+};
+
+/// Answer with one page of the login history of `user_id`.
+pub(crate) async fn login_events_response(
+    data: &AppState,
+    user_id: &str,
+    query: &ListLoginEventsSchema,
+) -> HttpResponse {
+    if let Err(e) = query.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status": "fail",
+            "message": e,
+        }));
+    }
+
+    let page = query.page.unwrap_or(1);
+    let per_page = query.per_page.unwrap_or(20);
+
+    match LoginHistoryService::new(data.db.clone())
+        .list(user_id, page, per_page)
+        .await
+    {
+        Ok((login_events, total)) => HttpResponse::Ok().json(LoginEventsResponseDto {
+            status: "success".to_string(),
+            data: LoginEventsData {
+                login_events: LoginEventDto::filter_iter(&login_events),
+                page,
+                per_page,
+                total,
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users/me/logins",
+    tag = "Users Endpoint",
+    params(ListLoginEventsSchema),
+    responses(
+        (status=200, description= "Successful and failed logins of the account, newest first", body= LoginEventsResponseDto ),
+        (status=400, description= "Validation Errors", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_my_logins_handler(
+    user: Authenticated,
+    query: web::Query<ListLoginEventsSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    login_events_response(&data, &user.id, &query).await
+}
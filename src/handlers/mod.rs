@@ -1,5 +1,16 @@
+pub mod admin_handler;
+pub mod api_key_handler;
 pub mod auth_handler;
 pub mod barang_handler;
+pub mod dashboard_handler;
+pub mod inventory_handler;
+pub mod jwks_handler;
+pub mod login_event_handler;
+pub mod oidc_handler;
+pub mod organization_handler;
 pub mod pdf_handler;
+pub mod role_handler;
+pub mod session_handler;
 pub mod storage_handler;
+pub mod two_factor_handler;
 pub mod user_handler;
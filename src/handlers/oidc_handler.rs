@@ -0,0 +1,234 @@
+use actix_web::{
+    cookie::{time::Duration as ActixWebDuration, Cookie, SameSite},
+    http::header,
+    web, HttpRequest, HttpResponse, Responder,
+};
+use serde_json::json;
+
+use crate::{
+    dtos::global::Response,
+    handlers::auth_handler::{
+        record_login_event, session_tokens_response, start_session, two_factor_challenge_response,
+    },
+    models::login_event::LoginFailureReason,
+    schemas::auth::OidcCallbackSchema,
+    services::{
+        oidc_service::{OidcLogin, OidcService},
+        session_service::SessionMeta,
+        two_factor_service::TwoFactorService,
+    },
+    AppState,
+};
+
+/// Binds a login to the browser that started it, so a callback URL carrying
+/// someone else's code and state can not sign the victim into their account.
+const OIDC_STATE_COOKIE: &str = "oidc_state";
+
+fn state_cookie(state: &str, max_age: i64) -> Cookie<'static> {
+    Cookie::build(OIDC_STATE_COOKIE, state.to_string())
+        .path("/")
+        .max_age(ActixWebDuration::new(max_age, 0))
+        .same_site(SameSite::Lax)
+        .http_only(true)
+        .finish()
+}
+
+fn oidc_disabled() -> HttpResponse {
+    HttpResponse::NotFound().json(Response {
+        status: "fail",
+        message: "OpenID Connect login is not configured".to_string(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/login",
+    tag = "Authentication Endpoint",
+    responses(
+        (status=302, description= "Redirect to the OpenID Connect provider, which sends the browser back to /auth/oidc/callback"),
+        (status=404, description= "OpenID Connect login is not configured", body= Response),
+        (status=502, description= "The provider could not be reached", body= Response),
+    )
+)]
+pub async fn oidc_login_handler(data: web::Data<AppState>) -> impl Responder {
+    if !data.config.oidc_enabled() {
+        return oidc_disabled();
+    }
+
+    let oidc_service = OidcService::new(data.db.clone(), data.redis.clone());
+
+    match oidc_service.authorization_url(&data.config).await {
+        Ok((url, state)) => HttpResponse::Found()
+            .cookie(state_cookie(&state, data.config.oidc_state_max_age as i64))
+            .insert_header((header::LOCATION, url))
+            .finish(),
+        Err(e) => {
+            eprintln!("🔥 Failed to start OpenID Connect login: {}", e);
+            HttpResponse::BadGateway().json(json!({
+                "status": "error",
+                "message": "Could not reach the OpenID Connect provider",
+            }))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/callback",
+    tag = "Authentication Endpoint",
+    params(
+        OidcCallbackSchema,
+        ("X-Device-Name" = Option<String>, Header, description = "Name of the device shown in the session list"),
+    ),
+    responses(
+        (status=201, description= "Login successfully", body= UserLoginResponseDto ),
+        (status=202, description= "Identity is verified, answer the challenge at /auth/login/2fa to get the tokens", body= TwoFactorChallengeResponseDto ),
+        (status=400, description= "The provider sent an error or no code", body= Response),
+        (status=401, description= "The login expired, was already used or was started in another browser, start over at /auth/oidc/login", body= Response),
+        (status=403, description= "No account may sign in with this identity, or the account is disabled or not verified", body= Response),
+        (status=404, description= "OpenID Connect login is not configured", body= Response),
+        (status=502, description= "The provider could not be reached or sent an invalid ID token", body= Response),
+    )
+)]
+pub async fn oidc_callback_handler(
+    req: HttpRequest,
+    query: web::Query<OidcCallbackSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let mut response = finish_oidc_login(&req, &query, &data).await;
+
+    // The state is single use, whatever became of the login.
+    if let Err(e) = response.add_removal_cookie(&state_cookie("", 0)) {
+        eprintln!("🔥 Failed to clear the OpenID Connect state cookie: {}", e);
+    }
+
+    response
+}
+
+async fn finish_oidc_login(
+    req: &HttpRequest,
+    query: &OidcCallbackSchema,
+    data: &web::Data<AppState>,
+) -> HttpResponse {
+    if !data.config.oidc_enabled() {
+        return oidc_disabled();
+    }
+
+    if let Some(error) = &query.error {
+        return HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message: query
+                .error_description
+                .clone()
+                .unwrap_or_else(|| error.to_owned()),
+        });
+    }
+
+    let (code, state) = match (&query.code, &query.state) {
+        (Some(code), Some(state)) if !code.is_empty() && !state.is_empty() => (code, state),
+        _ => {
+            return HttpResponse::BadRequest().json(Response {
+                status: "fail",
+                message: "Code and state are required".to_string(),
+            })
+        }
+    };
+
+    let started_here = req
+        .cookie(OIDC_STATE_COOKIE)
+        .is_some_and(|cookie| !cookie.value().is_empty() && cookie.value() == state.as_str());
+
+    if !started_here {
+        return HttpResponse::Unauthorized().json(Response {
+            status: "fail",
+            message: "Login was not started in this browser, please start again".to_string(),
+        });
+    }
+
+    let meta = SessionMeta::from_request(req);
+    let oidc_service = OidcService::new(data.db.clone(), data.redis.clone());
+
+    let user = match oidc_service.complete(code, state, &data.config).await {
+        Ok(OidcLogin::SignedIn(user)) => user,
+        Ok(OidcLogin::InvalidState) => {
+            return HttpResponse::Unauthorized().json(Response {
+                status: "fail",
+                message: "Login expired, please start again".to_string(),
+            })
+        }
+        Ok(OidcLogin::Rejected { email, message }) => {
+            record_login_event(
+                data,
+                &email,
+                None,
+                &meta,
+                Some(LoginFailureReason::ExternalLoginRejected),
+            )
+            .await;
+            return HttpResponse::Forbidden().json(Response {
+                status: "fail",
+                message,
+            });
+        }
+        Err(e) => {
+            eprintln!("🔥 Failed to finish OpenID Connect login: {}", e);
+            return HttpResponse::BadGateway().json(json!({
+                "status": "error",
+                "message": "The OpenID Connect provider could not confirm the login",
+            }));
+        }
+    };
+
+    if user.is_disabled() {
+        record_login_event(
+            data,
+            &user.email,
+            Some(&user.id),
+            &meta,
+            Some(LoginFailureReason::AccountDisabled),
+        )
+        .await;
+        return HttpResponse::Forbidden().json(json!({
+            "status": "fail",
+            "message": "This account has been disabled",
+        }));
+    }
+
+    if data.config.require_verified_email && user.verified == 0 {
+        record_login_event(
+            data,
+            &user.email,
+            Some(&user.id),
+            &meta,
+            Some(LoginFailureReason::EmailNotVerified),
+        )
+        .await;
+        return HttpResponse::Forbidden().json(json!({
+            "status": "fail",
+            "message": "Please verify your email before logging in",
+        }));
+    }
+
+    let two_factor_service = TwoFactorService::new(data.db.clone(), data.redis.clone());
+
+    match two_factor_service.is_enabled(&user.id).await {
+        Ok(true) => {
+            return two_factor_challenge_response(&two_factor_service, &user.id, data).await;
+        }
+        Ok(false) => {}
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": e,
+            }))
+        }
+    }
+
+    match start_session(data, &user.id, &meta).await {
+        Ok(tokens) => {
+            record_login_event(data, &user.email, Some(&user.id), &meta, None).await;
+            session_tokens_response(HttpResponse::Created(), data, tokens)
+        }
+        Err(response) => response,
+    }
+}
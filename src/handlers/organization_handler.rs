@@ -0,0 +1,464 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde_json::json;
+use validator::Validate;
+
+use crate::{
+    dtos::{
+        global::Response,
+        organization::{
+            MemberDto, MembersData, MembersResponseDto, OrganizationData, OrganizationDto,
+            OrganizationResponseDto, OrganizationsData, OrganizationsResponseDto, StoreData,
+            StoreDto, StoreResponseDto, StoresData, StoresResponseDto,
+        },
+    },
+    models::organization::{OrganizationMemberModel, OrganizationRole},
+    schemas::organization::{AddMemberSchema, CreateOrganizationSchema, CreateStoreSchema},
+    services::{
+        organization_service::{MemberChange, OrganizationService},
+        user_services::UserService,
+    },
+    utils::{error::ErrorMessage, extractor::Authenticated},
+    AppState,
+};
+
+/// Resolve the membership of the logged in user, optionally requiring a role
+/// that can manage the organization.
+async fn require_member(
+    organization_service: &OrganizationService,
+    organization_id: &str,
+    user_id: &str,
+    manage: bool,
+) -> Result<OrganizationMemberModel, HttpResponse> {
+    let member = match organization_service
+        .get_member(organization_id, user_id)
+        .await
+    {
+        Ok(Some(member)) => member,
+        Ok(None) => {
+            return Err(HttpResponse::Forbidden().json(Response {
+                status: "fail",
+                message: ErrorMessage::NotOrganizationMember.to_string(),
+            }))
+        }
+        Err(e) => {
+            return Err(HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": format!("{:?}", e)
+            })))
+        }
+    };
+
+    if manage && !member.role.can_manage() {
+        return Err(HttpResponse::Forbidden().json(Response {
+            status: "fail",
+            message: ErrorMessage::PermissionDenied.to_string(),
+        }));
+    }
+
+    Ok(member)
+}
+
+fn last_owner_response() -> HttpResponse {
+    HttpResponse::Conflict().json(Response {
+        status: "fail",
+        message: "The organization must keep at least one owner".to_string(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/organizations",
+    tag = "Organizations Endpoint",
+    responses(
+        (status=200, description= "Organizations of the logged in user", body= OrganizationsResponseDto ),
+        (status=500, description= "Failed get organizations", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_organizations_handler(
+    user: Authenticated,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let organization_service = OrganizationService::new(data.db.clone());
+
+    match organization_service.get_user_organizations(&user.id).await {
+        Ok(organizations) => HttpResponse::Ok().json(OrganizationsResponseDto {
+            status: "success".to_string(),
+            data: OrganizationsData {
+                organizations: OrganizationDto::filter_iter(&organizations),
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/organizations",
+    tag = "Organizations Endpoint",
+    request_body(content = CreateOrganizationSchema, description = "Create organization", example = json!({"name": "Toko Sejahtera"})),
+    responses(
+        (status=201, description= "Organization created, the creator becomes its owner", body= OrganizationResponseDto ),
+        (status=400, description= "Validation Errors", body= Response ),
+        (status=500, description= "Failed create organization", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn create_organization_handler(
+    user: Authenticated,
+    body: web::Json<CreateOrganizationSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status": "fail",
+            "message": e,
+        }));
+    }
+
+    let organization_service = OrganizationService::new(data.db.clone());
+
+    let organization_id = uuid::Uuid::new_v4().to_string();
+
+    if let Err(err) = organization_service
+        .create_organization(&organization_id, &user.id, &body.name)
+        .await
+    {
+        return HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", err)
+        }));
+    }
+
+    match organization_service
+        .get_organization_by_id(&organization_id)
+        .await
+    {
+        Ok(Some(organization)) => HttpResponse::Created().json(OrganizationResponseDto {
+            status: "success".to_string(),
+            data: OrganizationData {
+                organization: OrganizationDto {
+                    id: organization.id,
+                    name: organization.name,
+                    role: OrganizationRole::Owner,
+                    created_at: organization.created_at,
+                    updated_at: organization.updated_at,
+                },
+            },
+        }),
+        Ok(None) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": ErrorMessage::ServerError.to_string(),
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{id}/stores",
+    tag = "Organizations Endpoint",
+    params(
+        ("id" = String, Path, description = "Organization id"),
+    ),
+    responses(
+        (status=200, description= "Stores of the organization", body= StoresResponseDto ),
+        (status=403, description= "Not a member of the organization", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_stores_handler(
+    user: Authenticated,
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let organization_id = path.into_inner();
+    let organization_service = OrganizationService::new(data.db.clone());
+
+    if let Err(response) =
+        require_member(&organization_service, &organization_id, &user.id, false).await
+    {
+        return response;
+    }
+
+    match organization_service.get_stores(&organization_id).await {
+        Ok(stores) => HttpResponse::Ok().json(StoresResponseDto {
+            status: "success".to_string(),
+            data: StoresData {
+                stores: StoreDto::filter_iter(&stores),
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{id}/stores",
+    tag = "Organizations Endpoint",
+    params(
+        ("id" = String, Path, description = "Organization id"),
+    ),
+    request_body(content = CreateStoreSchema, description = "Create store", example = json!({"name": "Cabang Bandung", "address": "Jl. Asia Afrika 1"})),
+    responses(
+        (status=201, description= "Store created", body= StoreResponseDto ),
+        (status=400, description= "Validation Errors", body= Response ),
+        (status=403, description= "Not allowed to manage the organization", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn create_store_handler(
+    user: Authenticated,
+    path: web::Path<String>,
+    body: web::Json<CreateStoreSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status": "fail",
+            "message": e,
+        }));
+    }
+
+    let organization_id = path.into_inner();
+    let organization_service = OrganizationService::new(data.db.clone());
+
+    if let Err(response) =
+        require_member(&organization_service, &organization_id, &user.id, true).await
+    {
+        return response;
+    }
+
+    let store_id = uuid::Uuid::new_v4().to_string();
+
+    if let Err(err) = organization_service
+        .create_store(
+            &store_id,
+            &organization_id,
+            &body.name,
+            body.address.as_deref(),
+        )
+        .await
+    {
+        return HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", err)
+        }));
+    }
+
+    match organization_service
+        .get_store_by_id(&organization_id, &store_id)
+        .await
+    {
+        Ok(Some(store)) => HttpResponse::Created().json(StoreResponseDto {
+            status: "success".to_string(),
+            data: StoreData {
+                store: StoreDto::filter(&store),
+            },
+        }),
+        Ok(None) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": ErrorMessage::ServerError.to_string(),
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{id}/members",
+    tag = "Organizations Endpoint",
+    params(
+        ("id" = String, Path, description = "Organization id"),
+    ),
+    responses(
+        (status=200, description= "Members of the organization", body= MembersResponseDto ),
+        (status=403, description= "Not a member of the organization", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_members_handler(
+    user: Authenticated,
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let organization_id = path.into_inner();
+    let organization_service = OrganizationService::new(data.db.clone());
+
+    if let Err(response) =
+        require_member(&organization_service, &organization_id, &user.id, false).await
+    {
+        return response;
+    }
+
+    match organization_service.get_members(&organization_id).await {
+        Ok(members) => HttpResponse::Ok().json(MembersResponseDto {
+            status: "success".to_string(),
+            data: MembersData {
+                members: MemberDto::filter_iter(&members),
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{id}/members",
+    tag = "Organizations Endpoint",
+    params(
+        ("id" = String, Path, description = "Organization id"),
+    ),
+    request_body(content = AddMemberSchema, description = "Add or update a member", example = json!({"email": "user1@mail.com", "role": "member"})),
+    responses(
+        (status=200, description= "Member saved", body= Response ),
+        (status=403, description= "Not allowed to manage the organization, or only owners may grant ownership or change owners", body= Response ),
+        (status=409, description= "The last owner can not be demoted", body= Response ),
+        (status=404, description= "User not found", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn add_member_handler(
+    user: Authenticated,
+    path: web::Path<String>,
+    body: web::Json<AddMemberSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status": "fail",
+            "message": e,
+        }));
+    }
+
+    let organization_id = path.into_inner();
+    let organization_service = OrganizationService::new(data.db.clone());
+
+    let member = match require_member(&organization_service, &organization_id, &user.id, true).await
+    {
+        Ok(member) => member,
+        Err(response) => return response,
+    };
+
+    let user_service = UserService::new(data.db.clone());
+
+    let target = match user_service.get_user(None, None, Some(&body.email)).await {
+        Ok(Some(target)) => target,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(Response {
+                status: "fail",
+                message: "User not found!".to_string(),
+            })
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": format!("{:?}", e)
+            }))
+        }
+    };
+
+    match organization_service
+        .set_member(&organization_id, member.role, &target.id, body.role)
+        .await
+    {
+        Ok(MemberChange::Done) => HttpResponse::Ok().json(Response {
+            status: "success",
+            message: "Member saved".to_string(),
+        }),
+        Ok(MemberChange::Forbidden) => HttpResponse::Forbidden().json(Response {
+            status: "fail",
+            message: ErrorMessage::PermissionDenied.to_string(),
+        }),
+        Ok(MemberChange::LastOwner) => last_owner_response(),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/organizations/{id}/members/{user_id}",
+    tag = "Organizations Endpoint",
+    params(
+        ("id" = String, Path, description = "Organization id"),
+        ("user_id" = String, Path, description = "User id of the member"),
+    ),
+    responses(
+        (status=200, description= "Member removed", body= Response ),
+        (status=403, description= "Not allowed to manage the organization, or only owners may remove owners", body= Response ),
+        (status=409, description= "The last owner can not be removed", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn remove_member_handler(
+    user: Authenticated,
+    path: web::Path<(String, String)>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let (organization_id, member_id) = path.into_inner();
+    let organization_service = OrganizationService::new(data.db.clone());
+
+    let member = match require_member(&organization_service, &organization_id, &user.id, true).await
+    {
+        Ok(member) => member,
+        Err(response) => return response,
+    };
+
+    if member_id == user.id {
+        return HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message: "You cannot remove yourself from the organization".to_string(),
+        });
+    }
+
+    match organization_service
+        .remove_member(&organization_id, member.role, &member_id)
+        .await
+    {
+        Ok(MemberChange::Done) => HttpResponse::Ok().json(Response {
+            status: "success",
+            message: "Member removed".to_string(),
+        }),
+        Ok(MemberChange::Forbidden) => HttpResponse::Forbidden().json(Response {
+            status: "fail",
+            message: ErrorMessage::PermissionDenied.to_string(),
+        }),
+        Ok(MemberChange::LastOwner) => last_owner_response(),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
@@ -0,0 +1,295 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde_json::json;
+use validator::Validate;
+
+use crate::{
+    dtos::{
+        global::Response,
+        role::{
+            PermissionsData, PermissionsResponseDto, RoleData, RoleDto, RoleResponseDto, RolesData,
+            RolesResponseDto, UserPermissionsDto, UserPermissionsResponseDto,
+        },
+    },
+    models::{permission, user::UserModel},
+    schemas::role::{CreateRoleSchema, UpdateRolePermissionsSchema},
+    services::permission_service::{PermissionService, RoleChange},
+    utils::extractor::Authenticated,
+    AppState,
+};
+
+/// Answer with the roles and resulting permissions of `user`.
+pub(crate) async fn user_permissions_response(data: &AppState, user: &UserModel) -> HttpResponse {
+    let permission_service = PermissionService::new(data.db.clone());
+
+    let result = async {
+        let roles = permission_service.user_roles(&user.id).await?;
+        let permissions = permission_service.user_permissions(user).await?;
+
+        Ok::<_, String>((roles, permissions))
+    }
+    .await;
+
+    match result {
+        Ok((roles, permissions)) => HttpResponse::Ok().json(UserPermissionsResponseDto {
+            status: "success".to_string(),
+            data: UserPermissionsDto {
+                role: user.role.to_str().to_string(),
+                roles,
+                permissions,
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+pub(crate) fn permission_not_held(permission: &str) -> HttpResponse {
+    HttpResponse::Forbidden().json(Response {
+        status: "fail",
+        message: format!(
+            "You can not grant the permission {}, you do not hold it yourself",
+            permission
+        ),
+    })
+}
+
+/// Answer with the role as stored after a change.
+async fn role_response(data: &AppState, name: &str, created: bool) -> HttpResponse {
+    match PermissionService::new(data.db.clone()).get_role(name).await {
+        Ok(Some((role, permissions))) => {
+            let body = RoleResponseDto {
+                status: "success".to_string(),
+                data: RoleData {
+                    role: RoleDto::filter(&role, permissions),
+                },
+            };
+            if created {
+                HttpResponse::Created().json(body)
+            } else {
+                HttpResponse::Ok().json(body)
+            }
+        }
+        Ok(None) => role_not_found(),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+fn role_not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(Response {
+        status: "fail",
+        message: "Role not found".to_string(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users/me/permissions",
+    tag = "Users Endpoint",
+    responses(
+        (status=200, description= "Roles and permissions of the account", body= UserPermissionsResponseDto ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_my_permissions_handler(
+    user: Authenticated,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    user_permissions_response(&data, &user).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/permissions",
+    tag = "Admin Endpoint",
+    responses(
+        (status=200, description= "Every permission a role can be granted", body= PermissionsResponseDto ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_permissions_handler() -> impl Responder {
+    HttpResponse::Ok().json(PermissionsResponseDto {
+        status: "success".to_string(),
+        data: PermissionsData {
+            permissions: permission::PERMISSIONS
+                .iter()
+                .map(|permission| permission.to_string())
+                .collect(),
+        },
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/roles",
+    tag = "Admin Endpoint",
+    responses(
+        (status=200, description= "Every role with its permissions", body= RolesResponseDto ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_roles_handler(data: web::Data<AppState>) -> impl Responder {
+    match PermissionService::new(data.db.clone()).list_roles().await {
+        Ok(roles) => HttpResponse::Ok().json(RolesResponseDto {
+            status: "success".to_string(),
+            data: RolesData {
+                roles: roles
+                    .into_iter()
+                    .map(|(role, permissions)| RoleDto::filter(&role, permissions))
+                    .collect(),
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/roles",
+    tag = "Admin Endpoint",
+    request_body(content = CreateRoleSchema, description = "Name and permissions of the new role", example = json!({"name": "stock-clerk", "description": "Counts stock", "permissions": ["barang.read", "inventory.write"]})),
+    responses(
+        (status=201, description= "Role created", body= RoleResponseDto ),
+        (status=400, description= "Validation Errors", body= Response),
+        (status=403, description= "The role would grant a permission the caller does not hold", body= Response),
+        (status=409, description= "A role with this name exists", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn create_role_handler(
+    admin: Authenticated,
+    body: web::Json<CreateRoleSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status": "fail",
+            "message": e,
+        }));
+    }
+
+    match PermissionService::new(data.db.clone())
+        .create_role(&admin, &body)
+        .await
+    {
+        Ok(RoleChange::NotHeld(permission)) => permission_not_held(&permission),
+        Ok(RoleChange::Exists) => HttpResponse::Conflict().json(Response {
+            status: "fail",
+            message: "A role with this name already exists".to_string(),
+        }),
+        Ok(_) => role_response(&data, &body.name, true).await,
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/admin/roles/{name}/permissions",
+    tag = "Admin Endpoint",
+    params(
+        ("name" = String, Path, description = "Role name"),
+    ),
+    request_body(content = UpdateRolePermissionsSchema, description = "Permissions replacing the current ones", example = json!({"permissions": ["barang.read", "report.read"]})),
+    responses(
+        (status=200, description= "Permissions changed, effective on the next request", body= RoleResponseDto ),
+        (status=400, description= "Validation Errors or the admin role", body= Response),
+        (status=403, description= "The role would grant a permission the caller does not hold", body= Response),
+        (status=404, description= "Role not found", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn update_role_permissions_handler(
+    admin: Authenticated,
+    path: web::Path<String>,
+    body: web::Json<UpdateRolePermissionsSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status": "fail",
+            "message": e,
+        }));
+    }
+
+    match PermissionService::new(data.db.clone())
+        .set_role_permissions(&admin, &path, &body.permissions)
+        .await
+    {
+        Ok(RoleChange::NotHeld(permission)) => permission_not_held(&permission),
+        Ok(RoleChange::NotFound) => role_not_found(),
+        Ok(RoleChange::Protected) => HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message: "The admin role always has every permission".to_string(),
+        }),
+        Ok(_) => role_response(&data, &path, false).await,
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/roles/{name}",
+    tag = "Admin Endpoint",
+    params(
+        ("name" = String, Path, description = "Role name"),
+    ),
+    responses(
+        (status=200, description= "Role deleted and taken away from its users", body= Response ),
+        (status=400, description= "Built-in roles can not be deleted", body= Response),
+        (status=404, description= "Role not found", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn delete_role_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    match PermissionService::new(data.db.clone())
+        .delete_role(&path)
+        .await
+    {
+        Ok(RoleChange::NotFound) => role_not_found(),
+        Ok(RoleChange::Protected) => HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message: "Built-in roles can not be deleted".to_string(),
+        }),
+        Ok(_) => HttpResponse::Ok().json(Response {
+            status: "success",
+            message: "Role deleted".to_string(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
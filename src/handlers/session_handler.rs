@@ -0,0 +1,109 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde_json::json;
+
+use crate::{
+    dtos::{
+        global::Response,
+        session::{SessionDto, SessionsData, SessionsResponseDto},
+    },
+    services::session_service,
+    utils::extractor::Authenticated,
+    AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/users/me/sessions",
+    tag = "Users Endpoint",
+    responses(
+        (status=200, description= "Devices signed in to the account", body= SessionsResponseDto ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_sessions_handler(
+    user: Authenticated,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    match session_service::list_sessions(&data.redis, &user.id).await {
+        Ok(sessions) => HttpResponse::Ok().json(SessionsResponseDto {
+            status: "success".to_string(),
+            data: SessionsData {
+                sessions: SessionDto::filter_iter(&sessions, &user.session_id),
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/users/me/sessions/{id}",
+    tag = "Users Endpoint",
+    params(
+        ("id" = String, Path, description = "Session id"),
+    ),
+    responses(
+        (status=200, description= "Session revoked, the device has to log in again", body= Response ),
+        (status=404, description= "Session not found", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn revoke_session_handler(
+    user: Authenticated,
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    match session_service::revoke_session(&data.redis, &user.id, &path).await {
+        Ok(true) => HttpResponse::Ok().json(Response {
+            status: "success",
+            message: "Session revoked".to_string(),
+        }),
+        Ok(false) => HttpResponse::NotFound().json(Response {
+            status: "fail",
+            message: "Session not found".to_string(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/users/me/sessions",
+    tag = "Users Endpoint",
+    responses(
+        (status=200, description= "Every other session revoked, the current device stays signed in", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn revoke_other_sessions_handler(
+    user: Authenticated,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    match session_service::revoke_other_sessions(&data.redis, &user.id, Some(&user.session_id))
+        .await
+    {
+        Ok(()) => HttpResponse::Ok().json(Response {
+            status: "success",
+            message: "Other sessions revoked".to_string(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
@@ -0,0 +1,288 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde_json::json;
+use validator::Validate;
+
+use crate::{
+    dtos::{
+        global::Response,
+        two_factor::{
+            RecoveryCodesData, RecoveryCodesResponseDto, TwoFactorSetupDto,
+            TwoFactorSetupResponseDto, TwoFactorStatusDto, TwoFactorStatusResponseDto,
+        },
+    },
+    models::user::UserRole,
+    schemas::user::{DisableTwoFactorSchema, TwoFactorCodeSchema},
+    services::two_factor_service::TwoFactorService,
+    utils::{extractor::Authenticated, password},
+    AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/users/me/2fa",
+    tag = "Users Endpoint",
+    responses(
+        (status=200, description= "Two-factor state of the account", body= TwoFactorStatusResponseDto ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn get_two_factor_handler(
+    user: Authenticated,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let two_factor_service = TwoFactorService::new(data.db.clone(), data.redis.clone());
+
+    let result = async {
+        let enabled = two_factor_service.is_enabled(&user.id).await?;
+        let recovery_codes_left = if enabled {
+            two_factor_service.recovery_codes_left(&user.id).await?
+        } else {
+            0
+        };
+
+        Ok::<_, String>((enabled, recovery_codes_left))
+    }
+    .await;
+
+    match result {
+        Ok((enabled, recovery_codes_left)) => HttpResponse::Ok().json(TwoFactorStatusResponseDto {
+            status: "success".to_string(),
+            data: TwoFactorStatusDto {
+                enabled,
+                required: data.config.require_admin_two_factor && user.role == UserRole::Admin,
+                recovery_codes_left,
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/users/me/2fa/setup",
+    tag = "Users Endpoint",
+    responses(
+        (status=200, description= "New secret, confirm it with a code to enable two-factor", body= TwoFactorSetupResponseDto ),
+        (status=409, description= "Two-factor is already enabled", body= Response ),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn setup_two_factor_handler(
+    user: Authenticated,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let two_factor_service = TwoFactorService::new(data.db.clone(), data.redis.clone());
+
+    match two_factor_service.is_enabled(&user.id).await {
+        Ok(false) => {}
+        Ok(true) => {
+            return HttpResponse::Conflict().json(Response {
+                status: "fail",
+                message: "Two-factor authentication is already enabled".to_string(),
+            })
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": format!("{:?}", e)
+            }))
+        }
+    }
+
+    match two_factor_service.begin_setup(&user, &data.config).await {
+        Ok(setup) => HttpResponse::Ok().json(TwoFactorSetupResponseDto {
+            status: "success".to_string(),
+            data: TwoFactorSetupDto {
+                secret: setup.secret,
+                otpauth_url: setup.otpauth_url,
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/users/me/2fa/enable",
+    tag = "Users Endpoint",
+    request_body(content = TwoFactorCodeSchema, description = "Code from the authenticator app", example = json!({"code": "123456"})),
+    responses(
+        (status=200, description= "Two-factor enabled, the recovery codes are only shown this once", body= RecoveryCodesResponseDto ),
+        (status=400, description= "Validation Errors, wrong code or no setup started", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn enable_two_factor_handler(
+    user: Authenticated,
+    body: web::Json<TwoFactorCodeSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    let two_factor_service = TwoFactorService::new(data.db.clone(), data.redis.clone());
+
+    match two_factor_service
+        .enable(&user, &body.code, &data.config)
+        .await
+    {
+        Ok(Some(recovery_codes)) => HttpResponse::Ok().json(RecoveryCodesResponseDto {
+            status: "success".to_string(),
+            data: RecoveryCodesData { recovery_codes },
+        }),
+        Ok(None) => HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message: "Code is wrong or two-factor setup was not started".to_string(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/users/me/2fa",
+    tag = "Users Endpoint",
+    request_body(content = DisableTwoFactorSchema, description = "Current password and a TOTP or recovery code", example = json!({"current_password": "user1","code": "123456"})),
+    responses(
+        (status=200, description= "Two-factor disabled", body= Response ),
+        (status=400, description= "Validation Errors, wrong password or code", body= Response),
+        (status=403, description= "Two-factor is required for admins", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn disable_two_factor_handler(
+    user: Authenticated,
+    body: web::Json<DisableTwoFactorSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    if data.config.require_admin_two_factor && user.role == UserRole::Admin {
+        return HttpResponse::Forbidden().json(Response {
+            status: "fail",
+            message: "Two-factor authentication is required for admins".to_string(),
+        });
+    }
+
+    let two_factor_service = TwoFactorService::new(data.db.clone(), data.redis.clone());
+
+    let result = async {
+        if !password::compare(&body.current_password, &user.password)? {
+            return Ok(false);
+        }
+        if !two_factor_service
+            .verify(&user, &body.code, &data.config)
+            .await?
+        {
+            return Ok(false);
+        }
+
+        two_factor_service.disable(&user.id).await?;
+        Ok::<_, String>(true)
+    }
+    .await;
+
+    match result {
+        Ok(true) => HttpResponse::Ok().json(Response {
+            status: "success",
+            message: "Two-factor authentication disabled".to_string(),
+        }),
+        Ok(false) => HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message: "Current password or code is wrong".to_string(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/users/me/2fa/recovery-codes",
+    tag = "Users Endpoint",
+    request_body(content = TwoFactorCodeSchema, description = "Current TOTP or recovery code", example = json!({"code": "123456"})),
+    responses(
+        (status=200, description= "New recovery codes, the previous ones no longer work", body= RecoveryCodesResponseDto ),
+        (status=400, description= "Validation Errors or wrong code", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn regenerate_recovery_codes_handler(
+    user: Authenticated,
+    body: web::Json<TwoFactorCodeSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    let two_factor_service = TwoFactorService::new(data.db.clone(), data.redis.clone());
+
+    let result = async {
+        if !two_factor_service
+            .verify(&user, &body.code, &data.config)
+            .await?
+        {
+            return Ok(None);
+        }
+
+        two_factor_service
+            .regenerate_recovery_codes(&user.id, &data.config)
+            .await
+            .map(Some)
+    }
+    .await;
+
+    match result {
+        Ok(Some(recovery_codes)) => HttpResponse::Ok().json(RecoveryCodesResponseDto {
+            status: "success".to_string(),
+            data: RecoveryCodesData { recovery_codes },
+        }),
+        Ok(None) => HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message: "Code is wrong".to_string(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
@@ -3,13 +3,22 @@ use crate::{
         global::Response,
         user::{UserData, UserDto, UserResponseDto},
     },
-    services::user_services::UserService,
-    utils::extractor::Authenticated,
+    schemas::user::{
+        ChangeEmailSchema, ChangePasswordSchema, DeleteAccountSchema, UpdateProfileSchema,
+    },
+    services::{
+        account_service::{AccountService, DeleteOutcome},
+        session_service,
+        user_services::UserService,
+        verification_service::VerificationService,
+    },
+    utils::{extractor::Authenticated, password},
     AppState,
 };
 use actix_multipart::Multipart;
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{http::header::ContentDisposition, web, HttpResponse, Responder};
 use serde_json::json;
+use validator::Validate;
 
 #[utoipa::path(
     get,
@@ -33,6 +42,59 @@ pub async fn get_me_handler(user: Authenticated) -> impl Responder {
     HttpResponse::Ok().json(response_data)
 }
 
+#[utoipa::path(
+    patch,
+    path = "/api/users/me",
+    tag = "Users Endpoint",
+    request_body(content = UpdateProfileSchema, description = "Profile fields to change", example = json!({"name": "Budi", "phone": "+62 812 3456 7890", "address": "Jl. Merdeka 1, Bandung", "preferredLanguage": "id"})),
+    responses(
+        (status=200, description= "Profile updated", body= UserResponseDto ),
+        (status=400, description= "Validation Errors", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn update_me_handler(
+    user: Authenticated,
+    body: web::Json<UpdateProfileSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    let user_service = UserService::new(data.db.clone());
+
+    let result = async {
+        user_service.update_profile(&user.id, &body).await?;
+
+        user_service
+            .get_user(Some(&user.id), None, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("User no longer exists".to_string())
+    }
+    .await;
+
+    match result {
+        Ok(user) => HttpResponse::Ok().json(UserResponseDto {
+            status: "success".to_string(),
+            data: UserData {
+                user: UserDto::filter(&user),
+            },
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
 pub async fn update_photo_handler(
     user: Authenticated,
     payload: Multipart,
@@ -62,3 +124,249 @@ pub async fn update_photo_handler(
         message: "User updated".to_string(),
     });
 }
+
+#[utoipa::path(
+    patch,
+    path = "/api/users/me/password",
+    tag = "Users Endpoint",
+    request_body(content = ChangePasswordSchema, description = "Current and new password", example = json!({"current_password": "user1","password": "new-secret","passwordConfirm": "new-secret"})),
+    responses(
+        (status=200, description= "Password changed, other sessions are logged out", body= Response ),
+        (status=400, description= "Validation Errors or the current password is wrong", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn change_password_handler(
+    user: Authenticated,
+    body: web::Json<ChangePasswordSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    if let Err(message) = password::check_policy(&body.password, &data.config) {
+        return HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message,
+        });
+    }
+
+    let user_service = UserService::new(data.db.clone());
+
+    match user_service
+        .change_password(&user, &body.current_password, &body.password, &data.config)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::BadRequest().json(Response {
+                status: "fail",
+                message: "Current password is wrong".to_string(),
+            })
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": format!("{:?}", e)
+            }))
+        }
+    }
+
+    if let Err(e) =
+        session_service::revoke_other_sessions(&data.redis, &user.id, Some(&user.session_id)).await
+    {
+        return HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        }));
+    }
+
+    HttpResponse::Ok().json(Response {
+        status: "success",
+        message: "Password changed, other sessions have been logged out".to_string(),
+    })
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/users/me/email",
+    tag = "Users Endpoint",
+    request_body(content = ChangeEmailSchema, description = "Current password and the new email", example = json!({"current_password": "user1","email": "new@mail.com"})),
+    responses(
+        (status=200, description= "Email changed, a verification link is sent to the new address", body= UserResponseDto ),
+        (status=400, description= "Validation Errors or the current password is wrong", body= Response),
+        (status=409, description= "Email is used by another account", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn change_email_handler(
+    user: Authenticated,
+    body: web::Json<ChangeEmailSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    let user_service = UserService::new(data.db.clone());
+
+    match user_service
+        .change_email(&user, &body.current_password, &body.email)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::BadRequest().json(Response {
+                status: "fail",
+                message: "Current password is wrong".to_string(),
+            })
+        }
+        Err(e) if e.contains("Duplicate entry") => {
+            return HttpResponse::Conflict().json(Response {
+                status: "fail",
+                message: "User with that email already exists".to_string(),
+            })
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": format!("{:?}", e)
+            }))
+        }
+    }
+
+    let updated = match user_service.get_user(Some(&user.id), None, None).await {
+        Ok(Some(updated)) => updated,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(Response {
+                status: "fail",
+                message: "User not found".to_string(),
+            })
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(json!({
+                "status": "error",
+                "message": format!("{:?}", e)
+            }))
+        }
+    };
+
+    let verification_service =
+        VerificationService::new(data.db.clone(), data.redis.clone(), data.mailer.clone());
+
+    if let Err(e) = verification_service
+        .send_verification(&updated, &data.config)
+        .await
+    {
+        eprintln!("🔥 Failed to send verification email: {}", e);
+    }
+
+    HttpResponse::Ok().json(UserResponseDto {
+        status: "success".to_string(),
+        data: UserData {
+            user: UserDto::filter(&updated),
+        },
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users/me/export",
+    tag = "Users Endpoint",
+    responses(
+        (status=200, description= "Tar archive of profile, organizations, sessions, login history, API keys, stock movements and photo", content_type = "application/x-tar"),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn export_me_handler(user: Authenticated, data: web::Data<AppState>) -> impl Responder {
+    let account_service = AccountService::new(data.db.clone(), data.redis.clone());
+
+    match account_service
+        .export(&user, &user.session_id, &data.config)
+        .await
+    {
+        Ok(archive) => HttpResponse::Ok()
+            .content_type("application/x-tar")
+            .insert_header(ContentDisposition::attachment("account-export.tar"))
+            .body(archive),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/users/me",
+    tag = "Users Endpoint",
+    request_body(content = DeleteAccountSchema, description = "Current password confirming the deletion", example = json!({"current_password": "user1"})),
+    responses(
+        (status=200, description= "Account deleted and every session logged out", body= Response ),
+        (status=400, description= "Validation Errors or the current password is wrong", body= Response),
+        (status=409, description= "Last admin, or sole owner of organizations with other members", body= Response),
+        (status=500, description= "Internal Server Error", body= Response ),
+    ),
+    security(
+       ("token" = [])
+   )
+)]
+pub async fn delete_me_handler(
+    user: Authenticated,
+    body: web::Json<DeleteAccountSchema>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "status":"fail",
+            "message": e,
+        }));
+    }
+
+    let account_service = AccountService::new(data.db.clone(), data.redis.clone());
+
+    match account_service
+        .delete(&user, &body.current_password, &data.config)
+        .await
+    {
+        Ok(DeleteOutcome::Deleted) => HttpResponse::Ok().json(Response {
+            status: "success",
+            message: "Account deleted".to_string(),
+        }),
+        Ok(DeleteOutcome::WrongPassword) => HttpResponse::BadRequest().json(Response {
+            status: "fail",
+            message: "Current password is wrong".to_string(),
+        }),
+        Ok(DeleteOutcome::LastAdmin) => HttpResponse::Conflict().json(Response {
+            status: "fail",
+            message: "The last admin account can not be deleted".to_string(),
+        }),
+        Ok(DeleteOutcome::OwnsOrganizations(names)) => HttpResponse::Conflict().json(Response {
+            status: "fail",
+            message: format!(
+                "Transfer ownership or remove the other members first: {}",
+                names.join(", ")
+            ),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({
+            "status": "error",
+            "message": format!("{:?}", e)
+        })),
+    }
+}
@@ -1,6 +1,8 @@
-use redis::Client;
+use std::sync::Arc;
+
+use redis::aio::ConnectionManager;
 use sqlx::MySqlPool;
-use utils::config::Config;
+use utils::{config::Config, mailer::Mailer};
 
 pub mod dtos;
 pub mod handlers;
@@ -14,5 +16,7 @@ pub mod utils;
 pub struct AppState {
     pub db: MySqlPool,
     pub config: Config,
-    pub redis_client: Client,
+    /// Shared multiplexed connection for the Redis lookups of every request.
+    pub redis: ConnectionManager,
+    pub mailer: Arc<dyn Mailer>,
 }
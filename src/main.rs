@@ -1,25 +1,79 @@
 use actix_cors::Cors;
 use actix_web::{http::header, middleware::Logger, web, App, HttpResponse, HttpServer, Responder};
 use dotenv::dotenv;
-use redis::Client;
+use redis::{aio::ConnectionManager, Client};
 use rust_flutter_application::{
     dtos::{
-        barang::{BarangData, BarangDto, BarangResponseDto, BarangsData, BarangsResponseDto},
+        api_key::{
+            ApiKeyDto, ApiKeysData, ApiKeysResponseDto, CreatedApiKeyData, CreatedApiKeyResponseDto,
+        },
+        barang::{
+            BarangConflictDto, BarangConflictsData, BarangConflictsResponseDto, BarangData,
+            BarangDto, BarangResponseDto, BarangsData, BarangsResponseDto, BulkBarangChangeDto,
+            BulkBarangData, BulkBarangResponseDto, SyncConflictStatus,
+        },
+        dashboard::{ActivityDto, DashboardData, DashboardResponseDto, DashboardSummaryDto},
         global::Response,
-        token::TokenData,
-        user::{UserData, UserDto, UserLoginResponseDto, UserRegisterResponseDto, UserResponseDto},
+        inventory::{
+            InventoryValuationData, InventoryValuationDto, InventoryValuationResponseDto,
+            ItemValuationDto,
+        },
+        login_event::{LoginEventDto, LoginEventsData, LoginEventsResponseDto},
+        organization::{
+            MemberDto, MembersData, MembersResponseDto, OrganizationData, OrganizationDto,
+            OrganizationResponseDto, OrganizationsData, OrganizationsResponseDto, StoreData,
+            StoreDto, StoreResponseDto, StoresData, StoresResponseDto,
+        },
+        role::{
+            PermissionsData, PermissionsResponseDto, RoleData, RoleDto, RoleResponseDto,
+            RolesData, RolesResponseDto, UserPermissionsDto, UserPermissionsResponseDto,
+        },
+        session::{SessionDto, SessionsData, SessionsResponseDto},
+        token::{CsrfTokenData, CsrfTokenResponseDto, JwkDto, JwksDto, TokenData},
+        two_factor::{
+            RecoveryCodesData, RecoveryCodesResponseDto, TwoFactorChallengeData,
+            TwoFactorChallengeResponseDto, TwoFactorSetupDto, TwoFactorSetupResponseDto,
+            TwoFactorStatusDto, TwoFactorStatusResponseDto,
+        },
+        user::{
+            UserData, UserDto, UserLoginResponseDto, UserRegisterResponseDto, UserResponseDto,
+            UsersData, UsersResponseDto,
+        },
     },
     handlers,
-    models::user::UserRole,
+    models::{
+        api_key::ApiKeyScope,
+        inventory::{MovementType, ValuationMethod},
+        login_event::LoginOutcome,
+        organization::OrganizationRole,
+        user::UserRole,
+    },
     routes::{
-        auth::auth_config, barang::barang_config, pdf::pdf_config, storage::storage_config,
-        user::user_config,
+        admin::admin_config, auth::auth_config, barang::barang_config, dashboard::dashboard_config,
+        organization::organization_config, pdf::pdf_config, report::report_config,
+        storage::storage_config, user::user_config, well_known::well_known_config,
     },
     schemas::{
-        auth::{LoginUserSchema, RegisterUserSchema},
-        barang::{InsertBarangSchema, SyncBarangSchema},
+        admin::{SetUserDisabledSchema, SetUserVerifiedSchema, UpdateUserRoleSchema},
+        api_key::CreateApiKeySchema,
+        auth::{
+            ForgotPasswordSchema, LoginUserSchema, RefreshTokenSchema, RegisterUserSchema,
+            ResendVerificationSchema, ResetPasswordSchema, TwoFactorLoginSchema, VerifyEmailSchema,
+        },
+        barang::{
+            BarangSelectorSchema, BulkCategorySchema, BulkDeleteSchema, BulkPriceSchema,
+            InsertBarangSchema, PriceAdjustmentMode, PriceRoundingSchema, RoundingMode,
+            SyncBarangItemSchema, SyncBarangSchema, TagsMode, UpdateBarangSchema,
+        },
+        inventory::{StockInSchema, StockOutSchema},
+        organization::{AddMemberSchema, CreateOrganizationSchema, CreateStoreSchema},
+        role::{CreateRoleSchema, SetUserRolesSchema, UpdateRolePermissionsSchema},
+        user::{
+            ChangeEmailSchema, ChangePasswordSchema, DeleteAccountSchema, DisableTwoFactorSchema,
+            TwoFactorCodeSchema, UpdateProfileSchema,
+        },
     },
-    utils::config::Config,
+    utils::{config::Config, mailer},
     AppState,
 };
 use sqlx::mysql::MySqlPoolOptions;
@@ -33,23 +87,49 @@ use utoipa_swagger_ui::SwaggerUi;
 #[openapi(
     paths(
         health_checker_handler,
-        handlers::auth_handler::logout_user_handler,handlers::auth_handler::login_user_handler,handlers::auth_handler::register_user_handler,
-        handlers::user_handler::get_me_handler,
-        handlers::barang_handler::insert_barang_handler,handlers::barang_handler::get_barang_handler,handlers::barang_handler::sync_barang_handler
+        handlers::auth_handler::logout_user_handler,handlers::auth_handler::refresh_token_handler,handlers::auth_handler::login_user_handler,handlers::auth_handler::login_two_factor_handler,handlers::auth_handler::register_user_handler,handlers::auth_handler::verify_email_page_handler,handlers::auth_handler::verify_email_handler,handlers::auth_handler::resend_verification_handler,handlers::auth_handler::forgot_password_handler,handlers::auth_handler::reset_password_handler,handlers::auth_handler::get_csrf_token_handler,handlers::oidc_handler::oidc_login_handler,handlers::oidc_handler::oidc_callback_handler,handlers::jwks_handler::get_jwks_handler,
+        handlers::user_handler::get_me_handler,handlers::user_handler::update_me_handler,handlers::user_handler::change_password_handler,handlers::user_handler::change_email_handler,handlers::user_handler::export_me_handler,handlers::user_handler::delete_me_handler,
+        handlers::session_handler::get_sessions_handler,handlers::session_handler::revoke_session_handler,handlers::session_handler::revoke_other_sessions_handler,handlers::login_event_handler::get_my_logins_handler,
+        handlers::two_factor_handler::get_two_factor_handler,handlers::two_factor_handler::setup_two_factor_handler,handlers::two_factor_handler::enable_two_factor_handler,handlers::two_factor_handler::disable_two_factor_handler,handlers::two_factor_handler::regenerate_recovery_codes_handler,
+        handlers::api_key_handler::get_api_keys_handler,handlers::api_key_handler::create_api_key_handler,handlers::api_key_handler::revoke_api_key_handler,
+        handlers::barang_handler::insert_barang_handler,handlers::barang_handler::get_barang_handler,handlers::barang_handler::sync_barang_handler,handlers::barang_handler::get_barang_by_id_handler,handlers::barang_handler::update_barang_handler,handlers::barang_handler::delete_barang_handler,handlers::barang_handler::bulk_price_barang_handler,handlers::barang_handler::bulk_category_barang_handler,handlers::barang_handler::bulk_delete_barang_handler,
+        handlers::organization_handler::get_organizations_handler,handlers::organization_handler::create_organization_handler,handlers::organization_handler::get_stores_handler,handlers::organization_handler::create_store_handler,handlers::organization_handler::get_members_handler,handlers::organization_handler::add_member_handler,handlers::organization_handler::remove_member_handler,
+        handlers::inventory_handler::stock_in_handler,handlers::inventory_handler::stock_out_handler,handlers::inventory_handler::get_inventory_valuation_handler,handlers::inventory_handler::get_inventory_valuation_pdf_handler,
+        handlers::dashboard_handler::get_dashboard_handler,
+        handlers::admin_handler::get_users_handler,handlers::admin_handler::get_user_handler,handlers::admin_handler::update_user_role_handler,handlers::admin_handler::set_user_verified_handler,handlers::admin_handler::set_user_disabled_handler,handlers::admin_handler::reset_user_password_handler,handlers::admin_handler::unlock_user_handler,handlers::admin_handler::get_user_permissions_handler,handlers::admin_handler::set_user_roles_handler,handlers::admin_handler::get_user_logins_handler,
+        handlers::role_handler::get_my_permissions_handler,handlers::role_handler::get_permissions_handler,handlers::role_handler::get_roles_handler,handlers::role_handler::create_role_handler,handlers::role_handler::update_role_permissions_handler,handlers::role_handler::delete_role_handler
     ),
     components(
         schemas(
-            Response,UserRole,
+            Response,UserRole,JwkDto,JwksDto,CsrfTokenData,CsrfTokenResponseDto,
             UserDto,BarangDto,
             UserData,TokenData,BarangsData,BarangData,
-            UserResponseDto,UserLoginResponseDto,BarangsResponseDto,BarangResponseDto,UserRegisterResponseDto,
-            LoginUserSchema,RegisterUserSchema,SyncBarangSchema,InsertBarangSchema
+            UserResponseDto,UsersData,UsersResponseDto,UserLoginResponseDto,BarangsResponseDto,BarangResponseDto,UserRegisterResponseDto,
+            LoginUserSchema,RegisterUserSchema,RefreshTokenSchema,ResendVerificationSchema,ForgotPasswordSchema,ResetPasswordSchema,VerifyEmailSchema,ChangePasswordSchema,ChangeEmailSchema,UpdateProfileSchema,DeleteAccountSchema,SyncBarangSchema,SyncBarangItemSchema,InsertBarangSchema,UpdateBarangSchema,BarangConflictsResponseDto,BarangConflictsData,BarangConflictDto,SyncConflictStatus,
+            BarangSelectorSchema,PriceAdjustmentMode,RoundingMode,PriceRoundingSchema,BulkPriceSchema,TagsMode,BulkCategorySchema,BulkDeleteSchema,BulkBarangChangeDto,BulkBarangData,BulkBarangResponseDto,
+            OrganizationRole,OrganizationDto,StoreDto,MemberDto,
+            OrganizationsData,OrganizationData,StoresData,StoreData,MembersData,
+            OrganizationsResponseDto,OrganizationResponseDto,StoresResponseDto,StoreResponseDto,MembersResponseDto,
+            CreateOrganizationSchema,CreateStoreSchema,AddMemberSchema,
+            MovementType,ValuationMethod,ItemValuationDto,InventoryValuationDto,InventoryValuationData,InventoryValuationResponseDto,
+            StockInSchema,StockOutSchema,
+            DashboardSummaryDto,ActivityDto,DashboardData,DashboardResponseDto,
+            SessionDto,SessionsData,SessionsResponseDto,
+            LoginOutcome,LoginEventDto,LoginEventsData,LoginEventsResponseDto,
+            TwoFactorStatusDto,TwoFactorStatusResponseDto,TwoFactorSetupDto,TwoFactorSetupResponseDto,RecoveryCodesData,RecoveryCodesResponseDto,TwoFactorChallengeData,TwoFactorChallengeResponseDto,TwoFactorLoginSchema,TwoFactorCodeSchema,DisableTwoFactorSchema,
+            ApiKeyScope,ApiKeyDto,ApiKeysData,ApiKeysResponseDto,CreatedApiKeyData,CreatedApiKeyResponseDto,CreateApiKeySchema,
+            UpdateUserRoleSchema,SetUserVerifiedSchema,SetUserDisabledSchema,
+            RoleDto,RolesData,RolesResponseDto,RoleData,RoleResponseDto,PermissionsData,PermissionsResponseDto,UserPermissionsDto,UserPermissionsResponseDto,CreateRoleSchema,UpdateRolePermissionsSchema,SetUserRolesSchema
         ),
     ),
     tags(
         (name = "Authentication Endpoint", description = "Handle authentication"),
         (name = "Users Endpoint", description = "Handle user"),
         (name = "Barang Endpoint", description = "Handle barang"),
+        (name = "Organizations Endpoint", description = "Handle organizations, stores and members"),
+        (name = "Inventory Endpoint", description = "Handle stock movements and inventory valuation"),
+        (name = "Dashboard Endpoint", description = "Handle home screen summary"),
+        (name = "Admin Endpoint", description = "Handle administration of users, roles and permissions"),
     ),
     modifiers(&SecurityAddon)
 )]
@@ -113,6 +193,17 @@ async fn main() -> std::io::Result<()> {
         }
     };
 
+    // one multiplexed connection shared by every worker, reconnects on its own
+    let redis = match ConnectionManager::new(redis_client).await {
+        Ok(redis) => redis,
+        Err(e) => {
+            println!("🔥 Error connecting to Redis: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let mailer = mailer::from_config(&config);
+
     // run migration
     match sqlx::migrate!("./migrations").run(&pool).await {
         Ok(_) => println!("✅ Migrations executed successfully."),
@@ -130,30 +221,54 @@ async fn main() -> std::io::Result<()> {
     // setup server
     let server = HttpServer::new(move || {
         // configure cors
-        let cors = Cors::default()
-            // .allowed_origin("http://localhost:3000")
-            .allow_any_origin()
+        let mut cors = Cors::default()
             .allowed_methods(vec!["GET", "POST", "PATCH", "DELETE"])
             .allowed_headers(vec![
                 header::CONTENT_TYPE,
                 header::AUTHORIZATION,
                 header::ACCEPT,
+                header::HeaderName::from_static("x-organization-id"),
+                header::HeaderName::from_static("x-store-id"),
+                header::IF_MATCH,
+                header::HeaderName::from_static("x-device-name"),
+                header::HeaderName::from_static("x-csrf-token"),
             ])
-            .supports_credentials();
+            .expose_headers(vec![
+                header::ETAG,
+                header::HeaderName::from_static("x-csrf-token"),
+            ]);
+
+        // Cookies only travel to known web apps. Without any configured,
+        // every origin may call the API with a Bearer token but no cookies.
+        let allowed_origins = config.allowed_origins();
+        if allowed_origins.is_empty() {
+            cors = cors.allow_any_origin();
+        } else {
+            for origin in &allowed_origins {
+                cors = cors.allowed_origin(origin);
+            }
+            cors = cors.supports_credentials();
+        }
 
         App::new()
             .app_data(web::Data::new(AppState {
                 db: pool.clone(),
                 config: config.clone(),
-                redis_client: redis_client.clone(),
+                redis: redis.clone(),
+                mailer: mailer.clone(),
             }))
             .wrap(cors)
             .wrap(Logger::default())
             .configure(auth_config)
             .configure(user_config)
             .configure(barang_config)
+            .configure(organization_config)
+            .configure(report_config)
+            .configure(dashboard_config)
             .configure(storage_config)
             .configure(pdf_config)
+            .configure(admin_config)
+            .configure(well_known_config)
             .route("", web::get().to(health_checker_handler))
             .service(
                 SwaggerUi::new("/swagger-ui/{_:.*}").url("/api-docs/openapi.json", openapi.clone()),
@@ -0,0 +1,66 @@
+use actix_web::http::Method;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyScope {
+    Read,
+    Write,
+}
+
+impl ApiKeyScope {
+    pub fn to_str(&self) -> &str {
+        match self {
+            ApiKeyScope::Read => "read",
+            ApiKeyScope::Write => "write",
+        }
+    }
+
+    /// Read keys may only fetch, write keys may also change data.
+    pub fn allows(&self, method: &Method) -> bool {
+        match self {
+            ApiKeyScope::Read => {
+                matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+            }
+            ApiKeyScope::Write => true,
+        }
+    }
+}
+
+/// A personal access token. Only the SHA-256 of the secret is stored, the
+/// prefix identifies the key in lists and logs.
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct ApiKeyModel {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: serde_json::Value,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ApiKeyModel {
+    /// Scopes are stored as a JSON array, unknown entries are ignored.
+    pub fn scopes(&self) -> Vec<ApiKeyScope> {
+        match &self.scopes {
+            serde_json::Value::Array(scopes) => scopes
+                .iter()
+                .filter_map(|scope| serde_json::from_value(scope.clone()).ok())
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    pub fn allows(&self, method: &Method) -> bool {
+        self.scopes().iter().any(|scope| scope.allows(method))
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= chrono::Utc::now())
+    }
+}
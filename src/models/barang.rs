@@ -1,30 +1,53 @@
 use chrono::NaiveDate;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-use crate::dtos::barang::BarangDto;
+use crate::{dtos::barang::BarangDto, utils::money::Money};
 
 #[derive(Debug, Deserialize, sqlx::FromRow, sqlx::Type, Serialize, Clone)]
 pub struct BarangModel {
     pub id: String,
+    pub organization_id: String,
+    pub store_id: Option<String>,
     pub name: String,
-    pub price: i32,
+    pub category: Option<String>,
+    pub tags: Option<serde_json::Value>,
+    pub price: Decimal,
     pub stock: i32,
     // #[serde(rename = "expiredAt")]
     pub expired_at: Option<NaiveDate>,
+    pub version: i32,
     // #[serde(rename = "createdAt")]
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     // #[serde(rename = "updatedAt")]
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// Tags are stored as a JSON array of strings, anything else reads as empty.
+pub fn tags_from_json(tags: Option<&serde_json::Value>) -> Vec<String> {
+    match tags {
+        Some(serde_json::Value::Array(tags)) => tags
+            .iter()
+            .filter_map(|tag| tag.as_str().map(str::to_string))
+            .collect(),
+        _ => vec![],
+    }
+}
+
 impl Into<BarangDto> for BarangModel {
     fn into(self) -> BarangDto {
         BarangDto {
             id: self.id,
+            organization_id: self.organization_id,
+            store_id: self.store_id,
             name: self.name,
-            price: self.price,
+            category: self.category,
+            tags: tags_from_json(self.tags.as_ref()),
+            price: Money::from(self.price),
+            price_display: Money::from(self.price).to_rupiah(),
             stock: self.stock,
             expired_at: self.expired_at,
+            version: self.version,
             created_at: self.created_at,
             updated_at: self.updated_at,
         }
@@ -0,0 +1,31 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::inventory::MovementType;
+
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct BarangSummaryModel {
+    pub item_count: i64,
+    pub low_stock_count: i64,
+    pub expiring_soon_count: i64,
+}
+
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct SalesSummaryModel {
+    pub sales_count: i64,
+    pub sales_total: Decimal,
+}
+
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct ActivityModel {
+    pub id: String,
+    pub barang_id: String,
+    pub barang_name: String,
+    pub movement_type: MovementType,
+    pub quantity: i32,
+    pub unit_cost: Option<Decimal>,
+    pub unit_price: Option<Decimal>,
+    pub note: Option<String>,
+    pub user_name: Option<String>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// An account at an external OpenID Connect provider linked to a user. The
+/// provider names it by `issuer` and `subject`, the email is informational.
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct UserIdentityModel {
+    pub id: String,
+    pub user_id: String,
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub last_login_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Who the provider says signed in, taken from a verified ID token.
+#[derive(Debug, Clone)]
+pub struct ExternalIdentity {
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+}
@@ -0,0 +1,97 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, sqlx::Type, PartialEq, ToSchema)]
+#[sqlx(type_name = "movement_type", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum MovementType {
+    In,
+    Out,
+}
+
+impl MovementType {
+    pub fn to_str(&self) -> &str {
+        match self {
+            MovementType::In => "in",
+            MovementType::Out => "out",
+        }
+    }
+}
+
+impl From<String> for MovementType {
+    fn from(s: String) -> Self {
+        match s.to_lowercase().as_str() {
+            "in" => MovementType::In,
+            _ => MovementType::Out,
+        }
+    }
+}
+
+/// What became of a stock movement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MovementOutcome {
+    Recorded,
+    /// The barang does not exist in the organization.
+    NotFound,
+    /// Taking the quantity out would make the stock negative.
+    InsufficientStock,
+}
+
+/// Costing method used to value stock on hand and cost of goods sold.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ValuationMethod {
+    Fifo,
+    Average,
+}
+
+impl ValuationMethod {
+    pub fn to_str(&self) -> &str {
+        match self {
+            ValuationMethod::Fifo => "fifo",
+            ValuationMethod::Average => "average",
+        }
+    }
+}
+
+impl From<String> for ValuationMethod {
+    fn from(s: String) -> Self {
+        match s.to_lowercase().as_str() {
+            "average" | "avg" | "moving_average" => ValuationMethod::Average,
+            _ => ValuationMethod::Fifo,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct StockMovementModel {
+    pub id: String,
+    pub organization_id: String,
+    pub barang_id: String,
+    pub movement_type: MovementType,
+    pub quantity: i32,
+    pub unit_cost: Option<Decimal>,
+    pub unit_price: Option<Decimal>,
+    pub note: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Movement joined with the name of its barang, ordered for valuation.
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct ValuationMovementModel {
+    pub barang_id: String,
+    pub name: String,
+    pub movement_type: MovementType,
+    pub quantity: i32,
+    pub unit_cost: Option<Decimal>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ValuationMovementModel {
+    pub fn date(&self) -> Option<NaiveDate> {
+        self.created_at.map(|created_at| created_at.date_naive())
+    }
+}
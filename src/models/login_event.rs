@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, sqlx::Type, PartialEq, ToSchema)]
+#[sqlx(type_name = "login_outcome", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum LoginOutcome {
+    Success,
+    Failure,
+}
+
+impl LoginOutcome {
+    pub fn to_str(&self) -> &str {
+        match self {
+            LoginOutcome::Success => "success",
+            LoginOutcome::Failure => "failure",
+        }
+    }
+}
+
+/// Why a login attempt was turned away.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoginFailureReason {
+    UnknownEmail,
+    WrongPassword,
+    AccountDisabled,
+    EmailNotVerified,
+    RateLimited,
+    WrongTwoFactorCode,
+    TwoFactorExpired,
+    /// OpenID Connect login the provider allowed but the API turned away.
+    ExternalLoginRejected,
+}
+
+impl LoginFailureReason {
+    pub fn to_str(&self) -> &str {
+        match self {
+            LoginFailureReason::UnknownEmail => "unknown_email",
+            LoginFailureReason::WrongPassword => "wrong_password",
+            LoginFailureReason::AccountDisabled => "account_disabled",
+            LoginFailureReason::EmailNotVerified => "email_not_verified",
+            LoginFailureReason::RateLimited => "rate_limited",
+            LoginFailureReason::WrongTwoFactorCode => "wrong_two_factor_code",
+            LoginFailureReason::TwoFactorExpired => "two_factor_expired",
+            LoginFailureReason::ExternalLoginRejected => "external_login_rejected",
+        }
+    }
+}
+
+/// One login attempt. `user_id` is empty when the email matched no account,
+/// `fingerprint` is the SHA-256 of the device name and user agent.
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct LoginEventModel {
+    pub id: String,
+    pub user_id: Option<String>,
+    pub email: String,
+    pub outcome: LoginOutcome,
+    pub reason: Option<String>,
+    pub ip_address: String,
+    pub user_agent: String,
+    pub device_name: String,
+    pub fingerprint: String,
+    pub new_device: i8,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl LoginEventModel {
+    pub fn is_new_device(&self) -> bool {
+        self.new_device != 0
+    }
+}
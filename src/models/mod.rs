@@ -1,3 +1,12 @@
+pub mod api_key;
 pub mod barang;
+pub mod dashboard;
+pub mod identity;
+pub mod inventory;
+pub mod login_event;
+pub mod organization;
+pub mod permission;
+pub mod session;
 pub mod token;
+pub mod two_factor;
 pub mod user;
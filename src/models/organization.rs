@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, sqlx::Type, PartialEq, ToSchema)]
+#[sqlx(type_name = "organization_role", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum OrganizationRole {
+    Owner,
+    Admin,
+    Member,
+}
+
+impl OrganizationRole {
+    pub fn to_str(&self) -> &str {
+        match self {
+            OrganizationRole::Owner => "owner",
+            OrganizationRole::Admin => "admin",
+            OrganizationRole::Member => "member",
+        }
+    }
+
+    /// Owners and admins may manage stores and members of the organization,
+    /// and delete or bulk change its barang. Members may add and edit single
+    /// barang and record stock movements.
+    pub fn can_manage(&self) -> bool {
+        matches!(self, OrganizationRole::Owner | OrganizationRole::Admin)
+    }
+}
+
+impl From<String> for OrganizationRole {
+    fn from(s: String) -> Self {
+        match s.to_lowercase().as_str() {
+            "owner" => OrganizationRole::Owner,
+            "admin" => OrganizationRole::Admin,
+            "member" => OrganizationRole::Member,
+            _ => OrganizationRole::Member,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct OrganizationModel {
+    pub id: String,
+    pub name: String,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct StoreModel {
+    pub id: String,
+    pub organization_id: String,
+    pub name: String,
+    pub address: Option<String>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct OrganizationMemberModel {
+    pub organization_id: String,
+    pub user_id: String,
+    pub role: OrganizationRole,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Membership joined with the organization it belongs to, used when listing
+/// the organizations of a user.
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct UserOrganizationModel {
+    pub id: String,
+    pub name: String,
+    pub role: OrganizationRole,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+pub const BARANG_READ: &str = "barang.read";
+pub const BARANG_WRITE: &str = "barang.write";
+pub const BARANG_DELETE: &str = "barang.delete";
+pub const INVENTORY_WRITE: &str = "inventory.write";
+pub const DASHBOARD_READ: &str = "dashboard.read";
+pub const ORGANIZATION_READ: &str = "organization.read";
+pub const ORGANIZATION_WRITE: &str = "organization.write";
+pub const REPORT_READ: &str = "report.read";
+pub const USERS_MANAGE: &str = "users.manage";
+pub const ROLES_MANAGE: &str = "roles.manage";
+
+/// Granting this gives a role every permission, including ones added later.
+pub const ALL: &str = "*";
+
+/// Every permission a route checks. Roles can only be granted these.
+pub const PERMISSIONS: &[&str] = &[
+    BARANG_READ,
+    BARANG_WRITE,
+    BARANG_DELETE,
+    INVENTORY_WRITE,
+    DASHBOARD_READ,
+    ORGANIZATION_READ,
+    ORGANIZATION_WRITE,
+    REPORT_READ,
+    USERS_MANAGE,
+    ROLES_MANAGE,
+];
+
+pub fn is_known(permission: &str) -> bool {
+    permission == ALL || PERMISSIONS.contains(&permission)
+}
+
+/// A named set of permissions. Built-in roles match the values of
+/// `users.role`, which every user has one of, and can not be deleted.
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct RoleModel {
+    pub name: String,
+    pub description: Option<String>,
+    pub built_in: i8,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl RoleModel {
+    pub fn is_built_in(&self) -> bool {
+        self.built_in != 0
+    }
+}
+
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct RolePermissionModel {
+    pub role: String,
+    pub permission: String,
+}
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A signed in device, stored as a Redis hash rather than a table row.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SessionModel {
+    pub id: String,
+    pub user_id: String,
+    pub device_name: String,
+    pub ip_address: String,
+    pub user_agent: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+}
+
+impl SessionModel {
+    /// Build a session from its Redis hash, `None` when it expired.
+    pub fn from_fields(id: String, fields: &HashMap<String, String>) -> Option<Self> {
+        let timestamp = |name: &str| {
+            fields
+                .get(name)
+                .and_then(|value| value.parse::<i64>().ok())
+                .and_then(|value| Utc.timestamp_opt(value, 0).single())
+        };
+        let text = |name: &str| fields.get(name).cloned().unwrap_or_default();
+
+        Some(SessionModel {
+            id,
+            user_id: fields.get("user_id")?.to_owned(),
+            device_name: text("device_name"),
+            ip_address: text("ip_address"),
+            user_agent: text("user_agent"),
+            created_at: timestamp("created_at")?,
+            last_used_at: timestamp("last_used_at")?,
+        })
+    }
+}
@@ -4,6 +4,9 @@ use serde::{Deserialize, Serialize};
 pub struct TokenClaims {
     pub sub: String,
     pub token_uuid: String,
+    /// Session the token belongs to, empty for tokens issued before sessions.
+    #[serde(default)]
+    pub sid: String,
     pub exp: i64,
     pub iat: i64,
     pub nbf: i64,
@@ -14,5 +17,6 @@ pub struct TokenDetails {
     pub token: Option<String>,
     pub token_uuid: uuid::Uuid,
     pub user_id: String,
+    pub session_id: String,
     pub expires_in: Option<i64>,
 }
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// TOTP secret of a user. The row exists from setup on, the secret only
+/// counts once `enabled_at` is set by confirming a first code.
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct TwoFactorModel {
+    pub user_id: String,
+    pub secret: String,
+    pub enabled_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl TwoFactorModel {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+}
+
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct RecoveryCodeModel {
+    pub id: String,
+    pub user_id: String,
+    pub code_hash: String,
+    pub used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
@@ -40,13 +40,27 @@ pub struct UserModel {
     pub password: String,
     pub role: UserRole,
     pub photo: String,
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    /// Language tag such as `id` or `en-US`, `None` to follow the device.
+    #[serde(rename = "preferredLanguage")]
+    pub preferred_language: Option<String>,
     pub verified: i8,
+    /// Set while an admin has disabled the account.
+    #[serde(rename = "disabledAt")]
+    pub disabled_at: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(rename = "createdAt")]
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(rename = "updatedAt")]
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+impl UserModel {
+    pub fn is_disabled(&self) -> bool {
+        self.disabled_at.is_some()
+    }
+}
+
 impl Into<UserDto> for UserModel {
     fn into(self) -> UserDto {
         UserDto {
@@ -55,7 +69,11 @@ impl Into<UserDto> for UserModel {
             email: self.email,
             role: self.role,
             photo: self.photo,
+            phone: self.phone,
+            address: self.address,
+            preferred_language: self.preferred_language,
             verified: self.verified != 0,
+            disabled_at: self.disabled_at,
             created_at: self.created_at,
             updated_at: self.updated_at,
         }
@@ -0,0 +1,107 @@
+use sqlx::{mysql::MySqlQueryResult, types::Json, MySqlPool};
+
+use crate::{models::api_key::ApiKeyModel, schemas::api_key::CreateApiKeySchema};
+
+/// Store a new key. `expires_in_days` of the schema is counted from now.
+pub async fn insert_api_key(
+    api_key_id: &str,
+    user_id: &str,
+    prefix: &str,
+    key_hash: &str,
+    body: &CreateApiKeySchema,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let query_result = sqlx::query(
+        r#"
+            INSERT INTO api_keys (id, user_id, name, prefix, key_hash, scopes, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, DATE_ADD(NOW(), INTERVAL ? DAY))
+        "#,
+    )
+    .bind(api_key_id)
+    .bind(user_id)
+    .bind(&body.name)
+    .bind(prefix)
+    .bind(key_hash)
+    .bind(Json(&body.scopes))
+    .bind(body.expires_in_days)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string());
+
+    Ok(query_result?)
+}
+
+pub async fn get_api_key_by_prefix(
+    prefix: &str,
+    pool: MySqlPool,
+) -> Result<Option<ApiKeyModel>, sqlx::Error> {
+    let api_key = sqlx::query_as!(
+        ApiKeyModel,
+        r#"
+            SELECT *
+            FROM api_keys
+            WHERE prefix = ?
+        "#,
+        prefix,
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    Ok(api_key)
+}
+
+pub async fn get_user_api_keys(
+    user_id: &str,
+    pool: MySqlPool,
+) -> Result<Vec<ApiKeyModel>, sqlx::Error> {
+    let api_keys = sqlx::query_as!(
+        ApiKeyModel,
+        r#"
+            SELECT *
+            FROM api_keys
+            WHERE user_id = ?
+            ORDER BY created_at DESC
+        "#,
+        user_id,
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(api_keys)
+}
+
+pub async fn delete_api_key(
+    api_key_id: &str,
+    user_id: &str,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let query_result = sqlx::query(
+        r#"
+            DELETE FROM api_keys
+            WHERE id = ? AND user_id = ?
+        "#,
+    )
+    .bind(api_key_id)
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string());
+
+    Ok(query_result?)
+}
+
+pub async fn touch_api_key(api_key_id: &str, pool: MySqlPool) -> Result<MySqlQueryResult, String> {
+    let query_result = sqlx::query(
+        r#"
+            UPDATE api_keys
+            SET last_used_at = NOW()
+            WHERE id = ?
+        "#,
+    )
+    .bind(api_key_id)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string());
+
+    Ok(query_result?)
+}
@@ -1,14 +1,13 @@
 use sqlx::{mysql::MySqlQueryResult, MySqlPool};
 
-use crate::{schemas::auth::RegisterUserSchema, utils::password};
+use crate::schemas::auth::RegisterUserSchema;
 
 pub async fn register_user(
     user_id: &String,
     body: &RegisterUserSchema,
+    hashed_password: &str,
     pool: MySqlPool,
 ) -> Result<MySqlQueryResult, String> {
-    let hashed_password = password::hash(&body.password).map_err(|e| (e.to_string()))?;
-
     let query_result = sqlx::query(
         r#"
             INSERT INTO users (id, name, email, password) 
@@ -1,32 +1,68 @@
 extern crate chrono;
-use sqlx::{mysql::MySqlQueryResult, MySqlPool};
+use rust_decimal::Decimal;
+use sqlx::{mysql::MySqlQueryResult, types::Json, MySqlConnection, MySqlPool, QueryBuilder};
 
-use crate::{models::barang::BarangModel, schemas::barang::InsertBarangSchema};
+use crate::{
+    models::barang::BarangModel,
+    schemas::barang::{BarangSelectorSchema, InsertBarangSchema, UpdateBarangSchema},
+};
 
 pub async fn insert_barang(
     barang_id: &String,
+    organization_id: &str,
+    store_id: Option<&str>,
+    created_by: &str,
     body: &InsertBarangSchema,
     pool: MySqlPool,
 ) -> Result<MySqlQueryResult, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
     let query_result = sqlx::query(
         r#"
-            INSERT INTO barang (id, name, price, stock, expired_at)
-            VALUES (?, ?, ?, ?, ?)
+            INSERT INTO barang (id, organization_id, store_id, name, category, tags, price, stock, expired_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(barang_id.clone())
+    .bind(organization_id)
+    .bind(store_id)
     .bind(body.name.to_string())
-    .bind(body.price)
+    .bind(body.category.clone())
+    .bind(body.tags.clone().map(Json))
+    .bind(body.price.amount())
     .bind(body.stock)
     .bind(body.expired_at.clone())
-    .execute(&pool)
+    .execute(&mut *tx)
     .await
-    .map_err(|err: sqlx::Error| err.to_string());
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    // Opening stock is the first cost layer of the barang.
+    if body.stock > 0 {
+        sqlx::query(
+            r#"
+                INSERT INTO stock_movements (id, organization_id, barang_id, movement_type, quantity, unit_cost, note, created_by)
+                VALUES (?, ?, ?, 'in', ?, ?, 'Opening stock', ?)
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(organization_id)
+        .bind(barang_id.clone())
+        .bind(body.stock)
+        .bind(body.unit_cost.unwrap_or(body.price).amount())
+        .bind(created_by)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
 
-    Ok(query_result?)
+    Ok(query_result)
 }
 
 pub async fn get_barang_by_name(
+    organization_id: &str,
+    store_id: Option<&str>,
     name: Option<&str>,
     pool: MySqlPool,
 ) -> Result<Vec<BarangModel>, sqlx::Error> {
@@ -42,9 +78,14 @@ pub async fn get_barang_by_name(
         r#"
             SELECT *
             FROM barang
-            WHERE name LIKE ?
+            WHERE organization_id = ?
+            AND (? IS NULL OR store_id = ?)
+            AND name LIKE ?
             ORDER BY created_at DESC 
         "#,
+        organization_id,
+        store_id,
+        store_id,
         name_pattern,
     )
     .fetch_all(&pool)
@@ -54,6 +95,7 @@ pub async fn get_barang_by_name(
 }
 
 pub async fn get_barang_by_id(
+    organization_id: &str,
     barang_id: &str,
     pool: MySqlPool,
 ) -> Result<BarangModel, sqlx::Error> {
@@ -62,13 +104,206 @@ pub async fn get_barang_by_id(
         r#"
             SELECT *
             FROM barang
-            WHERE id = ?
+            WHERE id = ? AND organization_id = ?
             LIMIT 1
         "#,
         barang_id,
+        organization_id,
     )
     .fetch_one(&pool)
     .await?;
 
     Ok(barang)
 }
+
+/// Update a barang when its version still equals `expected_version` (any
+/// version when `None`), bumping the version. Returns the number of rows
+/// changed, which is zero on a version mismatch.
+pub async fn update_barang(
+    organization_id: &str,
+    barang_id: &str,
+    expected_version: Option<i32>,
+    body: &UpdateBarangSchema,
+    pool: MySqlPool,
+) -> Result<u64, String> {
+    let query_result = sqlx::query(
+        r#"
+            UPDATE barang
+            SET name = COALESCE(?, name),
+                category = COALESCE(?, category),
+                tags = COALESCE(?, tags),
+                price = COALESCE(?, price),
+                expired_at = COALESCE(?, expired_at),
+                version = version + 1
+            WHERE id = ? AND organization_id = ?
+            AND (? IS NULL OR version = ?)
+        "#,
+    )
+    .bind(body.name.clone())
+    .bind(body.category.clone())
+    .bind(body.tags.clone().map(Json))
+    .bind(body.price.map(|price| price.amount()))
+    .bind(body.expired_at.clone())
+    .bind(barang_id)
+    .bind(organization_id)
+    .bind(expected_version)
+    .bind(expected_version)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    Ok(query_result.rows_affected())
+}
+
+pub async fn delete_barang(
+    organization_id: &str,
+    barang_id: &str,
+    expected_version: Option<i32>,
+    pool: MySqlPool,
+) -> Result<u64, String> {
+    let query_result = sqlx::query(
+        r#"
+            DELETE FROM barang
+            WHERE id = ? AND organization_id = ?
+            AND (? IS NULL OR version = ?)
+        "#,
+    )
+    .bind(barang_id)
+    .bind(organization_id)
+    .bind(expected_version)
+    .bind(expected_version)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    Ok(query_result.rows_affected())
+}
+
+/// Lock and return the barang matched by a bulk selector, scoped to the
+/// organization and, when given, the store.
+pub async fn select_barang_for_update(
+    organization_id: &str,
+    store_id: Option<&str>,
+    selector: &BarangSelectorSchema,
+    conn: &mut MySqlConnection,
+) -> Result<Vec<BarangModel>, sqlx::Error> {
+    let mut query = QueryBuilder::new("SELECT * FROM barang WHERE organization_id = ");
+    query.push_bind(organization_id);
+
+    if let Some(store_id) = store_id {
+        query.push(" AND store_id = ").push_bind(store_id);
+    }
+
+    if let Some(ids) = selector.ids.as_ref().filter(|ids| !ids.is_empty()) {
+        query.push(" AND id IN (");
+        let mut separated = query.separated(", ");
+        for id in ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(")");
+    }
+
+    if let Some(name) = selector
+        .name
+        .as_deref()
+        .filter(|name| !name.trim().is_empty())
+    {
+        query
+            .push(" AND name LIKE ")
+            .push_bind(format!("%{}%", name));
+    }
+
+    if let Some(category) = selector
+        .category
+        .as_deref()
+        .filter(|category| !category.trim().is_empty())
+    {
+        query.push(" AND category = ").push_bind(category);
+    }
+
+    if let Some(tag) = selector.tag.as_deref().filter(|tag| !tag.trim().is_empty()) {
+        query
+            .push(" AND JSON_CONTAINS(tags, JSON_QUOTE(")
+            .push_bind(tag)
+            .push("))");
+    }
+
+    query.push(" ORDER BY name FOR UPDATE");
+
+    query
+        .build_query_as::<BarangModel>()
+        .fetch_all(&mut *conn)
+        .await
+}
+
+pub async fn set_barang_price(
+    organization_id: &str,
+    barang_id: &str,
+    price: Decimal,
+    conn: &mut MySqlConnection,
+) -> Result<MySqlQueryResult, String> {
+    sqlx::query(
+        r#"
+            UPDATE barang
+            SET price = ?, version = version + 1
+            WHERE id = ? AND organization_id = ?
+        "#,
+    )
+    .bind(price)
+    .bind(barang_id)
+    .bind(organization_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())
+}
+
+pub async fn set_barang_category(
+    organization_id: &str,
+    barang_id: &str,
+    category: Option<&str>,
+    tags: Option<&Vec<String>>,
+    conn: &mut MySqlConnection,
+) -> Result<MySqlQueryResult, String> {
+    sqlx::query(
+        r#"
+            UPDATE barang
+            SET category = COALESCE(?, category),
+                tags = COALESCE(?, tags),
+                version = version + 1
+            WHERE id = ? AND organization_id = ?
+        "#,
+    )
+    .bind(category)
+    .bind(tags.map(Json))
+    .bind(barang_id)
+    .bind(organization_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())
+}
+
+pub async fn delete_barang_in(
+    organization_id: &str,
+    barang_ids: &[String],
+    conn: &mut MySqlConnection,
+) -> Result<u64, String> {
+    if barang_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let mut query = QueryBuilder::new("DELETE FROM barang WHERE organization_id = ");
+    query.push_bind(organization_id).push(" AND id IN (");
+    let mut separated = query.separated(", ");
+    for id in barang_ids {
+        separated.push_bind(id);
+    }
+    separated.push_unseparated(")");
+
+    let query_result = query
+        .build()
+        .execute(&mut *conn)
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    Ok(query_result.rows_affected())
+}
@@ -0,0 +1,92 @@
+use sqlx::MySqlPool;
+
+use crate::models::dashboard::{ActivityModel, BarangSummaryModel, SalesSummaryModel};
+
+pub async fn get_barang_summary(
+    organization_id: &str,
+    store_id: Option<&str>,
+    low_stock_threshold: i32,
+    expiring_soon_days: i32,
+    pool: MySqlPool,
+) -> Result<BarangSummaryModel, sqlx::Error> {
+    let summary = sqlx::query_as::<_, BarangSummaryModel>(
+        r#"
+            SELECT
+                CAST(COUNT(*) AS SIGNED) AS item_count,
+                CAST(COALESCE(SUM(stock <= ?), 0) AS SIGNED) AS low_stock_count,
+                CAST(COALESCE(SUM(
+                    expired_at IS NOT NULL
+                    AND expired_at BETWEEN CURDATE() AND DATE_ADD(CURDATE(), INTERVAL ? DAY)
+                ), 0) AS SIGNED) AS expiring_soon_count
+            FROM barang
+            WHERE organization_id = ?
+            AND (? IS NULL OR store_id = ?)
+        "#,
+    )
+    .bind(low_stock_threshold)
+    .bind(expiring_soon_days)
+    .bind(organization_id)
+    .bind(store_id)
+    .bind(store_id)
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(summary)
+}
+
+pub async fn get_today_sales(
+    organization_id: &str,
+    store_id: Option<&str>,
+    pool: MySqlPool,
+) -> Result<SalesSummaryModel, sqlx::Error> {
+    let sales = sqlx::query_as::<_, SalesSummaryModel>(
+        r#"
+            SELECT
+                CAST(COUNT(*) AS SIGNED) AS sales_count,
+                CAST(COALESCE(SUM(m.quantity * m.unit_price), 0) AS DECIMAL(19, 2)) AS sales_total
+            FROM stock_movements m
+            INNER JOIN barang b ON b.id = m.barang_id
+            WHERE m.organization_id = ?
+            AND (? IS NULL OR b.store_id = ?)
+            AND m.movement_type = 'out'
+            AND m.unit_price IS NOT NULL
+            AND DATE(m.created_at) = CURDATE()
+        "#,
+    )
+    .bind(organization_id)
+    .bind(store_id)
+    .bind(store_id)
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(sales)
+}
+
+pub async fn get_recent_activity(
+    organization_id: &str,
+    store_id: Option<&str>,
+    limit: i64,
+    pool: MySqlPool,
+) -> Result<Vec<ActivityModel>, sqlx::Error> {
+    let activity = sqlx::query_as::<_, ActivityModel>(
+        r#"
+            SELECT m.id, m.barang_id, b.name AS barang_name, m.movement_type, m.quantity,
+                m.unit_cost, m.unit_price, m.note, u.name AS user_name, m.created_at
+            FROM stock_movements m
+            INNER JOIN barang b ON b.id = m.barang_id
+            LEFT JOIN users u ON u.id = m.created_by
+            WHERE m.organization_id = ?
+            AND (? IS NULL OR b.store_id = ?)
+            ORDER BY m.seq DESC
+            LIMIT ?
+        "#,
+    )
+    .bind(organization_id)
+    .bind(store_id)
+    .bind(store_id)
+    .bind(limit)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(activity)
+}
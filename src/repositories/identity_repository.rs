@@ -0,0 +1,131 @@
+use sqlx::{mysql::MySqlQueryResult, MySqlConnection, MySqlPool};
+
+use crate::models::identity::{ExternalIdentity, UserIdentityModel};
+
+pub async fn get_identity(
+    issuer: &str,
+    subject: &str,
+    pool: MySqlPool,
+) -> Result<Option<UserIdentityModel>, sqlx::Error> {
+    let identity = sqlx::query_as!(
+        UserIdentityModel,
+        r#"
+            SELECT *
+            FROM user_identities
+            WHERE issuer = ? AND subject = ?
+        "#,
+        issuer,
+        subject,
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    Ok(identity)
+}
+
+pub async fn get_user_identities(
+    user_id: &str,
+    pool: MySqlPool,
+) -> Result<Vec<UserIdentityModel>, sqlx::Error> {
+    let identities = sqlx::query_as!(
+        UserIdentityModel,
+        r#"
+            SELECT *
+            FROM user_identities
+            WHERE user_id = ?
+            ORDER BY created_at
+        "#,
+        user_id,
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(identities)
+}
+
+async fn insert_identity_on(
+    user_id: &str,
+    identity: &ExternalIdentity,
+    conn: &mut MySqlConnection,
+) -> Result<MySqlQueryResult, String> {
+    sqlx::query(
+        r#"
+            INSERT INTO user_identities (id, user_id, issuer, subject, email, last_login_at)
+            VALUES (?, ?, ?, ?, ?, NOW())
+        "#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(user_id)
+    .bind(&identity.issuer)
+    .bind(&identity.subject)
+    .bind(&identity.email)
+    .execute(&mut *conn)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())
+}
+
+/// Link an external identity to an existing user.
+pub async fn insert_identity(
+    user_id: &str,
+    identity: &ExternalIdentity,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+    insert_identity_on(user_id, identity, &mut *conn).await
+}
+
+/// Create a user for an external identity seen for the first time, together
+/// with the link so neither exists without the other.
+pub async fn insert_user_with_identity(
+    user_id: &str,
+    name: &str,
+    email: &str,
+    hashed_password: &str,
+    identity: &ExternalIdentity,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let query_result = sqlx::query(
+        r#"
+            INSERT INTO users (id, name, email, password, verified)
+            VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(user_id)
+    .bind(name)
+    .bind(email)
+    .bind(hashed_password)
+    .bind(identity.email_verified)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    insert_identity_on(user_id, identity, &mut *tx).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(query_result)
+}
+
+pub async fn touch_identity(
+    identity_id: &str,
+    email: Option<&str>,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let query_result = sqlx::query(
+        r#"
+            UPDATE user_identities
+            SET last_login_at = NOW(), email = COALESCE(?, email)
+            WHERE id = ?
+        "#,
+    )
+    .bind(email)
+    .bind(identity_id)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string());
+
+    Ok(query_result?)
+}
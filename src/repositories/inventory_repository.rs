@@ -0,0 +1,136 @@
+use chrono::NaiveDate;
+use sqlx::MySqlPool;
+
+use crate::models::inventory::{
+    MovementOutcome, MovementType, StockMovementModel, ValuationMovementModel,
+};
+
+/// Record a stock movement and apply it to `barang.stock` in one transaction.
+/// Nothing is recorded when the barang does not exist in the organization or
+/// an outgoing movement would take more than is in stock.
+pub async fn insert_movement(
+    movement: &StockMovementModel,
+    pool: MySqlPool,
+) -> Result<MovementOutcome, String> {
+    let quantity = movement.quantity;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    // Stock never goes below zero, outgoing movements need enough on hand.
+    let (delta, floor) = match movement.movement_type {
+        MovementType::In => (quantity, None),
+        MovementType::Out => (-quantity, Some(quantity)),
+    };
+
+    let updated = sqlx::query(
+        r#"
+            UPDATE barang
+            SET stock = stock + ?, version = version + 1
+            WHERE id = ? AND organization_id = ? AND (? IS NULL OR stock >= ?)
+        "#,
+    )
+    .bind(delta)
+    .bind(&movement.barang_id)
+    .bind(&movement.organization_id)
+    .bind(floor)
+    .bind(floor)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    if updated.rows_affected() == 0 {
+        let exists: i64 = sqlx::query_scalar(
+            r#"
+                SELECT COUNT(*)
+                FROM barang
+                WHERE id = ? AND organization_id = ?
+            "#,
+        )
+        .bind(&movement.barang_id)
+        .bind(&movement.organization_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+        tx.rollback().await.map_err(|e| e.to_string())?;
+
+        return Ok(if exists == 0 {
+            MovementOutcome::NotFound
+        } else {
+            MovementOutcome::InsufficientStock
+        });
+    }
+
+    sqlx::query(
+        r#"
+            INSERT INTO stock_movements (id, organization_id, barang_id, movement_type, quantity, unit_cost, unit_price, note, created_by)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&movement.id)
+    .bind(&movement.organization_id)
+    .bind(&movement.barang_id)
+    .bind(movement.movement_type.to_str())
+    .bind(quantity)
+    .bind(movement.unit_cost)
+    .bind(movement.unit_price)
+    .bind(&movement.note)
+    .bind(&movement.created_by)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(MovementOutcome::Recorded)
+}
+
+pub async fn get_valuation_movements(
+    organization_id: &str,
+    store_id: Option<&str>,
+    until: Option<NaiveDate>,
+    pool: MySqlPool,
+) -> Result<Vec<ValuationMovementModel>, sqlx::Error> {
+    let movements = sqlx::query_as!(
+        ValuationMovementModel,
+        r#"
+            SELECT m.barang_id, b.name, m.movement_type, m.quantity, m.unit_cost, m.created_at
+            FROM stock_movements m
+            INNER JOIN barang b ON b.id = m.barang_id
+            WHERE m.organization_id = ?
+            AND (? IS NULL OR b.store_id = ?)
+            AND (? IS NULL OR DATE(m.created_at) <= ?)
+            ORDER BY b.name, m.barang_id, m.seq
+        "#,
+        organization_id,
+        store_id,
+        store_id,
+        until,
+        until,
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(movements)
+}
+
+/// Every movement recorded by `user_id`, oldest first.
+pub async fn get_user_movements(
+    user_id: &str,
+    pool: MySqlPool,
+) -> Result<Vec<StockMovementModel>, sqlx::Error> {
+    let movements = sqlx::query_as!(
+        StockMovementModel,
+        r#"
+            SELECT id, organization_id, barang_id, movement_type, quantity, unit_cost,
+                unit_price, note, created_by, created_at
+            FROM stock_movements
+            WHERE created_by = ?
+            ORDER BY seq
+        "#,
+        user_id,
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(movements)
+}
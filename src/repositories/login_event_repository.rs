@@ -0,0 +1,90 @@
+use sqlx::{mysql::MySqlQueryResult, MySqlPool};
+
+use crate::models::login_event::LoginEventModel;
+
+pub async fn insert_login_event(
+    login_event: &LoginEventModel,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let query_result = sqlx::query(
+        r#"
+            INSERT INTO login_events
+                (id, user_id, email, outcome, reason, ip_address, user_agent, device_name, fingerprint, new_device)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&login_event.id)
+    .bind(&login_event.user_id)
+    .bind(&login_event.email)
+    .bind(login_event.outcome.to_str())
+    .bind(&login_event.reason)
+    .bind(&login_event.ip_address)
+    .bind(&login_event.user_agent)
+    .bind(&login_event.device_name)
+    .bind(&login_event.fingerprint)
+    .bind(login_event.new_device)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string());
+
+    Ok(query_result?)
+}
+
+/// Whether `user_id` ever logged in successfully from the device `fingerprint`.
+pub async fn has_successful_login(
+    user_id: &str,
+    fingerprint: &str,
+    pool: MySqlPool,
+) -> Result<bool, sqlx::Error> {
+    let (exists,): (bool,) = sqlx::query_as(
+        r#"
+            SELECT EXISTS (
+                SELECT 1 FROM login_events
+                WHERE user_id = ? AND fingerprint = ? AND outcome = 'success'
+            )
+        "#,
+    )
+    .bind(user_id)
+    .bind(fingerprint)
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(exists)
+}
+
+/// One page of login attempts on `user_id`, newest first, with the total count.
+pub async fn get_user_login_events(
+    user_id: &str,
+    page: i64,
+    per_page: i64,
+    pool: MySqlPool,
+) -> Result<(Vec<LoginEventModel>, i64), sqlx::Error> {
+    let (total,): (i64,) = sqlx::query_as(
+        r#"
+            SELECT COUNT(*)
+            FROM login_events
+            WHERE user_id = ?
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await?;
+
+    let login_events = sqlx::query_as!(
+        LoginEventModel,
+        r#"
+            SELECT *
+            FROM login_events
+            WHERE user_id = ?
+            ORDER BY created_at DESC, id
+            LIMIT ? OFFSET ?
+        "#,
+        user_id,
+        per_page,
+        (page - 1) * per_page,
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok((login_events, total))
+}
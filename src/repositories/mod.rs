@@ -1,3 +1,11 @@
+pub mod api_key_repository;
 pub mod auth_repository;
 pub mod barang_repository;
+pub mod dashboard_repository;
+pub mod identity_repository;
+pub mod inventory_repository;
+pub mod login_event_repository;
+pub mod organization_repository;
+pub mod permission_repository;
+pub mod two_factor_repository;
 pub mod user_repository;
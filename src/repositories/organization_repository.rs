@@ -0,0 +1,307 @@
+use sqlx::{mysql::MySqlQueryResult, MySqlConnection, MySqlPool};
+
+use crate::models::organization::{
+    OrganizationMemberModel, OrganizationModel, OrganizationRole, StoreModel, UserOrganizationModel,
+};
+
+pub async fn insert_organization(
+    organization_id: &str,
+    owner_id: &str,
+    name: &str,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    sqlx::query(
+        r#"
+            INSERT INTO organizations (id, name)
+            VALUES (?, ?)
+        "#,
+    )
+    .bind(organization_id)
+    .bind(name)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    let query_result = sqlx::query(
+        r#"
+            INSERT INTO organization_members (organization_id, user_id, role)
+            VALUES (?, ?, ?)
+        "#,
+    )
+    .bind(organization_id)
+    .bind(owner_id)
+    .bind(OrganizationRole::Owner.to_str())
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(query_result)
+}
+
+pub async fn get_organization_by_id(
+    organization_id: &str,
+    pool: MySqlPool,
+) -> Result<Option<OrganizationModel>, sqlx::Error> {
+    let organization = sqlx::query_as!(
+        OrganizationModel,
+        r#"
+            SELECT *
+            FROM organizations
+            WHERE id = ?
+        "#,
+        organization_id,
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    Ok(organization)
+}
+
+pub async fn get_user_organizations(
+    user_id: &str,
+    pool: MySqlPool,
+) -> Result<Vec<UserOrganizationModel>, sqlx::Error> {
+    let organizations = sqlx::query_as!(
+        UserOrganizationModel,
+        r#"
+            SELECT o.id, o.name, m.role, o.created_at, o.updated_at
+            FROM organizations o
+            INNER JOIN organization_members m ON m.organization_id = o.id
+            WHERE m.user_id = ?
+            ORDER BY o.name
+        "#,
+        user_id,
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(organizations)
+}
+
+/// Organizations `user_id` is the only owner of while others are still
+/// members, they would be left without an owner.
+pub async fn get_solely_owned_shared_organizations(
+    user_id: &str,
+    pool: MySqlPool,
+) -> Result<Vec<UserOrganizationModel>, sqlx::Error> {
+    let organizations = sqlx::query_as!(
+        UserOrganizationModel,
+        r#"
+            SELECT o.id, o.name, m.role, o.created_at, o.updated_at
+            FROM organizations o
+            INNER JOIN organization_members m ON m.organization_id = o.id
+            WHERE m.user_id = ? AND m.role = 'owner'
+            AND NOT EXISTS (
+                SELECT 1 FROM organization_members other
+                WHERE other.organization_id = o.id AND other.user_id <> m.user_id AND other.role = 'owner'
+            )
+            AND EXISTS (
+                SELECT 1 FROM organization_members other
+                WHERE other.organization_id = o.id AND other.user_id <> m.user_id
+            )
+            ORDER BY o.name
+        "#,
+        user_id,
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(organizations)
+}
+
+pub async fn get_member(
+    organization_id: &str,
+    user_id: &str,
+    pool: MySqlPool,
+) -> Result<Option<OrganizationMemberModel>, sqlx::Error> {
+    let member = sqlx::query_as!(
+        OrganizationMemberModel,
+        r#"
+            SELECT *
+            FROM organization_members
+            WHERE organization_id = ? AND user_id = ?
+        "#,
+        organization_id,
+        user_id,
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    Ok(member)
+}
+
+pub async fn get_members(
+    organization_id: &str,
+    pool: MySqlPool,
+) -> Result<Vec<OrganizationMemberModel>, sqlx::Error> {
+    let members = sqlx::query_as!(
+        OrganizationMemberModel,
+        r#"
+            SELECT *
+            FROM organization_members
+            WHERE organization_id = ?
+            ORDER BY created_at
+        "#,
+        organization_id,
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(members)
+}
+
+/// Whether taking `user_id` out of the owners would leave the organization
+/// without one. Locks the owner rows until the transaction ends, so two
+/// owners demoting each other at once can not both succeed.
+async fn is_last_owner(
+    organization_id: &str,
+    user_id: &str,
+    conn: &mut MySqlConnection,
+) -> Result<bool, String> {
+    let owners: Vec<String> = sqlx::query_scalar(
+        r#"
+            SELECT user_id
+            FROM organization_members
+            WHERE organization_id = ? AND role = 'owner'
+            FOR UPDATE
+        "#,
+    )
+    .bind(organization_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    Ok(owners.len() == 1 && owners[0] == user_id)
+}
+
+/// Add `user_id` to the organization or change their role. Returns `false`
+/// and changes nothing when it would demote the last owner.
+pub async fn upsert_member(
+    organization_id: &str,
+    user_id: &str,
+    role: OrganizationRole,
+    pool: MySqlPool,
+) -> Result<bool, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    if role != OrganizationRole::Owner && is_last_owner(organization_id, user_id, &mut *tx).await? {
+        return Ok(false);
+    }
+
+    sqlx::query(
+        r#"
+            INSERT INTO organization_members (organization_id, user_id, role)
+            VALUES (?, ?, ?)
+            ON DUPLICATE KEY UPDATE role = VALUES(role)
+        "#,
+    )
+    .bind(organization_id)
+    .bind(user_id)
+    .bind(role.to_str())
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(true)
+}
+
+/// Remove `user_id` from the organization. Returns `false` and removes
+/// nothing when they are its last owner.
+pub async fn delete_member(
+    organization_id: &str,
+    user_id: &str,
+    pool: MySqlPool,
+) -> Result<bool, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    if is_last_owner(organization_id, user_id, &mut *tx).await? {
+        return Ok(false);
+    }
+
+    sqlx::query(
+        r#"
+            DELETE FROM organization_members
+            WHERE organization_id = ? AND user_id = ?
+        "#,
+    )
+    .bind(organization_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(true)
+}
+
+pub async fn insert_store(
+    store_id: &str,
+    organization_id: &str,
+    name: &str,
+    address: Option<&str>,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let query_result = sqlx::query(
+        r#"
+            INSERT INTO stores (id, organization_id, name, address)
+            VALUES (?, ?, ?, ?)
+        "#,
+    )
+    .bind(store_id)
+    .bind(organization_id)
+    .bind(name)
+    .bind(address)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string());
+
+    Ok(query_result?)
+}
+
+pub async fn get_stores(
+    organization_id: &str,
+    pool: MySqlPool,
+) -> Result<Vec<StoreModel>, sqlx::Error> {
+    let stores = sqlx::query_as!(
+        StoreModel,
+        r#"
+            SELECT *
+            FROM stores
+            WHERE organization_id = ?
+            ORDER BY name
+        "#,
+        organization_id,
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(stores)
+}
+
+pub async fn get_store_by_id(
+    organization_id: &str,
+    store_id: &str,
+    pool: MySqlPool,
+) -> Result<Option<StoreModel>, sqlx::Error> {
+    let store = sqlx::query_as!(
+        StoreModel,
+        r#"
+            SELECT *
+            FROM stores
+            WHERE id = ? AND organization_id = ?
+        "#,
+        store_id,
+        organization_id,
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    Ok(store)
+}
@@ -0,0 +1,202 @@
+use sqlx::{mysql::MySqlQueryResult, MySqlConnection, MySqlPool};
+
+use crate::models::permission::{RoleModel, RolePermissionModel};
+
+/// Permissions of the user's base role plus those of every role assigned to
+/// them on top.
+pub async fn get_user_permissions(
+    user_id: &str,
+    base_role: &str,
+    pool: MySqlPool,
+) -> Result<Vec<RolePermissionModel>, sqlx::Error> {
+    let permissions = sqlx::query_as!(
+        RolePermissionModel,
+        r#"
+            SELECT role, permission
+            FROM role_permissions
+            WHERE role = ?
+                OR role IN (SELECT role FROM user_roles WHERE user_id = ?)
+        "#,
+        base_role,
+        user_id,
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(permissions)
+}
+
+pub async fn get_roles(pool: MySqlPool) -> Result<Vec<RoleModel>, sqlx::Error> {
+    let roles = sqlx::query_as!(
+        RoleModel,
+        r#"
+            SELECT *
+            FROM roles
+            ORDER BY built_in DESC, name
+        "#,
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(roles)
+}
+
+pub async fn get_role_permissions(
+    pool: MySqlPool,
+) -> Result<Vec<RolePermissionModel>, sqlx::Error> {
+    let permissions = sqlx::query_as!(
+        RolePermissionModel,
+        r#"
+            SELECT role, permission
+            FROM role_permissions
+            ORDER BY role, permission
+        "#,
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(permissions)
+}
+
+/// Create a custom role together with its permissions.
+pub async fn insert_role(
+    name: &str,
+    description: Option<&str>,
+    permissions: &[String],
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let query_result = sqlx::query(
+        r#"
+            INSERT INTO roles (name, description)
+            VALUES (?, ?)
+        "#,
+    )
+    .bind(name)
+    .bind(description)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    replace_role_permissions(name, permissions, &mut *tx).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(query_result)
+}
+
+/// Swap every permission of the role for `permissions`.
+pub async fn set_role_permissions(
+    name: &str,
+    permissions: &[String],
+    pool: MySqlPool,
+) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    replace_role_permissions(name, permissions, &mut *tx).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+async fn replace_role_permissions(
+    name: &str,
+    permissions: &[String],
+    conn: &mut MySqlConnection,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+            DELETE FROM role_permissions
+            WHERE role = ?
+        "#,
+    )
+    .bind(name)
+    .execute(&mut *conn)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    for permission in permissions {
+        sqlx::query(
+            r#"
+                INSERT INTO role_permissions (role, permission)
+                VALUES (?, ?)
+            "#,
+        )
+        .bind(name)
+        .bind(permission)
+        .execute(&mut *conn)
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Delete a custom role, its permissions and assignments go with it.
+pub async fn delete_role(name: &str, pool: MySqlPool) -> Result<MySqlQueryResult, String> {
+    let query_result = sqlx::query(
+        r#"
+            DELETE FROM roles
+            WHERE name = ? AND built_in = FALSE
+        "#,
+    )
+    .bind(name)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string());
+
+    Ok(query_result?)
+}
+
+/// Roles assigned to the user on top of their base role.
+pub async fn get_user_roles(user_id: &str, pool: MySqlPool) -> Result<Vec<String>, sqlx::Error> {
+    let roles: Vec<(String,)> = sqlx::query_as(
+        r#"
+            SELECT role
+            FROM user_roles
+            WHERE user_id = ?
+            ORDER BY role
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(roles.into_iter().map(|(role,)| role).collect())
+}
+
+/// Swap every additional role of the user for `roles`.
+pub async fn set_user_roles(
+    user_id: &str,
+    roles: &[String],
+    pool: MySqlPool,
+) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    sqlx::query(
+        r#"
+            DELETE FROM user_roles
+            WHERE user_id = ?
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    for role in roles {
+        sqlx::query(
+            r#"
+                INSERT INTO user_roles (user_id, role)
+                VALUES (?, ?)
+            "#,
+        )
+        .bind(user_id)
+        .bind(role)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())
+}
@@ -0,0 +1,185 @@
+use sqlx::{mysql::MySqlQueryResult, MySqlConnection, MySqlPool};
+
+use crate::models::two_factor::{RecoveryCodeModel, TwoFactorModel};
+
+pub async fn get_two_factor(
+    user_id: &str,
+    pool: MySqlPool,
+) -> Result<Option<TwoFactorModel>, sqlx::Error> {
+    let two_factor = sqlx::query_as!(
+        TwoFactorModel,
+        r#"
+            SELECT *
+            FROM user_two_factor
+            WHERE user_id = ?
+        "#,
+        user_id,
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    Ok(two_factor)
+}
+
+/// Store a new, not yet enabled secret. Starting over replaces a pending one.
+pub async fn upsert_two_factor_secret(
+    user_id: &str,
+    secret: &str,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let query_result = sqlx::query(
+        r#"
+            INSERT INTO user_two_factor (user_id, secret)
+            VALUES (?, ?)
+            ON DUPLICATE KEY UPDATE secret = VALUES(secret), enabled_at = NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(secret)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string());
+
+    Ok(query_result?)
+}
+
+/// Enable two-factor and store its recovery codes in one go.
+pub async fn enable_two_factor(
+    user_id: &str,
+    code_hashes: &[String],
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let query_result = sqlx::query(
+        r#"
+            UPDATE user_two_factor
+            SET enabled_at = NOW()
+            WHERE user_id = ?
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    replace_recovery_codes(user_id, code_hashes, &mut *tx).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(query_result)
+}
+
+pub async fn delete_two_factor(user_id: &str, pool: MySqlPool) -> Result<MySqlQueryResult, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    sqlx::query(
+        r#"
+            DELETE FROM user_recovery_codes
+            WHERE user_id = ?
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    let query_result = sqlx::query(
+        r#"
+            DELETE FROM user_two_factor
+            WHERE user_id = ?
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(query_result)
+}
+
+/// Swap every recovery code of the user for `code_hashes`.
+pub async fn set_recovery_codes(
+    user_id: &str,
+    code_hashes: &[String],
+    pool: MySqlPool,
+) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    replace_recovery_codes(user_id, code_hashes, &mut *tx).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+async fn replace_recovery_codes(
+    user_id: &str,
+    code_hashes: &[String],
+    conn: &mut MySqlConnection,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+            DELETE FROM user_recovery_codes
+            WHERE user_id = ?
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    for code_hash in code_hashes {
+        sqlx::query(
+            r#"
+                INSERT INTO user_recovery_codes (id, user_id, code_hash)
+                VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(&mut *conn)
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+    }
+
+    Ok(())
+}
+
+pub async fn get_unused_recovery_codes(
+    user_id: &str,
+    pool: MySqlPool,
+) -> Result<Vec<RecoveryCodeModel>, sqlx::Error> {
+    let codes = sqlx::query_as!(
+        RecoveryCodeModel,
+        r#"
+            SELECT *
+            FROM user_recovery_codes
+            WHERE user_id = ? AND used_at IS NULL
+        "#,
+        user_id,
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(codes)
+}
+
+/// Mark a recovery code used. Returns `false` when a concurrent login already
+/// spent it.
+pub async fn use_recovery_code(code_id: &str, pool: MySqlPool) -> Result<bool, String> {
+    let query_result = sqlx::query(
+        r#"
+            UPDATE user_recovery_codes
+            SET used_at = NOW()
+            WHERE id = ? AND used_at IS NULL
+        "#,
+    )
+    .bind(code_id)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    Ok(query_result.rows_affected() == 1)
+}
@@ -1,6 +1,9 @@
-use sqlx::{mysql::MySqlQueryResult, MySqlPool};
+use sqlx::{mysql::MySqlQueryResult, MySql, MySqlPool, QueryBuilder};
 
-use crate::models::user::UserModel;
+use crate::{
+    models::user::{UserModel, UserRole},
+    schemas::{admin::ListUsersSchema, user::UpdateProfileSchema},
+};
 
 pub async fn get_user(
     user_id: Option<&str>,
@@ -25,7 +28,7 @@ pub async fn get_user(
     Ok(user)
 }
 
-pub async fn update_user(
+pub async fn update_user_photo(
     user_id: &str,
     photo: Option<&str>,
     pool: MySqlPool,
@@ -48,3 +51,279 @@ pub async fn update_user(
 
     Ok(query_result?)
 }
+
+/// Write the profile fields present in `profile`, empty optional fields are
+/// stored as NULL.
+pub async fn update_user_profile(
+    user_id: &str,
+    profile: &UpdateProfileSchema,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let mut query = QueryBuilder::new("UPDATE users SET updated_at = NOW()");
+
+    if let Some(name) = &profile.name {
+        query.push(", name = ").push_bind(name.trim().to_string());
+    }
+
+    let optional_fields = [
+        ("phone", &profile.phone),
+        ("address", &profile.address),
+        ("preferred_language", &profile.preferred_language),
+    ];
+    for (column, value) in optional_fields {
+        if let Some(value) = value {
+            query
+                .push(format!(", {} = NULLIF(", column))
+                .push_bind(value.trim().to_string())
+                .push(", '')");
+        }
+    }
+
+    query.push(" WHERE id = ").push_bind(user_id.to_string());
+
+    let query_result = query
+        .build()
+        .execute(&pool)
+        .await
+        .map_err(|err: sqlx::Error| err.to_string());
+
+    Ok(query_result?)
+}
+
+pub async fn set_user_verified(
+    user_id: &str,
+    verified: bool,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let query_result = sqlx::query(
+        r#"
+            UPDATE users
+            SET verified = ?
+            WHERE id = ?
+        "#,
+    )
+    .bind(verified)
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string());
+
+    Ok(query_result?)
+}
+
+pub async fn update_user_password(
+    user_id: &str,
+    hashed_password: &str,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let query_result = sqlx::query(
+        r#"
+            UPDATE users
+            SET password = ?
+            WHERE id = ?
+        "#,
+    )
+    .bind(hashed_password)
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string());
+
+    Ok(query_result?)
+}
+
+/// Change the email of a user, who has to verify the new address again.
+pub async fn update_user_email(
+    user_id: &str,
+    email: &str,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let query_result = sqlx::query(
+        r#"
+            UPDATE users
+            SET email = ?, verified = 0
+            WHERE id = ?
+        "#,
+    )
+    .bind(email)
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string());
+
+    Ok(query_result?)
+}
+
+fn push_user_filters(query: &mut QueryBuilder<'_, MySql>, filter: &ListUsersSchema) {
+    query.push(" WHERE 1 = 1");
+
+    if let Some(search) = filter
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|search| !search.is_empty())
+    {
+        let pattern = format!("%{}%", search);
+        query
+            .push(" AND (name LIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR email LIKE ")
+            .push_bind(pattern)
+            .push(")");
+    }
+
+    if let Some(role) = filter.role {
+        query
+            .push(" AND role = ")
+            .push_bind(role.to_str().to_string());
+    }
+
+    if let Some(verified) = filter.verified {
+        query.push(" AND verified = ").push_bind(verified);
+    }
+
+    match filter.disabled {
+        Some(true) => {
+            query.push(" AND disabled_at IS NOT NULL");
+        }
+        Some(false) => {
+            query.push(" AND disabled_at IS NULL");
+        }
+        None => {}
+    }
+}
+
+/// One page of users matching `filter`, newest first, with the total count.
+pub async fn list_users(
+    filter: &ListUsersSchema,
+    page: i64,
+    per_page: i64,
+    pool: MySqlPool,
+) -> Result<(Vec<UserModel>, i64), sqlx::Error> {
+    let mut count_query = QueryBuilder::new("SELECT COUNT(*) FROM users");
+    push_user_filters(&mut count_query, filter);
+    let (total,): (i64,) = count_query.build_query_as().fetch_one(&pool).await?;
+
+    let mut query = QueryBuilder::new("SELECT * FROM users");
+    push_user_filters(&mut query, filter);
+    query
+        .push(" ORDER BY created_at DESC, id LIMIT ")
+        .push_bind(per_page)
+        .push(" OFFSET ")
+        .push_bind((page - 1) * per_page);
+
+    let users = query.build_query_as::<UserModel>().fetch_all(&pool).await?;
+
+    Ok((users, total))
+}
+
+pub async fn set_user_role(
+    user_id: &str,
+    role: UserRole,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let query_result = sqlx::query(
+        r#"
+            UPDATE users
+            SET role = ?
+            WHERE id = ?
+        "#,
+    )
+    .bind(role.to_str())
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string());
+
+    Ok(query_result?)
+}
+
+/// Disable or enable an account. Disabling again keeps the first timestamp.
+pub async fn set_user_disabled(
+    user_id: &str,
+    disabled: bool,
+    pool: MySqlPool,
+) -> Result<MySqlQueryResult, String> {
+    let query_result = sqlx::query(
+        r#"
+            UPDATE users
+            SET disabled_at = IF(?, COALESCE(disabled_at, NOW()), NULL)
+            WHERE id = ?
+        "#,
+    )
+    .bind(disabled)
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string());
+
+    Ok(query_result?)
+}
+
+/// Delete the account for good. Organizations nobody else is a member of go
+/// with it, stock movements it recorded stay but no longer name it. Returns
+/// `false` and deletes nothing when it is the last active admin.
+pub async fn delete_user_account(user_id: &str, pool: MySqlPool) -> Result<bool, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    // Locking the admin rows keeps two admins from deleting each other at once.
+    let admins: Vec<String> = sqlx::query_scalar(
+        r#"
+            SELECT id
+            FROM users
+            WHERE role = 'admin' AND disabled_at IS NULL
+            FOR UPDATE
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    if admins.len() == 1 && admins[0] == user_id {
+        tx.rollback().await.map_err(|e| e.to_string())?;
+        return Ok(false);
+    }
+
+    sqlx::query(
+        r#"
+            DELETE FROM organizations
+            WHERE id IN (SELECT organization_id FROM organization_members WHERE user_id = ?)
+            AND NOT EXISTS (
+                SELECT 1 FROM organization_members other
+                WHERE other.organization_id = organizations.id AND other.user_id <> ?
+            )
+        "#,
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    sqlx::query(
+        r#"
+            UPDATE stock_movements
+            SET created_by = NULL
+            WHERE created_by = ?
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    sqlx::query(
+        r#"
+            DELETE FROM users
+            WHERE id = ?
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err: sqlx::Error| err.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(true)
+}
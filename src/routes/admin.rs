@@ -0,0 +1,113 @@
+use crate::{
+    handlers::{
+        admin_handler::{
+            get_user_handler, get_user_logins_handler, get_user_permissions_handler,
+            get_users_handler, reset_user_password_handler, set_user_disabled_handler,
+            set_user_roles_handler, set_user_verified_handler, unlock_user_handler,
+            update_user_role_handler,
+        },
+        role_handler::{
+            create_role_handler, delete_role_handler, get_permissions_handler, get_roles_handler,
+            update_role_permissions_handler,
+        },
+    },
+    models::permission,
+    utils::extractor::RequirePermission,
+};
+use actix_web::web;
+
+pub fn admin_config(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/api/admin")
+        .route(
+            "/users",
+            web::get()
+                .to(get_users_handler)
+                .wrap(RequirePermission::new(permission::USERS_MANAGE)),
+        )
+        .route(
+            "/users/{id}",
+            web::get()
+                .to(get_user_handler)
+                .wrap(RequirePermission::new(permission::USERS_MANAGE)),
+        )
+        .route(
+            "/users/{id}/role",
+            web::patch()
+                .to(update_user_role_handler)
+                .wrap(RequirePermission::new(permission::USERS_MANAGE)),
+        )
+        .route(
+            "/users/{id}/roles",
+            web::patch()
+                .to(set_user_roles_handler)
+                .wrap(RequirePermission::new(permission::ROLES_MANAGE)),
+        )
+        .route(
+            "/users/{id}/permissions",
+            web::get()
+                .to(get_user_permissions_handler)
+                .wrap(RequirePermission::new(permission::USERS_MANAGE)),
+        )
+        .route(
+            "/users/{id}/logins",
+            web::get()
+                .to(get_user_logins_handler)
+                .wrap(RequirePermission::new(permission::USERS_MANAGE)),
+        )
+        .route(
+            "/users/{id}/verified",
+            web::patch()
+                .to(set_user_verified_handler)
+                .wrap(RequirePermission::new(permission::USERS_MANAGE)),
+        )
+        .route(
+            "/users/{id}/disabled",
+            web::patch()
+                .to(set_user_disabled_handler)
+                .wrap(RequirePermission::new(permission::USERS_MANAGE)),
+        )
+        .route(
+            "/users/{id}/password-reset",
+            web::post()
+                .to(reset_user_password_handler)
+                .wrap(RequirePermission::new(permission::USERS_MANAGE)),
+        )
+        .route(
+            "/users/{id}/lockout",
+            web::delete()
+                .to(unlock_user_handler)
+                .wrap(RequirePermission::new(permission::USERS_MANAGE)),
+        )
+        .route(
+            "/permissions",
+            web::get()
+                .to(get_permissions_handler)
+                .wrap(RequirePermission::new(permission::ROLES_MANAGE)),
+        )
+        .route(
+            "/roles",
+            web::get()
+                .to(get_roles_handler)
+                .wrap(RequirePermission::new(permission::ROLES_MANAGE)),
+        )
+        .route(
+            "/roles",
+            web::post()
+                .to(create_role_handler)
+                .wrap(RequirePermission::new(permission::ROLES_MANAGE)),
+        )
+        .route(
+            "/roles/{name}",
+            web::delete()
+                .to(delete_role_handler)
+                .wrap(RequirePermission::new(permission::ROLES_MANAGE)),
+        )
+        .route(
+            "/roles/{name}/permissions",
+            web::patch()
+                .to(update_role_permissions_handler)
+                .wrap(RequirePermission::new(permission::ROLES_MANAGE)),
+        );
+
+    conf.service(scope);
+}
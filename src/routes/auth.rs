@@ -2,9 +2,12 @@ use actix_web::web;
 
 use crate::{
     handlers::auth_handler::{
+        forgot_password_handler, get_csrf_token_handler, login_two_factor_handler,
         login_user_handler, logout_user_handler, refresh_token_handler, register_user_handler,
+        resend_verification_handler, reset_password_handler, verify_email_handler,
+        verify_email_page_handler,
     },
-    models::user::UserRole,
+    handlers::oidc_handler::{oidc_callback_handler, oidc_login_handler},
     utils::extractor::RequireAuth,
 };
 
@@ -12,16 +15,26 @@ pub fn auth_config(conf: &mut web::ServiceConfig) {
     let scope = web::scope("/auth")
         .route("/register", web::post().to(register_user_handler))
         .route("/login", web::post().to(login_user_handler))
+        .route("/login/2fa", web::post().to(login_two_factor_handler))
+        .route("/refresh", web::post().to(refresh_token_handler))
+        // Kept for app versions that still refresh with GET.
         .route("/refresh", web::get().to(refresh_token_handler))
+        .route("/csrf", web::get().to(get_csrf_token_handler))
+        .route("/oidc/login", web::get().to(oidc_login_handler))
+        .route("/oidc/callback", web::get().to(oidc_callback_handler))
+        .route("/verify-email", web::get().to(verify_email_page_handler))
+        .route("/verify-email", web::post().to(verify_email_handler))
+        .route(
+            "/verify-email/resend",
+            web::post().to(resend_verification_handler),
+        )
+        .route("/forgot-password", web::post().to(forgot_password_handler))
+        .route("/reset-password", web::post().to(reset_password_handler))
         .route(
             "/logout",
             web::post()
                 .to(logout_user_handler)
-                .wrap(RequireAuth::allowed_roles(vec![
-                    UserRole::User,
-                    UserRole::Moderator,
-                    UserRole::Admin,
-                ])),
+                .wrap(RequireAuth::authenticated().two_factor_exempt()),
         );
 
     conf.service(scope);
@@ -1,40 +1,85 @@
 use actix_web::web;
 
 use crate::{
-    handlers::barang_handler::{get_barang_handler, insert_barang_handler, sync_barang_handler},
-    models::user::UserRole,
-    utils::extractor::RequireAuth,
+    handlers::{
+        barang_handler::{
+            bulk_category_barang_handler, bulk_delete_barang_handler, bulk_price_barang_handler,
+            delete_barang_handler, get_barang_by_id_handler, get_barang_handler,
+            insert_barang_handler, sync_barang_handler, update_barang_handler,
+        },
+        inventory_handler::{stock_in_handler, stock_out_handler},
+    },
+    models::permission,
+    utils::extractor::RequirePermission,
 };
 
 pub fn barang_config(conf: &mut web::ServiceConfig) {
     let scope = web::scope("/api/barang")
         .route(
             "",
-            web::get().to(get_barang_handler),
-            // .wrap(RequireAuth::allowed_roles(vec![
-            //     UserRole::User,
-            //     UserRole::Moderator,
-            //     UserRole::Admin,
-            // ])),
+            web::get()
+                .to(get_barang_handler)
+                .wrap(RequirePermission::new(permission::BARANG_READ)),
         )
         .route(
             "",
             web::post()
                 .to(insert_barang_handler)
-                .wrap(RequireAuth::allowed_roles(vec![
-                    UserRole::User,
-                    UserRole::Moderator,
-                    UserRole::Admin,
-                ])),
+                .wrap(RequirePermission::new(permission::BARANG_WRITE)),
         )
         .route(
             "/sync",
-            web::post().to(sync_barang_handler),
-            // .wrap(RequireAuth::allowed_roles(vec![
-            //     UserRole::User,
-            //     UserRole::Moderator,
-            //     UserRole::Admin,
-            // ])),
+            web::post()
+                .to(sync_barang_handler)
+                .wrap(RequirePermission::new(permission::BARANG_WRITE)),
+        )
+        .route(
+            "/bulk/price",
+            web::post()
+                .to(bulk_price_barang_handler)
+                .wrap(RequirePermission::new(permission::BARANG_WRITE)),
+        )
+        .route(
+            "/bulk/category",
+            web::post()
+                .to(bulk_category_barang_handler)
+                .wrap(RequirePermission::new(permission::BARANG_WRITE)),
+        )
+        .route(
+            "/bulk/delete",
+            web::post()
+                .to(bulk_delete_barang_handler)
+                .wrap(RequirePermission::new(permission::BARANG_DELETE)),
+        )
+        .route(
+            "/{id}",
+            web::get()
+                .to(get_barang_by_id_handler)
+                .wrap(RequirePermission::new(permission::BARANG_READ)),
+        )
+        .route(
+            "/{id}",
+            web::patch()
+                .to(update_barang_handler)
+                .wrap(RequirePermission::new(permission::BARANG_WRITE)),
+        )
+        .route(
+            "/{id}",
+            web::delete()
+                .to(delete_barang_handler)
+                .wrap(RequirePermission::new(permission::BARANG_DELETE)),
+        )
+        .route(
+            "/{id}/stock-in",
+            web::post()
+                .to(stock_in_handler)
+                .wrap(RequirePermission::new(permission::INVENTORY_WRITE)),
+        )
+        .route(
+            "/{id}/stock-out",
+            web::post()
+                .to(stock_out_handler)
+                .wrap(RequirePermission::new(permission::INVENTORY_WRITE)),
         );
 
     conf.service(scope);
@@ -0,0 +1,17 @@
+use actix_web::web;
+
+use crate::{
+    handlers::dashboard_handler::get_dashboard_handler, models::permission,
+    utils::extractor::RequirePermission,
+};
+
+pub fn dashboard_config(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/api/dashboard").route(
+        "",
+        web::get()
+            .to(get_dashboard_handler)
+            .wrap(RequirePermission::new(permission::DASHBOARD_READ)),
+    );
+
+    conf.service(scope);
+}
@@ -1,5 +1,10 @@
+pub mod admin;
 pub mod auth;
 pub mod barang;
+pub mod dashboard;
+pub mod organization;
 pub mod pdf;
+pub mod report;
 pub mod storage;
 pub mod user;
+pub mod well_known;
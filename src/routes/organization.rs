@@ -0,0 +1,58 @@
+use actix_web::web;
+
+use crate::{
+    handlers::organization_handler::{
+        add_member_handler, create_organization_handler, create_store_handler, get_members_handler,
+        get_organizations_handler, get_stores_handler, remove_member_handler,
+    },
+    models::permission,
+    utils::extractor::RequirePermission,
+};
+
+pub fn organization_config(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/api/organizations")
+        .route(
+            "",
+            web::get()
+                .to(get_organizations_handler)
+                .wrap(RequirePermission::new(permission::ORGANIZATION_READ)),
+        )
+        .route(
+            "",
+            web::post()
+                .to(create_organization_handler)
+                .wrap(RequirePermission::new(permission::ORGANIZATION_WRITE)),
+        )
+        .route(
+            "/{id}/stores",
+            web::get()
+                .to(get_stores_handler)
+                .wrap(RequirePermission::new(permission::ORGANIZATION_READ)),
+        )
+        .route(
+            "/{id}/stores",
+            web::post()
+                .to(create_store_handler)
+                .wrap(RequirePermission::new(permission::ORGANIZATION_WRITE)),
+        )
+        .route(
+            "/{id}/members",
+            web::get()
+                .to(get_members_handler)
+                .wrap(RequirePermission::new(permission::ORGANIZATION_READ)),
+        )
+        .route(
+            "/{id}/members",
+            web::post()
+                .to(add_member_handler)
+                .wrap(RequirePermission::new(permission::ORGANIZATION_WRITE)),
+        )
+        .route(
+            "/{id}/members/{user_id}",
+            web::delete()
+                .to(remove_member_handler)
+                .wrap(RequirePermission::new(permission::ORGANIZATION_WRITE)),
+        );
+
+    conf.service(scope);
+}
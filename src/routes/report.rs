@@ -0,0 +1,27 @@
+use actix_web::web;
+
+use crate::{
+    handlers::inventory_handler::{
+        get_inventory_valuation_handler, get_inventory_valuation_pdf_handler,
+    },
+    models::permission,
+    utils::extractor::RequirePermission,
+};
+
+pub fn report_config(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/api/reports")
+        .route(
+            "/inventory-valuation",
+            web::get()
+                .to(get_inventory_valuation_handler)
+                .wrap(RequirePermission::new(permission::REPORT_READ)),
+        )
+        .route(
+            "/inventory-valuation/pdf",
+            web::get()
+                .to(get_inventory_valuation_pdf_handler)
+                .wrap(RequirePermission::new(permission::REPORT_READ)),
+        );
+
+    conf.service(scope);
+}
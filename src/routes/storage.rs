@@ -1,7 +1,4 @@
-use crate::{
-    handlers::storage_handler::get_image_handler, models::user::UserRole,
-    utils::extractor::RequireAuth,
-};
+use crate::{handlers::storage_handler::get_image_handler, utils::extractor::RequireAuth};
 use actix_web::web;
 
 pub fn storage_config(conf: &mut web::ServiceConfig) {
@@ -9,11 +6,7 @@ pub fn storage_config(conf: &mut web::ServiceConfig) {
         "/img/{title}",
         web::get()
             .to(get_image_handler)
-            .wrap(RequireAuth::allowed_roles(vec![
-                UserRole::User,
-                UserRole::Moderator,
-                UserRole::Admin,
-            ])),
+            .wrap(RequireAuth::authenticated()),
     );
 
     conf.service(scope);
@@ -1,6 +1,20 @@
 use crate::{
-    handlers::user_handler::{get_me_handler, update_photo_handler},
-    models::user::UserRole,
+    handlers::{
+        api_key_handler::{create_api_key_handler, get_api_keys_handler, revoke_api_key_handler},
+        login_event_handler::get_my_logins_handler,
+        role_handler::get_my_permissions_handler,
+        session_handler::{
+            get_sessions_handler, revoke_other_sessions_handler, revoke_session_handler,
+        },
+        two_factor_handler::{
+            disable_two_factor_handler, enable_two_factor_handler, get_two_factor_handler,
+            regenerate_recovery_codes_handler, setup_two_factor_handler,
+        },
+        user_handler::{
+            change_email_handler, change_password_handler, delete_me_handler, export_me_handler,
+            get_me_handler, update_me_handler, update_photo_handler,
+        },
+    },
     utils::extractor::RequireAuth,
 };
 use actix_web::web;
@@ -11,21 +25,131 @@ pub fn user_config(conf: &mut web::ServiceConfig) {
             "/me",
             web::get()
                 .to(get_me_handler)
-                .wrap(RequireAuth::allowed_roles(vec![
-                    UserRole::User,
-                    UserRole::Moderator,
-                    UserRole::Admin,
-                ])),
+                .wrap(RequireAuth::authenticated().two_factor_exempt()),
         )
         .route(
             "/me",
+            web::patch()
+                .to(update_me_handler)
+                .wrap(RequireAuth::authenticated().session_only()),
+        )
+        .route(
+            "/me",
+            web::delete()
+                .to(delete_me_handler)
+                .wrap(RequireAuth::authenticated().session_only()),
+        )
+        .route(
+            "/me/export",
+            web::get()
+                .to(export_me_handler)
+                .wrap(RequireAuth::authenticated().session_only()),
+        )
+        .route(
+            "/me/photo",
             web::patch()
                 .to(update_photo_handler)
-                .wrap(RequireAuth::allowed_roles(vec![
-                    UserRole::User,
-                    UserRole::Moderator,
-                    UserRole::Admin,
-                ])),
+                .wrap(RequireAuth::authenticated().session_only()),
+        )
+        .route(
+            "/me/password",
+            web::patch()
+                .to(change_password_handler)
+                .wrap(RequireAuth::authenticated().session_only()),
+        )
+        .route(
+            "/me/email",
+            web::patch()
+                .to(change_email_handler)
+                .wrap(RequireAuth::authenticated().session_only()),
+        )
+        .route(
+            "/me/permissions",
+            web::get()
+                .to(get_my_permissions_handler)
+                .wrap(RequireAuth::authenticated()),
+        )
+        .route(
+            "/me/logins",
+            web::get()
+                .to(get_my_logins_handler)
+                .wrap(RequireAuth::authenticated().session_only()),
+        )
+        .route(
+            "/me/sessions",
+            web::get()
+                .to(get_sessions_handler)
+                .wrap(RequireAuth::authenticated().session_only()),
+        )
+        .route(
+            "/me/sessions",
+            web::delete()
+                .to(revoke_other_sessions_handler)
+                .wrap(RequireAuth::authenticated().session_only()),
+        )
+        .route(
+            "/me/sessions/{id}",
+            web::delete()
+                .to(revoke_session_handler)
+                .wrap(RequireAuth::authenticated().session_only()),
+        )
+        .route(
+            "/me/2fa",
+            web::get().to(get_two_factor_handler).wrap(
+                RequireAuth::authenticated()
+                    .session_only()
+                    .two_factor_exempt(),
+            ),
+        )
+        .route(
+            "/me/2fa",
+            web::delete().to(disable_two_factor_handler).wrap(
+                RequireAuth::authenticated()
+                    .session_only()
+                    .two_factor_exempt(),
+            ),
+        )
+        .route(
+            "/me/2fa/setup",
+            web::post().to(setup_two_factor_handler).wrap(
+                RequireAuth::authenticated()
+                    .session_only()
+                    .two_factor_exempt(),
+            ),
+        )
+        .route(
+            "/me/2fa/enable",
+            web::post().to(enable_two_factor_handler).wrap(
+                RequireAuth::authenticated()
+                    .session_only()
+                    .two_factor_exempt(),
+            ),
+        )
+        .route(
+            "/me/2fa/recovery-codes",
+            web::post().to(regenerate_recovery_codes_handler).wrap(
+                RequireAuth::authenticated()
+                    .session_only()
+                    .two_factor_exempt(),
+            ),
+        )
+        .route(
+            "/me/api-keys",
+            web::get()
+                .to(get_api_keys_handler)
+                .wrap(RequireAuth::authenticated().session_only()),
+        )
+        .route(
+            "/me/api-keys",
+            web::post()
+                .to(create_api_key_handler)
+                .wrap(RequireAuth::authenticated().session_only()),
+        )
+        .route(
+            "/me/api-keys/{id}",
+            web::delete()
+                .to(revoke_api_key_handler)
+                .wrap(RequireAuth::authenticated().session_only()),
         );
 
     conf.service(scope);
@@ -0,0 +1,9 @@
+use actix_web::web;
+
+use crate::handlers::jwks_handler::get_jwks_handler;
+
+pub fn well_known_config(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/.well-known").route("/jwks.json", web::get().to(get_jwks_handler));
+
+    conf.service(scope);
+}
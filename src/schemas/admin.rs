@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::models::user::UserRole;
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, IntoParams)]
+pub struct ListUsersSchema {
+    /// Part of the name or email.
+    pub search: Option<String>,
+    pub role: Option<UserRole>,
+    pub verified: Option<bool>,
+    pub disabled: Option<bool>,
+    /// Page number starting at 1, defaults to 1.
+    #[validate(range(min = 1))]
+    pub page: Option<i64>,
+    /// Users per page, defaults to 20.
+    #[validate(range(min = 1, max = 100))]
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateUserRoleSchema {
+    pub role: UserRole,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetUserVerifiedSchema {
+    pub verified: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetUserDisabledSchema {
+    pub disabled: bool,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::models::api_key::ApiKeyScope;
+
+#[derive(Validate, Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateApiKeySchema {
+    #[validate(length(min = 1, max = 100, message = "Name is required"))]
+    pub name: String,
+    #[validate(length(min = 1, message = "At least one scope is required"))]
+    pub scopes: Vec<ApiKeyScope>,
+    /// Days until the key stops working, `None` for a key that never expires.
+    #[validate(range(
+        min = 1,
+        max = 3650,
+        message = "Expiry must be between 1 and 3650 days"
+    ))]
+    pub expires_in_days: Option<i64>,
+}
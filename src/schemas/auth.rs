@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
@@ -11,10 +11,7 @@ pub struct RegisterUserSchema {
         email(message = "Email is invalid")
     )]
     pub email: String,
-    #[validate(
-        length(min = 1, message = "Password is required"),
-        length(min = 6, message = "Password must be at least 6 characters")
-    )]
+    #[validate(length(min = 1, message = "Password is required"))]
     pub password: String,
     #[validate(
         length(min = 1, message = "Please confirm your password"),
@@ -31,15 +28,74 @@ pub struct LoginUserSchema {
         email(message = "Email is invalid")
     )]
     pub email: String,
-    #[validate(
-        length(min = 1, message = "Password is required"),
-        length(min = 6, message = "Password must be at least 6 characters")
-    )]
+    #[validate(length(
+        min = 1,
+        max = 256,
+        message = "Password is required and at most 256 characters"
+    ))]
     pub password: String,
 }
 
+/// Second step of a login, answering the challenge with a TOTP or recovery
+/// code.
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorLoginSchema {
+    #[validate(length(min = 1, message = "Challenge is required"))]
+    pub challenge: String,
+    #[validate(length(min = 1, max = 32, message = "Code is required"))]
+    pub code: String,
+}
+
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RefreshTokenSchema {
     #[validate(length(min = 1, message = "Refresh token is required"))]
     pub refresh_token: String,
 }
+
+/// Query the OpenID Connect provider redirects back with, either `code` and
+/// `state` or an `error`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, IntoParams)]
+pub struct OidcCallbackSchema {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema, IntoParams)]
+pub struct VerifyEmailSchema {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ResendVerificationSchema {
+    #[validate(
+        length(min = 1, message = "Email is required"),
+        email(message = "Email is invalid")
+    )]
+    pub email: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ForgotPasswordSchema {
+    #[validate(
+        length(min = 1, message = "Email is required"),
+        email(message = "Email is invalid")
+    )]
+    pub email: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ResetPasswordSchema {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+    #[validate(
+        length(min = 1, message = "Please confirm your password"),
+        must_match(other = "password", message = "Passwords do not match")
+    )]
+    #[serde(rename = "passwordConfirm")]
+    pub password_confirm: String,
+}
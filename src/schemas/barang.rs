@@ -1,16 +1,54 @@
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
-use validator::Validate;
+use validator::{Validate, ValidationError};
+
+use crate::utils::money::Money;
 
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
 pub struct InsertBarangSchema {
     #[validate(length(min = 1, message = "Name is required"))]
     pub name: String,
-    #[validate(range(min = 0))]
-    pub price: i32,
+    #[validate(length(max = 100))]
+    pub category: Option<String>,
+    pub tags: Option<Vec<String>>,
+    #[validate(custom = "crate::utils::money::validate_non_negative")]
+    #[schema(value_type = String, example = "11000.00")]
+    pub price: Money,
     #[validate(range(min = 0))]
     pub stock: i32,
     pub expired_at: Option<String>,
+    /// Purchase cost of the opening stock, defaults to `price`.
+    #[validate(custom = "crate::utils::money::validate_non_negative")]
+    #[schema(value_type = Option<String>, example = "9500.00")]
+    pub unit_cost: Option<Money>,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateBarangSchema {
+    #[validate(length(min = 1, message = "Name is required"))]
+    pub name: Option<String>,
+    #[validate(length(max = 100))]
+    pub category: Option<String>,
+    pub tags: Option<Vec<String>>,
+    #[validate(custom = "crate::utils::money::validate_non_negative")]
+    #[schema(value_type = Option<String>, example = "11000.00")]
+    pub price: Option<Money>,
+    pub expired_at: Option<String>,
+}
+
+/// Stock is left out, after the opening stock it only changes through the
+/// stock-in and stock-out movements that the valuation is built from.
+impl From<InsertBarangSchema> for UpdateBarangSchema {
+    fn from(barang: InsertBarangSchema) -> Self {
+        UpdateBarangSchema {
+            name: Some(barang.name),
+            category: barang.category,
+            tags: barang.tags,
+            price: Some(barang.price),
+            expired_at: barang.expired_at,
+        }
+    }
 }
 
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, IntoParams)]
@@ -20,5 +58,130 @@ pub struct GetBarangSchema {
 
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema, IntoParams)]
 pub struct SyncBarangSchema {
-    pub barang: Vec<InsertBarangSchema>,
+    pub barang: Vec<SyncBarangItemSchema>,
+}
+
+/// A barang sent by the app while syncing. Items without `id` are inserted,
+/// items with `id` update the server copy when `version` still matches it.
+/// `stock` is only used as opening stock of inserted items.
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncBarangItemSchema {
+    pub id: Option<String>,
+    pub version: Option<i32>,
+    #[serde(flatten)]
+    #[validate]
+    pub barang: InsertBarangSchema,
+}
+
+/// Picks the barang a bulk operation applies to, either explicit `ids` or a
+/// filter on name, category and tag. At least one of them is required so a
+/// bulk request never silently targets every barang.
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+#[validate(schema(function = "validate_selector"))]
+pub struct BarangSelectorSchema {
+    pub ids: Option<Vec<String>>,
+    pub name: Option<String>,
+    pub category: Option<String>,
+    pub tag: Option<String>,
+}
+
+fn validate_selector(selector: &BarangSelectorSchema) -> Result<(), ValidationError> {
+    let has_ids = selector.ids.as_ref().is_some_and(|ids| !ids.is_empty());
+    let has_filter = [&selector.name, &selector.category, &selector.tag]
+        .iter()
+        .any(|value| {
+            value
+                .as_deref()
+                .is_some_and(|value| !value.trim().is_empty())
+        });
+
+    if has_ids || has_filter {
+        Ok(())
+    } else {
+        Err(ValidationError::new(
+            "ids or a name, category or tag filter is required",
+        ))
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceAdjustmentMode {
+    /// `value` is a percentage of the current price, e.g. `10` or `-5`.
+    #[default]
+    Percentage,
+    /// `value` is an amount added to the current price.
+    Fixed,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingMode {
+    #[default]
+    Nearest,
+    Up,
+    Down,
+}
+
+/// Round adjusted prices to a multiple of `step`, e.g. `500` for Rp 500.
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PriceRoundingSchema {
+    pub mode: RoundingMode,
+    #[validate(custom = "crate::utils::money::validate_money_step")]
+    #[schema(value_type = String, example = "500")]
+    pub step: Decimal,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BulkPriceSchema {
+    #[validate]
+    pub selector: BarangSelectorSchema,
+    pub mode: PriceAdjustmentMode,
+    #[validate(custom = "crate::utils::money::validate_amount_range")]
+    #[schema(value_type = String, example = "10")]
+    pub value: Decimal,
+    #[validate]
+    pub rounding: Option<PriceRoundingSchema>,
+    /// Report the affected barang without saving anything.
+    #[serde(default)]
+    pub preview: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TagsMode {
+    #[default]
+    Replace,
+    Add,
+    Remove,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+#[validate(schema(function = "validate_bulk_category"))]
+pub struct BulkCategorySchema {
+    #[validate]
+    pub selector: BarangSelectorSchema,
+    #[validate(length(min = 1, max = 100))]
+    pub category: Option<String>,
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub tags_mode: TagsMode,
+    #[serde(default)]
+    pub preview: bool,
+}
+
+fn validate_bulk_category(body: &BulkCategorySchema) -> Result<(), ValidationError> {
+    if body.category.is_none() && body.tags.is_none() {
+        return Err(ValidationError::new("category or tags is required"));
+    }
+
+    Ok(())
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BulkDeleteSchema {
+    #[validate]
+    pub selector: BarangSelectorSchema,
+    #[serde(default)]
+    pub preview: bool,
 }
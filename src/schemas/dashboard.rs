@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+use validator::Validate;
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, IntoParams)]
+pub struct GetDashboardSchema {
+    /// Number of recent activity entries, defaults to 10.
+    #[validate(range(min = 1, max = 50))]
+    pub activity_limit: Option<i64>,
+}
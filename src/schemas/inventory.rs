@@ -0,0 +1,39 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::utils::money::Money;
+
+#[derive(Validate, Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StockInSchema {
+    #[validate(range(min = 1, message = "Quantity must be at least 1"))]
+    pub quantity: i32,
+    #[validate(custom = "crate::utils::money::validate_non_negative")]
+    #[schema(value_type = String, example = "9500.00")]
+    pub unit_cost: Money,
+    #[validate(length(max = 255))]
+    pub note: Option<String>,
+}
+
+#[derive(Validate, Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StockOutSchema {
+    #[validate(range(min = 1, message = "Quantity must be at least 1"))]
+    pub quantity: i32,
+    /// Selling price per unit when the stock leaves through a sale.
+    #[validate(custom = "crate::utils::money::validate_non_negative")]
+    #[schema(value_type = Option<String>, example = "11000.00")]
+    pub unit_price: Option<Money>,
+    #[validate(length(max = 255))]
+    pub note: Option<String>,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, IntoParams)]
+pub struct InventoryValuationSchema {
+    /// `fifo` or `average`, defaults to `INVENTORY_VALUATION_METHOD`.
+    pub method: Option<String>,
+    /// First day counted in cost of goods sold.
+    pub from: Option<NaiveDate>,
+    /// Day the stock on hand is valued at, defaults to today.
+    pub to: Option<NaiveDate>,
+}
@@ -1,3 +1,9 @@
+pub mod admin;
+pub mod api_key;
 pub mod auth;
 pub mod barang;
+pub mod dashboard;
+pub mod inventory;
+pub mod organization;
+pub mod role;
 pub mod user;
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::models::organization::OrganizationRole;
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateOrganizationSchema {
+    #[validate(length(min = 1, max = 100, message = "Name is required"))]
+    pub name: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateStoreSchema {
+    #[validate(length(min = 1, max = 100, message = "Name is required"))]
+    pub name: String,
+    #[validate(length(max = 255))]
+    pub address: Option<String>,
+}
+
+#[derive(Validate, Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AddMemberSchema {
+    #[validate(
+        length(min = 1, message = "Email is required"),
+        email(message = "Email is invalid")
+    )]
+    pub email: String,
+    pub role: OrganizationRole,
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+use crate::models::permission;
+
+fn validate_role_name(name: &str) -> Result<(), ValidationError> {
+    let valid = name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+    if !valid {
+        return Err(ValidationError::new(
+            "Role name may only contain lowercase letters, digits, '_' and '-'",
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_permissions(permissions: &[String]) -> Result<(), ValidationError> {
+    if !permissions.iter().all(|p| permission::is_known(p)) {
+        return Err(ValidationError::new("Unknown permission"));
+    }
+
+    Ok(())
+}
+
+#[derive(Validate, Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateRoleSchema {
+    #[validate(
+        length(min = 1, max = 50, message = "Role name is required"),
+        custom = "validate_role_name"
+    )]
+    pub name: String,
+    #[validate(length(max = 255))]
+    pub description: Option<String>,
+    /// Names from `GET /api/admin/permissions`, or `*` for all of them.
+    #[validate(custom = "validate_permissions")]
+    pub permissions: Vec<String>,
+}
+
+#[derive(Validate, Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateRolePermissionsSchema {
+    #[validate(custom = "validate_permissions")]
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetUserRolesSchema {
+    /// Roles granted on top of the user's base role, replacing the current ones.
+    pub roles: Vec<String>,
+}
@@ -1,6 +1,124 @@
-use utoipa::ToSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::{Validate, ValidationError};
 
 #[derive(Debug, Clone, ToSchema)]
 pub struct UpdatePhotoUserSchema {
     pub file: Option<Vec<u8>>,
 }
+
+fn validate_name(name: &str) -> Result<(), ValidationError> {
+    if name.trim().is_empty() {
+        return Err(ValidationError::new("Name is required"));
+    }
+
+    Ok(())
+}
+
+fn validate_phone(phone: &str) -> Result<(), ValidationError> {
+    let phone = phone.trim();
+    let digits = phone.chars().filter(|c| c.is_ascii_digit()).count();
+    let valid = phone
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | ' ' | '(' | ')'));
+
+    // Empty clears the phone number.
+    if !phone.is_empty() && (!valid || digits < 6) {
+        return Err(ValidationError::new("Phone number is invalid"));
+    }
+
+    Ok(())
+}
+
+/// A language tag like `id`, `en` or `en-US`.
+fn validate_language(language: &str) -> Result<(), ValidationError> {
+    let language = language.trim();
+    let mut parts = language.split('-');
+    let primary = parts.next().unwrap_or_default();
+    let valid = (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|part| {
+            (2..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+
+    if !language.is_empty() && !valid {
+        return Err(ValidationError::new(
+            "Language must be a tag like en or en-US",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Fields left out stay as they are, an empty phone, address or language
+/// clears it.
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateProfileSchema {
+    #[validate(
+        length(max = 100, message = "Name must not be more than 100 characters"),
+        custom = "validate_name"
+    )]
+    pub name: Option<String>,
+    #[validate(length(max = 30), custom = "validate_phone")]
+    pub phone: Option<String>,
+    #[validate(length(max = 255, message = "Address must not be more than 255 characters"))]
+    pub address: Option<String>,
+    #[validate(length(max = 16), custom = "validate_language")]
+    #[serde(rename = "preferredLanguage")]
+    pub preferred_language: Option<String>,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChangePasswordSchema {
+    #[validate(length(min = 1, message = "Current password is required"))]
+    pub current_password: String,
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+    #[validate(
+        length(min = 1, message = "Please confirm your password"),
+        must_match(other = "password", message = "Passwords do not match")
+    )]
+    #[serde(rename = "passwordConfirm")]
+    pub password_confirm: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChangeEmailSchema {
+    #[validate(length(min = 1, message = "Current password is required"))]
+    pub current_password: String,
+    #[validate(
+        length(min = 1, message = "Email is required"),
+        email(message = "Email is invalid")
+    )]
+    pub email: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeleteAccountSchema {
+    #[validate(length(min = 1, message = "Current password is required"))]
+    pub current_password: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorCodeSchema {
+    #[validate(length(min = 1, max = 32, message = "Code is required"))]
+    pub code: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DisableTwoFactorSchema {
+    #[validate(length(min = 1, message = "Current password is required"))]
+    pub current_password: String,
+    #[validate(length(min = 1, max = 32, message = "Code is required"))]
+    pub code: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize, IntoParams)]
+pub struct ListLoginEventsSchema {
+    /// Page number starting at 1, defaults to 1.
+    #[validate(range(min = 1))]
+    pub page: Option<i64>,
+    /// Attempts per page, defaults to 20.
+    #[validate(range(min = 1, max = 100))]
+    pub per_page: Option<i64>,
+}
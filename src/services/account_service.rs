@@ -0,0 +1,177 @@
+use redis::aio::ConnectionManager;
+use sanitize_filename::sanitize;
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+use crate::{
+    dtos::{api_key::ApiKeyDto, login_event::LoginEventDto, session::SessionDto, user::UserDto},
+    models::user::UserModel,
+    repositories::{
+        api_key_repository, identity_repository, inventory_repository, login_event_repository,
+        organization_repository, user_repository,
+    },
+    services::session_service,
+    utils::{config::Config, password},
+};
+
+/// Photo every account starts with, it is shared and never removed.
+const DEFAULT_PHOTO: &str = "default.png";
+
+#[derive(Debug)]
+pub enum DeleteOutcome {
+    Deleted,
+    WrongPassword,
+    /// The account is the last enabled admin.
+    LastAdmin,
+    /// Names of organizations that would be left without an owner.
+    OwnsOrganizations(Vec<String>),
+}
+
+pub struct AccountService {
+    pool: MySqlPool,
+    redis: ConnectionManager,
+}
+
+impl AccountService {
+    pub fn new(pool: MySqlPool, redis: ConnectionManager) -> Self {
+        Self { pool, redis }
+    }
+
+    /// Where the uploaded photo of `user` is stored, `None` for the default one.
+    fn photo_path(user: &UserModel, config: &Config) -> Option<String> {
+        let photo = sanitize(&user.photo);
+        (!photo.is_empty() && photo != DEFAULT_PHOTO)
+            .then(|| format!("{}{}", config.storage_dir, photo))
+    }
+
+    /// Everything stored about `user` as a tar archive: profile, memberships,
+    /// sessions, login history, linked identities, API keys, recorded stock
+    /// movements and the uploaded photo.
+    pub async fn export(
+        &self,
+        user: &UserModel,
+        current_session_id: &str,
+        config: &Config,
+    ) -> Result<Vec<u8>, String> {
+        let organizations =
+            organization_repository::get_user_organizations(&user.id, self.pool.clone())
+                .await
+                .map_err(|e| e.to_string())?;
+        let sessions = session_service::list_sessions(&self.redis, &user.id).await?;
+        let (login_events, _) =
+            login_event_repository::get_user_login_events(&user.id, 1, i64::MAX, self.pool.clone())
+                .await
+                .map_err(|e| e.to_string())?;
+        let identities = identity_repository::get_user_identities(&user.id, self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        let api_keys = api_key_repository::get_user_api_keys(&user.id, self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        let movements = inventory_repository::get_user_movements(&user.id, self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut archive = tar::Builder::new(Vec::new());
+
+        append_json(&mut archive, "profile.json", &UserDto::filter(user))?;
+        append_json(&mut archive, "organizations.json", &organizations)?;
+        append_json(
+            &mut archive,
+            "sessions.json",
+            &sessions
+                .iter()
+                .map(|session| SessionDto::filter(session, current_session_id))
+                .collect::<Vec<_>>(),
+        )?;
+        append_json(
+            &mut archive,
+            "login_history.json",
+            &LoginEventDto::filter_iter(&login_events),
+        )?;
+        append_json(&mut archive, "identities.json", &identities)?;
+        append_json(
+            &mut archive,
+            "api_keys.json",
+            &ApiKeyDto::filter_iter(&api_keys),
+        )?;
+        append_json(&mut archive, "stock_movements.json", &movements)?;
+
+        if let Some(photo_path) = Self::photo_path(user, config) {
+            if std::path::Path::new(&photo_path).is_file() {
+                archive
+                    .append_path_with_name(&photo_path, format!("photo/{}", sanitize(&user.photo)))
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+
+        archive.into_inner().map_err(|e| e.to_string())
+    }
+
+    /// Delete `user` after checking `current_password`: memberships, keys and
+    /// two-factor data go with the account, stock movements are kept without
+    /// their author, the photo is removed and every session revoked.
+    pub async fn delete(
+        &self,
+        user: &UserModel,
+        current_password: &str,
+        config: &Config,
+    ) -> Result<DeleteOutcome, String> {
+        if !password::compare(current_password, &user.password)? {
+            return Ok(DeleteOutcome::WrongPassword);
+        }
+
+        let owned = organization_repository::get_solely_owned_shared_organizations(
+            &user.id,
+            self.pool.clone(),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        if !owned.is_empty() {
+            return Ok(DeleteOutcome::OwnsOrganizations(
+                owned
+                    .into_iter()
+                    .map(|organization| organization.name)
+                    .collect(),
+            ));
+        }
+
+        if !user_repository::delete_user_account(&user.id, self.pool.clone()).await? {
+            return Ok(DeleteOutcome::LastAdmin);
+        }
+
+        // The account is gone, so leftover tokens no longer authenticate
+        // anyone even if this fails.
+        if let Err(e) = session_service::revoke_all_sessions(&self.redis, &user.id).await {
+            eprintln!("🔥 Failed to revoke sessions of deleted user: {}", e);
+        }
+
+        if let Some(photo_path) = Self::photo_path(user, config) {
+            match std::fs::remove_file(&photo_path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => eprintln!("🔥 Failed to remove photo {}: {}", photo_path, e),
+            }
+        }
+
+        Ok(DeleteOutcome::Deleted)
+    }
+}
+
+fn append_json(
+    archive: &mut tar::Builder<Vec<u8>>,
+    path: &str,
+    value: &impl Serialize,
+) -> Result<(), String> {
+    let bytes = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Utc::now().timestamp() as u64);
+    header.set_cksum();
+
+    archive
+        .append_data(&mut header, path, bytes.as_slice())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,139 @@
+use actix_web::http::Method;
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+
+use crate::{
+    models::{api_key::ApiKeyModel, user::UserModel},
+    repositories::{api_key_repository, user_repository},
+    schemas::api_key::CreateApiKeySchema,
+};
+
+/// Every key starts with this, so it can not be mistaken for a JWT and
+/// secret scanners can spot leaked ones.
+pub const API_KEY_PREFIX: &str = "rfa_";
+
+/// Random characters after [`API_KEY_PREFIX`] that identify a key.
+const PREFIX_ID_LENGTH: usize = 8;
+
+/// `last_used_at` is only written when it is older than this many seconds.
+const LAST_USED_PRECISION: i64 = 60;
+
+pub fn is_api_key(token: &str) -> bool {
+    token.starts_with(API_KEY_PREFIX)
+}
+
+fn hash_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+#[derive(Debug)]
+pub enum ApiKeyCheck {
+    Valid {
+        api_key: ApiKeyModel,
+        user: UserModel,
+    },
+    /// Unknown, revoked or expired key.
+    Invalid,
+    /// The key is fine but its scopes do not cover the request.
+    OutOfScope,
+}
+
+pub struct ApiKeyService {
+    pool: MySqlPool,
+}
+
+impl ApiKeyService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a key for `user_id`. Returns the stored key and the plain key,
+    /// which is never shown again.
+    pub async fn create(
+        &self,
+        user_id: &str,
+        body: &CreateApiKeySchema,
+    ) -> Result<(ApiKeyModel, String), String> {
+        let random = uuid::Uuid::new_v4().simple().to_string();
+        let secret = uuid::Uuid::new_v4().simple().to_string();
+        let prefix = format!("{}{}", API_KEY_PREFIX, &random[..PREFIX_ID_LENGTH]);
+        let key = format!("{}_{}", prefix, secret);
+
+        api_key_repository::insert_api_key(
+            &uuid::Uuid::new_v4().to_string(),
+            user_id,
+            &prefix,
+            &hash_key(&key),
+            body,
+            self.pool.clone(),
+        )
+        .await?;
+
+        let api_key = api_key_repository::get_api_key_by_prefix(&prefix, self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Failed to read the new api key".to_string())?;
+
+        Ok((api_key, key))
+    }
+
+    pub async fn list(&self, user_id: &str) -> Result<Vec<ApiKeyModel>, String> {
+        api_key_repository::get_user_api_keys(user_id, self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Delete a key of `user_id`. Returns `false` when it does not exist or
+    /// belongs to someone else.
+    pub async fn revoke(&self, user_id: &str, api_key_id: &str) -> Result<bool, String> {
+        let query_result =
+            api_key_repository::delete_api_key(api_key_id, user_id, self.pool.clone()).await?;
+
+        Ok(query_result.rows_affected() == 1)
+    }
+
+    /// Check `key` for a request with `method` and load its owner.
+    pub async fn authenticate(&self, key: &str, method: &Method) -> Result<ApiKeyCheck, String> {
+        let prefix = match key.get(..API_KEY_PREFIX.len() + PREFIX_ID_LENGTH) {
+            Some(prefix) if is_api_key(prefix) => prefix,
+            _ => return Ok(ApiKeyCheck::Invalid),
+        };
+
+        let api_key = match api_key_repository::get_api_key_by_prefix(prefix, self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            Some(api_key) => api_key,
+            None => return Ok(ApiKeyCheck::Invalid),
+        };
+
+        if api_key.key_hash != hash_key(key) || api_key.is_expired() {
+            return Ok(ApiKeyCheck::Invalid);
+        }
+
+        if !api_key.allows(method) {
+            return Ok(ApiKeyCheck::OutOfScope);
+        }
+
+        let user =
+            match user_repository::get_user(Some(&api_key.user_id), None, None, self.pool.clone())
+                .await
+                .map_err(|e| e.to_string())?
+            {
+                Some(user) if !user.is_disabled() => user,
+                _ => return Ok(ApiKeyCheck::Invalid),
+            };
+
+        let stale = api_key.last_used_at.is_none_or(|last_used_at| {
+            (chrono::Utc::now() - last_used_at).num_seconds() >= LAST_USED_PRECISION
+        });
+        if stale {
+            if let Err(e) = api_key_repository::touch_api_key(&api_key.id, self.pool.clone()).await
+            {
+                eprintln!("🔥 Failed to update api key: {}", e);
+            }
+        }
+
+        Ok(ApiKeyCheck::Valid { api_key, user })
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{repositories::auth_repository, schemas::auth::RegisterUserSchema};
+use crate::{
+    repositories::auth_repository,
+    schemas::auth::RegisterUserSchema,
+    utils::{config::Config, password},
+};
 use actix_web::web::Json;
 use sqlx::{mysql::MySqlQueryResult, MySqlPool};
 
@@ -16,8 +20,13 @@ impl AuthService {
         &self,
         user_id: &String,
         body: Json<RegisterUserSchema>,
+        config: &Config,
     ) -> Result<MySqlQueryResult, String> {
-        let query_result = auth_repository::register_user(&user_id, &body, self.pool.clone()).await;
+        let hashed_password = password::hash(&body.password, config)?;
+
+        let query_result =
+            auth_repository::register_user(&user_id, &body, &hashed_password, self.pool.clone())
+                .await;
 
         Ok(query_result?)
     }
@@ -1,11 +1,43 @@
 use actix_web::web::Json;
+use rust_decimal::{Decimal, RoundingStrategy};
 use sqlx::{mysql::MySqlQueryResult, MySqlPool};
 
 use crate::{
-    models::barang::BarangModel, repositories::barang_repository,
-    schemas::barang::InsertBarangSchema,
+    models::barang::{tags_from_json, BarangModel},
+    repositories::barang_repository,
+    schemas::barang::{
+        BarangSelectorSchema, BulkCategorySchema, BulkDeleteSchema, BulkPriceSchema,
+        InsertBarangSchema, PriceAdjustmentMode, RoundingMode, TagsMode, UpdateBarangSchema,
+    },
+    utils::{
+        extractor::Tenant,
+        money::{max_amount, MONEY_SCALE},
+    },
 };
 
+/// Result of a write guarded by the barang version.
+#[derive(Debug)]
+pub enum VersionedWrite {
+    Applied,
+    NotFound,
+    /// The version did not match, carries the current server copy.
+    Conflict(BarangModel),
+}
+
+/// A barang touched by a bulk operation, `after` is `None` once deleted.
+#[derive(Debug)]
+pub struct BulkChange {
+    pub before: BarangModel,
+    pub after: Option<BarangModel>,
+}
+
+#[derive(Debug)]
+pub enum BulkOutcome {
+    Applied(Vec<BulkChange>),
+    /// Nothing was saved, carries the reason shown to the client.
+    Rejected(String),
+}
+
 #[derive(Debug)]
 pub struct BarangService {
     pool: MySqlPool,
@@ -18,27 +50,460 @@ impl BarangService {
 
     pub async fn insert_barang(
         &self,
+        tenant: &Tenant,
         barang_id: &String,
         body: Json<InsertBarangSchema>,
     ) -> Result<MySqlQueryResult, String> {
-        let query_result =
-            barang_repository::insert_barang(&barang_id, &body, self.pool.clone()).await;
+        let query_result = barang_repository::insert_barang(
+            &barang_id,
+            &tenant.organization_id,
+            tenant.store_id.as_deref(),
+            &tenant.user.id,
+            &body,
+            self.pool.clone(),
+        )
+        .await;
 
         Ok(query_result?)
     }
 
-    pub async fn get_barang_by_id(&self, barang_id: &str) -> Result<BarangModel, sqlx::Error> {
-        let barang = barang_repository::get_barang_by_id(barang_id, self.pool.clone()).await?;
+    pub async fn get_barang_by_id(
+        &self,
+        tenant: &Tenant,
+        barang_id: &str,
+    ) -> Result<BarangModel, sqlx::Error> {
+        let barang = barang_repository::get_barang_by_id(
+            &tenant.organization_id,
+            barang_id,
+            self.pool.clone(),
+        )
+        .await?;
 
         Ok(barang)
     }
 
     pub async fn get_barang_by_name(
         &self,
+        tenant: &Tenant,
         name: Option<&str>,
     ) -> Result<Vec<BarangModel>, sqlx::Error> {
-        let barang = barang_repository::get_barang_by_name(name, self.pool.clone()).await?;
+        let barang = barang_repository::get_barang_by_name(
+            &tenant.organization_id,
+            tenant.store_id.as_deref(),
+            name,
+            self.pool.clone(),
+        )
+        .await?;
 
         Ok(barang)
     }
+
+    pub async fn update_barang(
+        &self,
+        tenant: &Tenant,
+        barang_id: &str,
+        expected_version: Option<i32>,
+        body: &UpdateBarangSchema,
+    ) -> Result<VersionedWrite, String> {
+        let affected = barang_repository::update_barang(
+            &tenant.organization_id,
+            barang_id,
+            expected_version,
+            body,
+            self.pool.clone(),
+        )
+        .await?;
+
+        self.versioned_outcome(tenant, barang_id, affected).await
+    }
+
+    pub async fn delete_barang(
+        &self,
+        tenant: &Tenant,
+        barang_id: &str,
+        expected_version: Option<i32>,
+    ) -> Result<VersionedWrite, String> {
+        let affected = barang_repository::delete_barang(
+            &tenant.organization_id,
+            barang_id,
+            expected_version,
+            self.pool.clone(),
+        )
+        .await?;
+
+        self.versioned_outcome(tenant, barang_id, affected).await
+    }
+
+    /// Adjust the price of every selected barang in one transaction. The
+    /// whole batch is rejected when any price would end up negative or too
+    /// large for the price column.
+    pub async fn bulk_adjust_price(
+        &self,
+        tenant: &Tenant,
+        body: &BulkPriceSchema,
+    ) -> Result<BulkOutcome, String> {
+        let mut tx = self.pool.begin().await.map_err(|e| e.to_string())?;
+
+        let selected = barang_repository::select_barang_for_update(
+            &tenant.organization_id,
+            tenant.store_id.as_deref(),
+            &body.selector,
+            &mut *tx,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        for barang in &selected {
+            let price = match new_price(&barang.name, barang.price, body) {
+                Ok(price) => price,
+                Err(message) => return Ok(BulkOutcome::Rejected(message)),
+            };
+
+            barang_repository::set_barang_price(
+                &tenant.organization_id,
+                &barang.id,
+                price,
+                &mut *tx,
+            )
+            .await?;
+        }
+
+        let changes = bulk_changes(tenant, selected, false, &mut *tx).await?;
+        finish_bulk(tx, body.preview).await?;
+
+        Ok(BulkOutcome::Applied(changes))
+    }
+
+    /// Set the category and tags of every selected barang in one transaction.
+    pub async fn bulk_set_category(
+        &self,
+        tenant: &Tenant,
+        body: &BulkCategorySchema,
+    ) -> Result<BulkOutcome, String> {
+        let mut tx = self.pool.begin().await.map_err(|e| e.to_string())?;
+
+        let selected = barang_repository::select_barang_for_update(
+            &tenant.organization_id,
+            tenant.store_id.as_deref(),
+            &body.selector,
+            &mut *tx,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        for barang in &selected {
+            let tags = body.tags.as_ref().map(|tags| {
+                merged_tags(tags_from_json(barang.tags.as_ref()), tags, body.tags_mode)
+            });
+
+            barang_repository::set_barang_category(
+                &tenant.organization_id,
+                &barang.id,
+                body.category.as_deref(),
+                tags.as_ref(),
+                &mut *tx,
+            )
+            .await?;
+        }
+
+        let changes = bulk_changes(tenant, selected, false, &mut *tx).await?;
+        finish_bulk(tx, body.preview).await?;
+
+        Ok(BulkOutcome::Applied(changes))
+    }
+
+    /// Delete every selected barang in one transaction.
+    pub async fn bulk_delete(
+        &self,
+        tenant: &Tenant,
+        body: &BulkDeleteSchema,
+    ) -> Result<BulkOutcome, String> {
+        let mut tx = self.pool.begin().await.map_err(|e| e.to_string())?;
+
+        let selected = barang_repository::select_barang_for_update(
+            &tenant.organization_id,
+            tenant.store_id.as_deref(),
+            &body.selector,
+            &mut *tx,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let ids: Vec<String> = selected.iter().map(|barang| barang.id.clone()).collect();
+        barang_repository::delete_barang_in(&tenant.organization_id, &ids, &mut *tx).await?;
+
+        let changes = bulk_changes(tenant, selected, true, &mut *tx).await?;
+        finish_bulk(tx, body.preview).await?;
+
+        Ok(BulkOutcome::Applied(changes))
+    }
+
+    async fn versioned_outcome(
+        &self,
+        tenant: &Tenant,
+        barang_id: &str,
+        affected: u64,
+    ) -> Result<VersionedWrite, String> {
+        if affected > 0 {
+            return Ok(VersionedWrite::Applied);
+        }
+
+        match self.get_barang_by_id(tenant, barang_id).await {
+            Ok(current) => Ok(VersionedWrite::Conflict(current)),
+            Err(sqlx::Error::RowNotFound) => Ok(VersionedWrite::NotFound),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// Adjusted price of the barang `name`, or the reason the batch is rejected.
+fn new_price(name: &str, price: Decimal, body: &BulkPriceSchema) -> Result<Decimal, String> {
+    match adjusted_price(price, body) {
+        Some(price) if price < Decimal::ZERO => {
+            Err(format!("Price of {} would become negative", name))
+        }
+        Some(price) if price <= max_amount() => Ok(price),
+        _ => Err(format!("Price of {} would become too large", name)),
+    }
+}
+
+/// New price after the adjustment of `body`, `None` when the arithmetic
+/// overflows.
+fn adjusted_price(price: Decimal, body: &BulkPriceSchema) -> Option<Decimal> {
+    let price = match body.mode {
+        PriceAdjustmentMode::Percentage => price.checked_add(
+            price
+                .checked_mul(body.value)?
+                .checked_div(Decimal::ONE_HUNDRED)?,
+        )?,
+        PriceAdjustmentMode::Fixed => price.checked_add(body.value)?,
+    };
+
+    let price = match &body.rounding {
+        Some(rounding) => {
+            let strategy = match rounding.mode {
+                RoundingMode::Nearest => RoundingStrategy::MidpointAwayFromZero,
+                RoundingMode::Up => RoundingStrategy::ToPositiveInfinity,
+                RoundingMode::Down => RoundingStrategy::ToNegativeInfinity,
+            };
+
+            price
+                .checked_div(rounding.step)?
+                .round_dp_with_strategy(0, strategy)
+                .checked_mul(rounding.step)?
+        }
+        None => price,
+    };
+
+    Some(price.round_dp_with_strategy(MONEY_SCALE, RoundingStrategy::MidpointAwayFromZero))
+}
+
+fn merged_tags(current: Vec<String>, tags: &[String], mode: TagsMode) -> Vec<String> {
+    match mode {
+        TagsMode::Replace => tags.to_vec(),
+        TagsMode::Add => {
+            let mut merged = current;
+            for tag in tags {
+                if !merged.contains(tag) {
+                    merged.push(tag.clone());
+                }
+            }
+            merged
+        }
+        TagsMode::Remove => current
+            .into_iter()
+            .filter(|tag| !tags.contains(tag))
+            .collect(),
+    }
+}
+
+/// Pair the selected rows with their state after the writes, read back
+/// inside the same transaction so previews show exactly what would be saved.
+async fn bulk_changes(
+    tenant: &Tenant,
+    selected: Vec<BarangModel>,
+    deleted: bool,
+    conn: &mut sqlx::MySqlConnection,
+) -> Result<Vec<BulkChange>, String> {
+    if deleted || selected.is_empty() {
+        return Ok(selected
+            .into_iter()
+            .map(|before| BulkChange {
+                before,
+                after: None,
+            })
+            .collect());
+    }
+
+    let selector = BarangSelectorSchema {
+        ids: Some(selected.iter().map(|barang| barang.id.clone()).collect()),
+        ..Default::default()
+    };
+
+    let mut updated = barang_repository::select_barang_for_update(
+        &tenant.organization_id,
+        tenant.store_id.as_deref(),
+        &selector,
+        conn,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(selected
+        .into_iter()
+        .map(|before| {
+            let after = updated
+                .iter()
+                .position(|barang| barang.id == before.id)
+                .map(|index| updated.swap_remove(index));
+
+            BulkChange { before, after }
+        })
+        .collect())
+}
+
+/// Commit the bulk transaction, or roll it back when only previewing.
+async fn finish_bulk(tx: sqlx::Transaction<'_, sqlx::MySql>, preview: bool) -> Result<(), String> {
+    if preview {
+        tx.rollback().await.map_err(|e| e.to_string())
+    } else {
+        tx.commit().await.map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::barang::PriceRoundingSchema;
+
+    fn adjustment(mode: PriceAdjustmentMode, value: Decimal) -> BulkPriceSchema {
+        BulkPriceSchema {
+            mode,
+            value,
+            ..Default::default()
+        }
+    }
+
+    fn rounded(body: BulkPriceSchema, mode: RoundingMode, step: i64) -> BulkPriceSchema {
+        BulkPriceSchema {
+            rounding: Some(PriceRoundingSchema {
+                mode,
+                step: Decimal::from(step),
+            }),
+            ..body
+        }
+    }
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    #[test]
+    fn percentage_adjusts_relative_to_the_price() {
+        let raise = adjustment(PriceAdjustmentMode::Percentage, Decimal::from(10));
+        let cut = adjustment(PriceAdjustmentMode::Percentage, Decimal::from(-5));
+
+        assert_eq!(
+            adjusted_price(Decimal::from(10_000), &raise),
+            Some(Decimal::from(11_000))
+        );
+        assert_eq!(
+            adjusted_price(Decimal::from(999), &raise),
+            Some(Decimal::new(109890, 2))
+        );
+        assert_eq!(
+            adjusted_price(Decimal::from(12_345), &cut),
+            Some(Decimal::new(1172775, 2))
+        );
+    }
+
+    #[test]
+    fn fixed_adds_the_value() {
+        let body = adjustment(PriceAdjustmentMode::Fixed, Decimal::new(50050, 2));
+
+        assert_eq!(
+            adjusted_price(Decimal::from(10_000), &body),
+            Some(Decimal::new(1050050, 2))
+        );
+    }
+
+    #[test]
+    fn rounding_snaps_to_a_multiple_of_step() {
+        let cut = adjustment(PriceAdjustmentMode::Percentage, Decimal::from(-5));
+        let price = Decimal::from(12_345);
+
+        let nearest = rounded(cut.clone(), RoundingMode::Nearest, 500);
+        let up = rounded(cut.clone(), RoundingMode::Up, 500);
+        let down = rounded(cut, RoundingMode::Down, 500);
+
+        assert_eq!(adjusted_price(price, &nearest), Some(Decimal::from(11_500)));
+        assert_eq!(adjusted_price(price, &up), Some(Decimal::from(12_000)));
+        assert_eq!(adjusted_price(price, &down), Some(Decimal::from(11_500)));
+    }
+
+    #[test]
+    fn nearest_rounds_midpoints_up() {
+        let body = rounded(
+            adjustment(PriceAdjustmentMode::Fixed, Decimal::ZERO),
+            RoundingMode::Nearest,
+            500,
+        );
+
+        assert_eq!(
+            adjusted_price(Decimal::from(11_750), &body),
+            Some(Decimal::from(12_000))
+        );
+        assert_eq!(
+            adjusted_price(Decimal::from(11_749), &body),
+            Some(Decimal::from(11_500))
+        );
+    }
+
+    #[test]
+    fn new_price_rejects_negative_results() {
+        let body = adjustment(PriceAdjustmentMode::Fixed, Decimal::from(-20_000));
+
+        assert_eq!(
+            new_price("Barang 1", Decimal::from(10_000), &body),
+            Err("Price of Barang 1 would become negative".to_string())
+        );
+    }
+
+    #[test]
+    fn new_price_rejects_prices_the_column_can_not_hold() {
+        let too_large = adjustment(PriceAdjustmentMode::Fixed, max_amount());
+        let overflow = adjustment(PriceAdjustmentMode::Percentage, max_amount());
+
+        assert_eq!(
+            new_price("Barang 1", Decimal::ONE, &too_large),
+            Err("Price of Barang 1 would become too large".to_string())
+        );
+        assert_eq!(adjusted_price(max_amount(), &overflow), None);
+        assert_eq!(
+            new_price("Barang 1", max_amount(), &overflow),
+            Err("Price of Barang 1 would become too large".to_string())
+        );
+        assert_eq!(
+            new_price("Barang 1", Decimal::ZERO, &too_large),
+            Ok(max_amount())
+        );
+    }
+
+    #[test]
+    fn merged_tags_replaces_adds_or_removes() {
+        let current = tags(&["food", "snack"]);
+
+        assert_eq!(
+            merged_tags(current.clone(), &tags(&["drink"]), TagsMode::Replace),
+            tags(&["drink"])
+        );
+        assert_eq!(
+            merged_tags(current.clone(), &tags(&["snack", "promo"]), TagsMode::Add),
+            tags(&["food", "snack", "promo"])
+        );
+        assert_eq!(
+            merged_tags(current, &tags(&["snack", "promo"]), TagsMode::Remove),
+            tags(&["food"])
+        );
+    }
 }
@@ -0,0 +1,145 @@
+use redis::{aio::ConnectionManager, AsyncCommands};
+use sqlx::MySqlPool;
+
+use crate::{
+    dtos::dashboard::DashboardSummaryDto,
+    models::dashboard::ActivityModel,
+    repositories::dashboard_repository,
+    services::inventory_service::InventoryService,
+    utils::{config::Config, extractor::Tenant, money::Money},
+};
+
+/// Redis set holding every cached summary key of an organization.
+fn cache_index_key(organization_id: &str) -> String {
+    format!("dashboard:{}:keys", organization_id)
+}
+
+fn cache_key(tenant: &Tenant) -> String {
+    format!(
+        "dashboard:{}:{}",
+        tenant.organization_id,
+        tenant.store_id.as_deref().unwrap_or("all")
+    )
+}
+
+/// Drop the cached dashboard aggregates of an organization after its barang
+/// or stock changed. Failures only mean the cache lives until its TTL.
+pub async fn invalidate_dashboard(redis: &ConnectionManager, organization_id: &str) {
+    let mut redis = redis.clone();
+
+    let index_key = cache_index_key(organization_id);
+    let mut keys: Vec<String> = redis.smembers(&index_key).await.unwrap_or_default();
+    keys.push(index_key);
+
+    let result: redis::RedisResult<usize> = redis.del(keys).await;
+    if let Err(e) = result {
+        eprintln!("🔥 Failed to invalidate dashboard cache: {}", e);
+    }
+}
+
+pub struct DashboardService {
+    pool: MySqlPool,
+    redis: ConnectionManager,
+}
+
+impl DashboardService {
+    pub fn new(pool: MySqlPool, redis: ConnectionManager) -> Self {
+        Self { pool, redis }
+    }
+
+    pub async fn summary(
+        &self,
+        tenant: &Tenant,
+        config: &Config,
+    ) -> Result<DashboardSummaryDto, String> {
+        let key = cache_key(tenant);
+        let mut redis = self.redis.clone();
+
+        // The cache is best effort, Redis errors fall back to computing.
+        let cached: Option<String> = redis.get(&key).await.unwrap_or(None);
+
+        if let Some(summary) = cached.and_then(|c| serde_json::from_str(&c).ok()) {
+            return Ok(summary);
+        }
+
+        let summary = self.compute_summary(tenant, config).await?;
+
+        let value = serde_json::to_string(&summary).map_err(|e| e.to_string())?;
+        let index_key = cache_index_key(&tenant.organization_id);
+
+        let result: redis::RedisResult<()> = redis::pipe()
+            .set_ex(&key, value, config.dashboard_cache_ttl)
+            .ignore()
+            .sadd(&index_key, &key)
+            .ignore()
+            .expire(&index_key, config.dashboard_cache_ttl as i64)
+            .ignore()
+            .query_async(&mut redis)
+            .await;
+
+        if let Err(e) = result {
+            eprintln!("🔥 Failed to cache dashboard summary: {}", e);
+        }
+
+        Ok(summary)
+    }
+
+    async fn compute_summary(
+        &self,
+        tenant: &Tenant,
+        config: &Config,
+    ) -> Result<DashboardSummaryDto, String> {
+        let barang = dashboard_repository::get_barang_summary(
+            &tenant.organization_id,
+            tenant.store_id.as_deref(),
+            config.low_stock_threshold,
+            config.expiring_soon_days,
+            self.pool.clone(),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let sales = dashboard_repository::get_today_sales(
+            &tenant.organization_id,
+            tenant.store_id.as_deref(),
+            self.pool.clone(),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let valuation = InventoryService::new(self.pool.clone())
+            .valuation(tenant, config.inventory_valuation_method, None, None)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let today_sales = Money::from(sales.sales_total);
+
+        Ok(DashboardSummaryDto {
+            item_count: barang.item_count,
+            total_stock_value: valuation.total_value,
+            total_stock_value_display: valuation.total_value_display,
+            low_stock_count: barang.low_stock_count,
+            expiring_soon_count: barang.expiring_soon_count,
+            today_sales_count: sales.sales_count,
+            today_sales,
+            today_sales_display: today_sales.to_rupiah(),
+            generated_at: chrono::Utc::now(),
+        })
+    }
+
+    pub async fn recent_activity(
+        &self,
+        tenant: &Tenant,
+        limit: i64,
+    ) -> Result<Vec<ActivityModel>, sqlx::Error> {
+        let activity = dashboard_repository::get_recent_activity(
+            &tenant.organization_id,
+            tenant.store_id.as_deref(),
+            limit,
+            self.pool.clone(),
+        )
+        .await?;
+
+        Ok(activity)
+    }
+}
@@ -0,0 +1,407 @@
+use std::collections::VecDeque;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::MySqlPool;
+
+use crate::{
+    dtos::inventory::{InventoryValuationDto, ItemValuationDto},
+    models::inventory::{
+        MovementOutcome, MovementType, StockMovementModel, ValuationMethod, ValuationMovementModel,
+    },
+    repositories::inventory_repository,
+    utils::{extractor::Tenant, money::Money},
+};
+
+#[derive(Debug)]
+pub struct InventoryService {
+    pool: MySqlPool,
+}
+
+impl InventoryService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Receive stock bought at `unit_cost`.
+    pub async fn stock_in(
+        &self,
+        tenant: &Tenant,
+        barang_id: &str,
+        quantity: i32,
+        unit_cost: Money,
+        note: Option<&str>,
+    ) -> Result<MovementOutcome, String> {
+        let movement = new_movement(tenant, barang_id, MovementType::In, quantity, note);
+
+        inventory_repository::insert_movement(
+            &StockMovementModel {
+                unit_cost: Some(unit_cost.amount()),
+                ..movement
+            },
+            self.pool.clone(),
+        )
+        .await
+    }
+
+    /// Take stock out, e.g. for a sale at `unit_price`. Refused when less
+    /// than `quantity` is in stock.
+    pub async fn stock_out(
+        &self,
+        tenant: &Tenant,
+        barang_id: &str,
+        quantity: i32,
+        unit_price: Option<Money>,
+        note: Option<&str>,
+    ) -> Result<MovementOutcome, String> {
+        let movement = new_movement(tenant, barang_id, MovementType::Out, quantity, note);
+
+        inventory_repository::insert_movement(
+            &StockMovementModel {
+                unit_price: unit_price.map(|price| price.amount()),
+                ..movement
+            },
+            self.pool.clone(),
+        )
+        .await
+    }
+
+    /// Value stock on hand at the end of `to` and the cost of goods sold
+    /// between `from` and `to` (both inclusive, open when `None`).
+    pub async fn valuation(
+        &self,
+        tenant: &Tenant,
+        method: ValuationMethod,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<InventoryValuationDto, sqlx::Error> {
+        let movements = inventory_repository::get_valuation_movements(
+            &tenant.organization_id,
+            tenant.store_id.as_deref(),
+            to,
+            self.pool.clone(),
+        )
+        .await?;
+
+        let mut items = vec![];
+        for group in movements.chunk_by(|a, b| a.barang_id == b.barang_id) {
+            items.push(value_item(method, group, from, to));
+        }
+
+        let total_quantity = items.iter().map(|item| item.quantity).sum();
+        let total_value: Money = items.iter().map(|item| item.value).sum();
+        let total_cogs: Money = items.iter().map(|item| item.cogs).sum();
+
+        Ok(InventoryValuationDto {
+            method,
+            from,
+            to,
+            items,
+            total_quantity,
+            total_value,
+            total_value_display: total_value.to_rupiah(),
+            total_cogs,
+            total_cogs_display: total_cogs.to_rupiah(),
+        })
+    }
+}
+
+/// Movement of `quantity` by the user of `tenant`, without cost or price yet.
+fn new_movement(
+    tenant: &Tenant,
+    barang_id: &str,
+    movement_type: MovementType,
+    quantity: i32,
+    note: Option<&str>,
+) -> StockMovementModel {
+    StockMovementModel {
+        id: uuid::Uuid::new_v4().to_string(),
+        organization_id: tenant.organization_id.to_owned(),
+        barang_id: barang_id.to_string(),
+        movement_type,
+        quantity,
+        unit_cost: None,
+        unit_price: None,
+        note: note.map(str::to_string),
+        created_by: Some(tenant.user.id.to_owned()),
+        created_at: None,
+    }
+}
+
+/// Replay the movements of one barang, oldest first. Movements after `to`
+/// are ignored, only those from `from` on count as cost of goods sold.
+fn value_item(
+    method: ValuationMethod,
+    movements: &[ValuationMovementModel],
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> ItemValuationDto {
+    let in_period = |movement: &ValuationMovementModel| match (from, movement.date()) {
+        (Some(from), Some(date)) => date >= from,
+        _ => true,
+    };
+    let until_to = |movement: &&ValuationMovementModel| match (to, movement.date()) {
+        (Some(to), Some(date)) => date <= to,
+        _ => true,
+    };
+
+    let (quantity, value, cogs) = match method {
+        ValuationMethod::Fifo => {
+            // Cost layers still on hand, oldest first.
+            let mut layers: VecDeque<(i64, Decimal)> = VecDeque::new();
+            let mut last_cost = Decimal::ZERO;
+            let mut cogs = Decimal::ZERO;
+
+            for movement in movements.iter().filter(until_to) {
+                let quantity = movement.quantity as i64;
+
+                match movement.movement_type {
+                    MovementType::In => {
+                        let cost = movement.unit_cost.unwrap_or(last_cost);
+                        last_cost = cost;
+                        layers.push_back((quantity, cost));
+                    }
+                    MovementType::Out => {
+                        let mut remaining = quantity;
+                        let mut cost = Decimal::ZERO;
+
+                        while remaining > 0 {
+                            match layers.front_mut() {
+                                Some((layer_quantity, layer_cost)) => {
+                                    let taken = remaining.min(*layer_quantity);
+                                    cost += Decimal::from(taken) * *layer_cost;
+                                    *layer_quantity -= taken;
+                                    remaining -= taken;
+
+                                    if *layer_quantity == 0 {
+                                        layers.pop_front();
+                                    }
+                                }
+                                // Sold more than was received, cost the rest at
+                                // the latest known cost.
+                                None => {
+                                    cost += Decimal::from(remaining) * last_cost;
+                                    remaining = 0;
+                                }
+                            }
+                        }
+
+                        if in_period(movement) {
+                            cogs += cost;
+                        }
+                    }
+                }
+            }
+
+            let quantity = layers.iter().map(|(quantity, _)| quantity).sum::<i64>();
+            let value = layers
+                .iter()
+                .map(|(quantity, cost)| Decimal::from(*quantity) * cost)
+                .sum::<Decimal>();
+
+            (quantity, value, cogs)
+        }
+        ValuationMethod::Average => {
+            let mut quantity: i64 = 0;
+            let mut average = Decimal::ZERO;
+            let mut cogs = Decimal::ZERO;
+
+            for movement in movements.iter().filter(until_to) {
+                let moved = movement.quantity as i64;
+
+                match movement.movement_type {
+                    MovementType::In => {
+                        let cost = movement.unit_cost.unwrap_or(average);
+
+                        average = if quantity > 0 {
+                            (Decimal::from(quantity) * average + Decimal::from(moved) * cost)
+                                / Decimal::from(quantity + moved)
+                        } else {
+                            cost
+                        };
+                        quantity += moved;
+                    }
+                    MovementType::Out => {
+                        if in_period(movement) {
+                            cogs += Decimal::from(moved) * average;
+                        }
+                        quantity -= moved;
+                    }
+                }
+            }
+
+            let quantity = quantity.max(0);
+            (quantity, Decimal::from(quantity) * average, cogs)
+        }
+    };
+
+    let value = Money::from(value);
+    let cogs = Money::from(cogs);
+    let unit_cost = if quantity > 0 {
+        Money::from(value.amount() / Decimal::from(quantity))
+    } else {
+        Money::zero()
+    };
+
+    ItemValuationDto {
+        barang_id: movements[0].barang_id.clone(),
+        name: movements[0].name.clone(),
+        quantity,
+        unit_cost,
+        value,
+        value_display: value.to_rupiah(),
+        cogs,
+        cogs_display: cogs.to_rupiah(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn movement(
+        movement_type: MovementType,
+        quantity: i32,
+        unit_cost: Option<i64>,
+        day: u32,
+    ) -> ValuationMovementModel {
+        ValuationMovementModel {
+            barang_id: "barang-1".to_string(),
+            name: "Barang 1".to_string(),
+            movement_type,
+            quantity,
+            unit_cost: unit_cost.map(Decimal::from),
+            created_at: Some(
+                chrono::Utc
+                    .with_ymd_and_hms(2026, 1, day, 10, 0, 0)
+                    .unwrap(),
+            ),
+        }
+    }
+
+    fn day(day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(2026, 1, day)
+    }
+
+    #[test]
+    fn fifo_consumes_layers_partially() {
+        let movements = [
+            movement(MovementType::In, 5, Some(100), 1),
+            movement(MovementType::In, 5, Some(120), 2),
+            movement(MovementType::Out, 7, None, 3),
+        ];
+
+        let item = value_item(ValuationMethod::Fifo, &movements, None, None);
+
+        assert_eq!(item.cogs, Money::from(740));
+        assert_eq!(item.quantity, 3);
+        assert_eq!(item.value, Money::from(360));
+        assert_eq!(item.unit_cost, Money::from(120));
+    }
+
+    #[test]
+    fn fifo_costs_an_out_from_layers_received_before_it() {
+        let movements = [
+            movement(MovementType::In, 2, Some(100), 1),
+            movement(MovementType::Out, 2, None, 1),
+            movement(MovementType::In, 2, Some(300), 1),
+        ];
+
+        let item = value_item(ValuationMethod::Fifo, &movements, None, None);
+
+        assert_eq!(item.cogs, Money::from(200));
+        assert_eq!(item.quantity, 2);
+        assert_eq!(item.value, Money::from(600));
+    }
+
+    #[test]
+    fn average_follows_mixed_ins_and_outs() {
+        let movements = [
+            movement(MovementType::In, 10, Some(100), 1),
+            movement(MovementType::Out, 4, None, 2),
+            movement(MovementType::In, 4, Some(150), 3),
+            movement(MovementType::Out, 5, None, 4),
+        ];
+
+        let item = value_item(ValuationMethod::Average, &movements, None, None);
+
+        assert_eq!(item.cogs, Money::from(1_000));
+        assert_eq!(item.quantity, 5);
+        assert_eq!(item.value, Money::from(600));
+        assert_eq!(item.unit_cost, Money::from(120));
+    }
+
+    #[test]
+    fn average_depends_on_the_order_of_movements() {
+        let out_first = [
+            movement(MovementType::In, 2, Some(100), 1),
+            movement(MovementType::Out, 2, None, 1),
+            movement(MovementType::In, 2, Some(300), 1),
+        ];
+        let in_first = [
+            movement(MovementType::In, 2, Some(100), 1),
+            movement(MovementType::In, 2, Some(300), 1),
+            movement(MovementType::Out, 2, None, 1),
+        ];
+
+        let item = value_item(ValuationMethod::Average, &out_first, None, None);
+        assert_eq!(item.cogs, Money::from(200));
+        assert_eq!(item.value, Money::from(600));
+
+        let item = value_item(ValuationMethod::Average, &in_first, None, None);
+        assert_eq!(item.cogs, Money::from(400));
+        assert_eq!(item.value, Money::from(400));
+    }
+
+    #[test]
+    fn cogs_only_counts_outs_from_the_start_of_the_period() {
+        let movements = [
+            movement(MovementType::In, 10, Some(100), 1),
+            movement(MovementType::Out, 3, None, 2),
+            movement(MovementType::Out, 2, None, 4),
+        ];
+
+        for method in [ValuationMethod::Fifo, ValuationMethod::Average] {
+            let item = value_item(method, &movements, day(3), None);
+            assert_eq!(item.cogs, Money::from(200));
+            assert_eq!(item.quantity, 5);
+            assert_eq!(item.value, Money::from(500));
+
+            let item = value_item(method, &movements, None, None);
+            assert_eq!(item.cogs, Money::from(500));
+        }
+    }
+
+    #[test]
+    fn movements_after_the_end_of_the_period_are_ignored() {
+        let movements = [
+            movement(MovementType::In, 10, Some(100), 1),
+            movement(MovementType::Out, 3, None, 2),
+            movement(MovementType::In, 5, Some(200), 5),
+            movement(MovementType::Out, 2, None, 6),
+        ];
+
+        for method in [ValuationMethod::Fifo, ValuationMethod::Average] {
+            let item = value_item(method, &movements, day(2), day(4));
+            assert_eq!(item.cogs, Money::from(300));
+            assert_eq!(item.quantity, 7);
+            assert_eq!(item.value, Money::from(700));
+        }
+    }
+
+    #[test]
+    fn outs_on_the_first_day_of_the_period_are_counted() {
+        let movements = [
+            movement(MovementType::In, 4, Some(50), 1),
+            movement(MovementType::Out, 4, None, 3),
+        ];
+
+        let item = value_item(ValuationMethod::Fifo, &movements, day(3), None);
+
+        assert_eq!(item.cogs, Money::from(200));
+        assert_eq!(item.quantity, 0);
+        assert_eq!(item.unit_cost, Money::zero());
+    }
+}
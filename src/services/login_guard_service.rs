@@ -0,0 +1,113 @@
+use redis::{aio::ConnectionManager, AsyncCommands};
+
+use crate::utils::config::Config;
+
+fn failures_key(scope: &str, value: &str) -> String {
+    format!("login_failures:{}:{}", scope, value.to_lowercase())
+}
+
+fn block_key(scope: &str, value: &str) -> String {
+    format!("login_block:{}:{}", scope, value.to_lowercase())
+}
+
+/// Counts failed logins per email and per IP address in Redis. Every failure
+/// blocks further attempts for an exponentially growing delay, and reaching
+/// the limit locks the email or IP out for `LOGIN_LOCKOUT_SECONDS`.
+pub struct LoginGuardService {
+    redis: ConnectionManager,
+}
+
+impl LoginGuardService {
+    pub fn new(redis: ConnectionManager) -> Self {
+        Self { redis }
+    }
+
+    /// Seconds to wait before `email` may try again from `ip`, `None` when a
+    /// login attempt is allowed now.
+    pub async fn retry_after(&self, email: &str, ip: &str) -> Result<Option<u64>, String> {
+        let mut redis = self.redis.clone();
+
+        let (email_ttl, ip_ttl): (i64, i64) = redis::pipe()
+            .ttl(block_key("email", email))
+            .ttl(block_key("ip", ip))
+            .query_async(&mut redis)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let wait = email_ttl.max(ip_ttl);
+
+        Ok(if wait > 0 { Some(wait as u64) } else { None })
+    }
+
+    /// Count a failed attempt and block the next ones. Returns the seconds
+    /// until the next attempt is allowed.
+    pub async fn record_failure(
+        &self,
+        email: &str,
+        ip: &str,
+        config: &Config,
+    ) -> Result<u64, String> {
+        let email_wait = self
+            .count_failure("email", email, config.login_max_attempts, config)
+            .await?;
+        let ip_wait = self
+            .count_failure("ip", ip, config.login_max_attempts_per_ip, config)
+            .await?;
+
+        Ok(email_wait.max(ip_wait))
+    }
+
+    /// Forget the failures of `email` after a successful login. The IP
+    /// counter is kept, so one valid account can not be used to reset it.
+    pub async fn record_success(&self, email: &str) -> Result<(), String> {
+        self.unlock(email).await
+    }
+
+    /// Lift the lockout of `email`.
+    pub async fn unlock(&self, email: &str) -> Result<(), String> {
+        let mut redis = self.redis.clone();
+
+        let result: redis::RedisResult<usize> = redis
+            .del(&[failures_key("email", email), block_key("email", email)])
+            .await;
+        result.map(|_| ()).map_err(|e| e.to_string())
+    }
+
+    async fn count_failure(
+        &self,
+        scope: &str,
+        value: &str,
+        max_attempts: u64,
+        config: &Config,
+    ) -> Result<u64, String> {
+        let mut redis = self.redis.clone();
+
+        let key = failures_key(scope, value);
+        let (failures,): (u64,) = redis::pipe()
+            .incr(&key, 1)
+            .expire(&key, config.login_lockout_seconds as i64)
+            .ignore()
+            .query_async(&mut redis)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let wait = if failures >= max_attempts {
+            config.login_lockout_seconds
+        } else {
+            // 1, 2, 4, 8 ... times the base delay, never longer than a lockout.
+            let exponent = (failures.saturating_sub(1)).min(32) as u32;
+            config
+                .login_backoff_base_seconds
+                .saturating_mul(2u64.saturating_pow(exponent))
+                .min(config.login_lockout_seconds)
+        };
+
+        if wait > 0 {
+            let result: redis::RedisResult<()> =
+                redis.set_ex(block_key(scope, value), failures, wait).await;
+            result.map_err(|e| e.to_string())?;
+        }
+
+        Ok(wait)
+    }
+}
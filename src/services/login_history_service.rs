@@ -0,0 +1,95 @@
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+
+use crate::{
+    models::login_event::{LoginEventModel, LoginFailureReason, LoginOutcome},
+    repositories::{login_event_repository, user_repository},
+    services::session_service::SessionMeta,
+};
+
+/// Same device as far as the login history is concerned: the device name
+/// and user agent, the IP address changes too often to count.
+pub fn device_fingerprint(meta: &SessionMeta) -> String {
+    format!(
+        "{:x}",
+        Sha256::digest(format!("{}\n{}", meta.device_name, meta.user_agent).as_bytes())
+    )
+}
+
+fn truncate(value: &str, max_chars: usize) -> String {
+    value.chars().take(max_chars).collect()
+}
+
+/// Keeps a row per login attempt so users and admins can review where an
+/// account was used from.
+pub struct LoginHistoryService {
+    pool: MySqlPool,
+}
+
+impl LoginHistoryService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record an attempt on `email`. `user_id` may be left out when the
+    /// account was not loaded yet, it is then looked up by email. The event
+    /// is flagged as a new device when the account never logged in from
+    /// this fingerprint before.
+    pub async fn record(
+        &self,
+        email: &str,
+        user_id: Option<&str>,
+        meta: &SessionMeta,
+        outcome: LoginOutcome,
+        reason: Option<LoginFailureReason>,
+    ) -> Result<LoginEventModel, String> {
+        let user_id = match user_id {
+            Some(user_id) => Some(user_id.to_string()),
+            None => user_repository::get_user(None, None, Some(email), self.pool.clone())
+                .await
+                .map_err(|e| e.to_string())?
+                .map(|user| user.id),
+        };
+
+        let fingerprint = device_fingerprint(meta);
+        let new_device = match &user_id {
+            Some(user_id) => !login_event_repository::has_successful_login(
+                user_id,
+                &fingerprint,
+                self.pool.clone(),
+            )
+            .await
+            .map_err(|e| e.to_string())?,
+            None => false,
+        };
+
+        let login_event = LoginEventModel {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id,
+            email: truncate(email, 255),
+            outcome,
+            reason: reason.map(|reason| reason.to_str().to_string()),
+            ip_address: truncate(&meta.ip_address, 45),
+            user_agent: truncate(&meta.user_agent, 512),
+            device_name: truncate(&meta.device_name, 100),
+            fingerprint,
+            new_device: new_device as i8,
+            created_at: Some(chrono::Utc::now()),
+        };
+
+        login_event_repository::insert_login_event(&login_event, self.pool.clone()).await?;
+
+        Ok(login_event)
+    }
+
+    pub async fn list(
+        &self,
+        user_id: &str,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<LoginEventModel>, i64), String> {
+        login_event_repository::get_user_login_events(user_id, page, per_page, self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
@@ -1,4 +1,17 @@
+pub mod account_service;
+pub mod api_key_service;
 pub mod auth_service;
 pub mod barang_service;
+pub mod dashboard_service;
+pub mod inventory_service;
+pub mod login_guard_service;
+pub mod login_history_service;
+pub mod oidc_service;
+pub mod organization_service;
+pub mod password_reset_service;
+pub mod permission_service;
 pub mod pdf_service;
+pub mod session_service;
+pub mod two_factor_service;
 pub mod user_services;
+pub mod verification_service;
@@ -0,0 +1,412 @@
+use std::time::Duration;
+
+use actix_web::web;
+use base64::{engine::general_purpose, Engine};
+use jsonwebtoken::{jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use redis::{aio::ConnectionManager, AsyncCommands};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+
+use crate::{
+    models::{identity::ExternalIdentity, user::UserModel},
+    repositories::{identity_repository, user_repository},
+    utils::{config::Config, password},
+};
+
+/// Seconds a call to the provider may take before the login fails.
+const PROVIDER_TIMEOUT: u64 = 10;
+
+/// Signing algorithms accepted for ID tokens. The HMAC ones are left out,
+/// they would make the client secret a signing key.
+const ID_TOKEN_ALGORITHMS: [Algorithm; 7] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+/// Redis key of a login that was sent to the provider and has not come back.
+fn state_key(state: &str) -> String {
+    format!("oidc:state:{}", state)
+}
+
+/// What has to survive the round trip to the provider.
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingLogin {
+    code_verifier: String,
+    nonce: String,
+}
+
+/// The parts of the provider's discovery document the login needs.
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    /// Some providers send it as the string `"true"`.
+    email_verified: Option<serde_json::Value>,
+    name: Option<String>,
+    preferred_username: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum OidcLogin {
+    /// The identity belongs to this user, who may still be disabled.
+    SignedIn(UserModel),
+    /// The state is unknown, used or expired, the login has to start over.
+    InvalidState,
+    /// The provider vouched for someone this API will not let in.
+    Rejected { email: String, message: String },
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(PROVIDER_TIMEOUT))
+        .build()
+}
+
+fn read_json<T: DeserializeOwned>(
+    result: Result<ureq::Response, ureq::Error>,
+) -> Result<T, String> {
+    let body = match result {
+        Ok(response) => response.into_string().map_err(|e| e.to_string())?,
+        Err(ureq::Error::Status(code, response)) => {
+            return Err(format!(
+                "Provider answered {}: {}",
+                code,
+                response.into_string().unwrap_or_default()
+            ))
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+
+    serde_json::from_str(&body).map_err(|e| format!("Unexpected provider response: {}", e))
+}
+
+/// ureq blocks, so provider calls run on the blocking thread pool.
+async fn get_json<T: DeserializeOwned + Send + 'static>(url: String) -> Result<T, String> {
+    web::block(move || read_json(agent().get(&url).call()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Random value for the state, nonce and PKCE verifier.
+fn random_token() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// The S256 PKCE challenge of `code_verifier`.
+fn code_challenge(code_verifier: &str) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+fn is_email_verified(value: &Option<serde_json::Value>) -> bool {
+    match value {
+        Some(serde_json::Value::Bool(verified)) => *verified,
+        Some(serde_json::Value::String(verified)) => verified == "true",
+        _ => false,
+    }
+}
+
+fn rejected(identity: &ExternalIdentity, message: &str) -> OidcLogin {
+    OidcLogin::Rejected {
+        email: identity.email.clone().unwrap_or_default(),
+        message: message.to_string(),
+    }
+}
+
+pub struct OidcService {
+    pool: MySqlPool,
+    redis: ConnectionManager,
+}
+
+impl OidcService {
+    pub fn new(pool: MySqlPool, redis: ConnectionManager) -> Self {
+        Self { pool, redis }
+    }
+
+    async fn metadata(&self, config: &Config) -> Result<ProviderMetadata, String> {
+        let metadata: ProviderMetadata = get_json(format!(
+            "{}/.well-known/openid-configuration",
+            config.oidc_issuer
+        ))
+        .await?;
+
+        if metadata.issuer.trim_end_matches('/') != config.oidc_issuer {
+            return Err(format!(
+                "Provider names itself {} instead of {}",
+                metadata.issuer, config.oidc_issuer
+            ));
+        }
+
+        Ok(metadata)
+    }
+
+    /// Start a login: remember a fresh state, nonce and PKCE verifier and
+    /// return the provider URL to send the browser to, with the state the
+    /// browser has to keep until the callback.
+    pub async fn authorization_url(&self, config: &Config) -> Result<(String, String), String> {
+        let metadata = self.metadata(config).await?;
+
+        let state = random_token();
+        let pending = PendingLogin {
+            code_verifier: random_token(),
+            nonce: random_token(),
+        };
+
+        let mut redis = self.redis.clone();
+
+        let value = serde_json::to_string(&pending).map_err(|e| e.to_string())?;
+        let result: redis::RedisResult<()> = redis
+            .set_ex(state_key(&state), value, config.oidc_state_max_age)
+            .await;
+        result.map_err(|e| e.to_string())?;
+
+        let url = url::Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", config.oidc_client_id.as_str()),
+                ("redirect_uri", config.oidc_redirect_url.as_str()),
+                ("scope", config.oidc_scopes.as_str()),
+                ("state", state.as_str()),
+                ("nonce", pending.nonce.as_str()),
+                (
+                    "code_challenge",
+                    code_challenge(&pending.code_verifier).as_str(),
+                ),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| format!("Invalid authorization endpoint: {}", e))?;
+
+        Ok((url.to_string(), state))
+    }
+
+    /// Finish a login the provider redirected back with `code` and `state`:
+    /// redeem the code, verify the ID token and find or create its user.
+    pub async fn complete(
+        &self,
+        code: &str,
+        state: &str,
+        config: &Config,
+    ) -> Result<OidcLogin, String> {
+        let mut redis = self.redis.clone();
+
+        // GETDEL makes the state single use even with concurrent callbacks.
+        let pending: Option<String> = redis::cmd("GETDEL")
+            .arg(state_key(state))
+            .query_async(&mut redis)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let pending: PendingLogin = match pending {
+            Some(pending) => serde_json::from_str(&pending).map_err(|e| e.to_string())?,
+            None => return Ok(OidcLogin::InvalidState),
+        };
+
+        let metadata = self.metadata(config).await?;
+        let id_token = self
+            .exchange_code(code, &pending, &metadata, config)
+            .await?;
+        let jwks: JwkSet = get_json(metadata.jwks_uri.clone()).await?;
+        let claims = verify_id_token(&id_token, &jwks, &pending.nonce, config)?;
+
+        let identity = ExternalIdentity {
+            issuer: config.oidc_issuer.clone(),
+            subject: claims.sub,
+            email: claims.email.map(|email| email.trim().to_lowercase()),
+            email_verified: is_email_verified(&claims.email_verified),
+            name: claims.name.or(claims.preferred_username),
+        };
+
+        self.link(&identity, config).await
+    }
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        pending: &PendingLogin,
+        metadata: &ProviderMetadata,
+        config: &Config,
+    ) -> Result<String, String> {
+        let mut form = vec![
+            ("grant_type".to_string(), "authorization_code".to_string()),
+            ("code".to_string(), code.to_string()),
+            ("redirect_uri".to_string(), config.oidc_redirect_url.clone()),
+            ("client_id".to_string(), config.oidc_client_id.clone()),
+            ("code_verifier".to_string(), pending.code_verifier.clone()),
+        ];
+        if !config.oidc_client_secret.is_empty() {
+            form.push((
+                "client_secret".to_string(),
+                config.oidc_client_secret.clone(),
+            ));
+        }
+
+        let token_endpoint = metadata.token_endpoint.clone();
+        let response: TokenResponse = web::block(move || {
+            let form = form
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect::<Vec<_>>();
+            read_json(agent().post(&token_endpoint).send_form(&form))
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+
+        response
+            .id_token
+            .ok_or_else(|| "Provider returned no ID token".to_string())
+    }
+
+    /// The user behind `identity`: the one it is linked to, else the account
+    /// that already verified the same email, else a new account when allowed.
+    async fn link(
+        &self,
+        identity: &ExternalIdentity,
+        config: &Config,
+    ) -> Result<OidcLogin, String> {
+        let linked = identity_repository::get_identity(
+            &identity.issuer,
+            &identity.subject,
+            self.pool.clone(),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if let Some(linked) = linked {
+            identity_repository::touch_identity(
+                &linked.id,
+                identity.email.as_deref(),
+                self.pool.clone(),
+            )
+            .await?;
+
+            return match self.get_user(Some(&linked.user_id), None).await? {
+                Some(user) => Ok(OidcLogin::SignedIn(user)),
+                None => Ok(rejected(identity, "The linked account no longer exists")),
+            };
+        }
+
+        let email = match (&identity.email, identity.email_verified) {
+            (Some(email), true) if !email.is_empty() => email,
+            _ => {
+                return Ok(rejected(
+                    identity,
+                    "The provider did not share a verified email address",
+                ))
+            }
+        };
+
+        if let Some(user) = self.get_user(None, Some(email)).await? {
+            // Whoever registered an unverified account may not own the
+            // address, linking it would let them keep a way into the account.
+            if !config.oidc_link_by_email || user.verified == 0 {
+                return Ok(rejected(
+                    identity,
+                    "An account with this email already exists, log in with its password",
+                ));
+            }
+
+            identity_repository::insert_identity(&user.id, identity, self.pool.clone()).await?;
+
+            return Ok(OidcLogin::SignedIn(user));
+        }
+
+        if !config.oidc_create_users {
+            return Ok(rejected(identity, "No account is linked to this identity"));
+        }
+
+        // The account can only be used through the provider until the user
+        // resets the password.
+        let user_id = uuid::Uuid::new_v4().to_string();
+        let hashed_password = password::hash(random_token(), config)?;
+        let name = identity.name.as_deref().unwrap_or(email);
+
+        identity_repository::insert_user_with_identity(
+            &user_id,
+            name,
+            email,
+            &hashed_password,
+            identity,
+            self.pool.clone(),
+        )
+        .await?;
+
+        match self.get_user(Some(&user_id), None).await? {
+            Some(user) => Ok(OidcLogin::SignedIn(user)),
+            None => Err("Created user not found".to_string()),
+        }
+    }
+
+    async fn get_user(
+        &self,
+        user_id: Option<&str>,
+        email: Option<&str>,
+    ) -> Result<Option<UserModel>, String> {
+        user_repository::get_user(user_id, None, email, self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Check the signature, issuer, audience, expiry and nonce of `id_token`.
+fn verify_id_token(
+    id_token: &str,
+    jwks: &JwkSet,
+    nonce: &str,
+    config: &Config,
+) -> Result<IdTokenClaims, String> {
+    let header = jsonwebtoken::decode_header(id_token).map_err(|e| e.to_string())?;
+
+    if !ID_TOKEN_ALGORITHMS.contains(&header.alg) {
+        return Err(format!("ID token signed with {:?}", header.alg));
+    }
+
+    let jwk = match &header.kid {
+        Some(kid) => jwks.find(kid),
+        None => jwks.keys.first(),
+    }
+    .ok_or_else(|| "No provider key matches the ID token".to_string())?;
+    let decoding_key = DecodingKey::from_jwk(jwk).map_err(|e| e.to_string())?;
+
+    let mut validation = Validation::new(header.alg);
+    // Discovery already compared the issuer without its trailing slash.
+    validation.set_issuer(&[
+        config.oidc_issuer.clone(),
+        format!("{}/", config.oidc_issuer),
+    ]);
+    validation.set_audience(&[config.oidc_client_id.as_str()]);
+
+    let claims = jsonwebtoken::decode::<IdTokenClaims>(id_token, &decoding_key, &validation)
+        .map_err(|e| format!("Invalid ID token: {}", e))?
+        .claims;
+
+    if claims.nonce.as_deref() != Some(nonce) {
+        return Err("ID token nonce does not match".to_string());
+    }
+
+    Ok(claims)
+}
@@ -0,0 +1,188 @@
+use sqlx::{mysql::MySqlQueryResult, MySqlPool};
+
+use crate::{
+    models::organization::{
+        OrganizationMemberModel, OrganizationModel, OrganizationRole, StoreModel,
+        UserOrganizationModel,
+    },
+    repositories::organization_repository,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum MemberChange {
+    Done,
+    /// Only owners may grant ownership or change and remove owners.
+    Forbidden,
+    /// The organization would be left without an owner.
+    LastOwner,
+}
+
+#[derive(Debug)]
+pub struct OrganizationService {
+    pool: MySqlPool,
+}
+
+impl OrganizationService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create_organization(
+        &self,
+        organization_id: &str,
+        owner_id: &str,
+        name: &str,
+    ) -> Result<MySqlQueryResult, String> {
+        let query_result = organization_repository::insert_organization(
+            organization_id,
+            owner_id,
+            name,
+            self.pool.clone(),
+        )
+        .await;
+
+        Ok(query_result?)
+    }
+
+    pub async fn get_organization_by_id(
+        &self,
+        organization_id: &str,
+    ) -> Result<Option<OrganizationModel>, sqlx::Error> {
+        let organization =
+            organization_repository::get_organization_by_id(organization_id, self.pool.clone())
+                .await?;
+
+        Ok(organization)
+    }
+
+    pub async fn get_user_organizations(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<UserOrganizationModel>, sqlx::Error> {
+        let organizations =
+            organization_repository::get_user_organizations(user_id, self.pool.clone()).await?;
+
+        Ok(organizations)
+    }
+
+    pub async fn get_member(
+        &self,
+        organization_id: &str,
+        user_id: &str,
+    ) -> Result<Option<OrganizationMemberModel>, sqlx::Error> {
+        let member =
+            organization_repository::get_member(organization_id, user_id, self.pool.clone())
+                .await?;
+
+        Ok(member)
+    }
+
+    pub async fn get_members(
+        &self,
+        organization_id: &str,
+    ) -> Result<Vec<OrganizationMemberModel>, sqlx::Error> {
+        let members =
+            organization_repository::get_members(organization_id, self.pool.clone()).await?;
+
+        Ok(members)
+    }
+
+    /// Give `user_id` the role `role` on behalf of a member with
+    /// `actor_role`. Only owners may make or touch owners.
+    pub async fn set_member(
+        &self,
+        organization_id: &str,
+        actor_role: OrganizationRole,
+        user_id: &str,
+        role: OrganizationRole,
+    ) -> Result<MemberChange, String> {
+        if actor_role != OrganizationRole::Owner
+            && (role == OrganizationRole::Owner || self.is_owner(organization_id, user_id).await?)
+        {
+            return Ok(MemberChange::Forbidden);
+        }
+
+        let saved = organization_repository::upsert_member(
+            organization_id,
+            user_id,
+            role,
+            self.pool.clone(),
+        )
+        .await?;
+
+        Ok(if saved {
+            MemberChange::Done
+        } else {
+            MemberChange::LastOwner
+        })
+    }
+
+    /// Remove `user_id` on behalf of a member with `actor_role`. Only owners
+    /// may remove owners.
+    pub async fn remove_member(
+        &self,
+        organization_id: &str,
+        actor_role: OrganizationRole,
+        user_id: &str,
+    ) -> Result<MemberChange, String> {
+        if actor_role != OrganizationRole::Owner && self.is_owner(organization_id, user_id).await? {
+            return Ok(MemberChange::Forbidden);
+        }
+
+        let removed =
+            organization_repository::delete_member(organization_id, user_id, self.pool.clone())
+                .await?;
+
+        Ok(if removed {
+            MemberChange::Done
+        } else {
+            MemberChange::LastOwner
+        })
+    }
+
+    async fn is_owner(&self, organization_id: &str, user_id: &str) -> Result<bool, String> {
+        Ok(self
+            .get_member(organization_id, user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .is_some_and(|member| member.role == OrganizationRole::Owner))
+    }
+
+    pub async fn create_store(
+        &self,
+        store_id: &str,
+        organization_id: &str,
+        name: &str,
+        address: Option<&str>,
+    ) -> Result<MySqlQueryResult, String> {
+        let query_result = organization_repository::insert_store(
+            store_id,
+            organization_id,
+            name,
+            address,
+            self.pool.clone(),
+        )
+        .await;
+
+        Ok(query_result?)
+    }
+
+    pub async fn get_stores(&self, organization_id: &str) -> Result<Vec<StoreModel>, sqlx::Error> {
+        let stores =
+            organization_repository::get_stores(organization_id, self.pool.clone()).await?;
+
+        Ok(stores)
+    }
+
+    pub async fn get_store_by_id(
+        &self,
+        organization_id: &str,
+        store_id: &str,
+    ) -> Result<Option<StoreModel>, sqlx::Error> {
+        let store =
+            organization_repository::get_store_by_id(organization_id, store_id, self.pool.clone())
+                .await?;
+
+        Ok(store)
+    }
+}
@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use redis::{aio::ConnectionManager, AsyncCommands};
+use sqlx::MySqlPool;
+
+use crate::{
+    models::user::UserModel,
+    repositories::user_repository,
+    services::{session_service, verification_service::ResendOutcome},
+    utils::{
+        config::Config,
+        mailer::{Mail, Mailer},
+        password,
+    },
+};
+
+fn token_key(token: &str) -> String {
+    format!("password_reset:token:{}", token)
+}
+
+/// Latest token of a user, so asking again invalidates the previous link.
+fn user_key(user_id: &str) -> String {
+    format!("password_reset:user:{}", user_id)
+}
+
+fn request_key(email: &str) -> String {
+    format!("password_reset:request:{}", email.to_lowercase())
+}
+
+pub struct PasswordResetService {
+    pool: MySqlPool,
+    redis: ConnectionManager,
+    mailer: Arc<dyn Mailer>,
+}
+
+impl PasswordResetService {
+    pub fn new(pool: MySqlPool, redis: ConnectionManager, mailer: Arc<dyn Mailer>) -> Self {
+        Self {
+            pool,
+            redis,
+            mailer,
+        }
+    }
+
+    /// Email a reset token to `email`, at most once per
+    /// `EMAIL_RESEND_INTERVAL`. Unknown emails report `Sent` too, so the
+    /// endpoint does not reveal which accounts exist.
+    pub async fn request_reset(
+        &self,
+        email: &str,
+        config: &Config,
+    ) -> Result<ResendOutcome, String> {
+        let mut redis = self.redis.clone();
+
+        let key = request_key(email);
+        let acquired: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(config.email_resend_interval)
+            .query_async(&mut redis)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if acquired.is_none() {
+            let ttl: i64 = redis.ttl(&key).await.map_err(|e| e.to_string())?;
+            return Ok(ResendOutcome::Throttled(ttl.max(1) as u64));
+        }
+
+        let user = match user_repository::get_user(None, None, Some(email), self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            Some(user) => user,
+            None => return Ok(ResendOutcome::Sent),
+        };
+
+        self.send_reset(&user, config).await?;
+
+        Ok(ResendOutcome::Sent)
+    }
+
+    /// Email a fresh reset token to `user`, replacing any previous one. Not
+    /// throttled, admins use it directly.
+    pub async fn send_reset(&self, user: &UserModel, config: &Config) -> Result<(), String> {
+        let mut redis = self.redis.clone();
+
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let max_age = (config.password_reset_max_age * 60) as u64;
+
+        let previous: Option<String> = redis
+            .get(user_key(&user.id))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut pipe = redis::pipe();
+        if let Some(previous) = previous {
+            pipe.del(token_key(&previous)).ignore();
+        }
+        pipe.set_ex(token_key(&token), &user.id, max_age)
+            .ignore()
+            .set_ex(user_key(&user.id), &token, max_age)
+            .ignore();
+
+        let result: redis::RedisResult<()> = pipe.query_async(&mut redis).await;
+        result.map_err(|e| e.to_string())?;
+
+        self.mailer
+            .send(&Mail {
+                to: user.email.to_owned(),
+                subject: "Reset your password".to_string(),
+                body: format!(
+                    "Hi {},\n\nSomeone asked to reset the password of your account. Open the link below to choose a new one:\n\n{}/reset-password?token={}\n\nOr enter this code in the app: {}\n\nThe link expires in {} minutes and works once. If you did not ask for it, ignore this email.\n",
+                    user.name, config.client_origin, token, token, config.password_reset_max_age
+                ),
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Set a new password for the owner of `token` and log them out
+    /// everywhere. Returns `false` for an unknown, used or expired token.
+    pub async fn reset_password(
+        &self,
+        token: &str,
+        new_password: &str,
+        config: &Config,
+    ) -> Result<bool, String> {
+        let mut redis = self.redis.clone();
+
+        // GETDEL makes the token single use even with concurrent requests.
+        let user_id: Option<String> = redis::cmd("GETDEL")
+            .arg(token_key(token))
+            .query_async(&mut redis)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let user_id = match user_id {
+            Some(user_id) => user_id,
+            None => return Ok(false),
+        };
+
+        let hashed_password = password::hash(new_password, config)?;
+        user_repository::update_user_password(&user_id, &hashed_password, self.pool.clone())
+            .await?;
+
+        let result: redis::RedisResult<usize> = redis.del(user_key(&user_id)).await;
+        result.map_err(|e| e.to_string())?;
+
+        session_service::revoke_all_sessions(&self.redis, &user_id).await?;
+
+        Ok(true)
+    }
+}
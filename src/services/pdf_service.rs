@@ -23,7 +23,11 @@ use typst::syntax::{FileId, Source, VirtualPath};
 use typst::World;
 use uuid::Uuid;
 
-use crate::utils::typst_wrapper_world::TypstWrapperWorld;
+use crate::{
+    dtos::inventory::InventoryValuationDto,
+    models::inventory::ValuationMethod,
+    utils::{money::Money, typst_wrapper_world::TypstWrapperWorld},
+};
 
 #[derive(Debug, Clone)]
 struct Item {
@@ -31,7 +35,7 @@ struct Item {
     keterangan: String,
     kode_ac: String,
     deskripsi: String,
-    nilai: i32,
+    nilai: Money,
 }
 
 #[derive(Debug)]
@@ -50,6 +54,32 @@ impl PdfService {
         let title = "BUKTI PENERIMAAN BANK BCA (2264100550)".to_owned();
         let voucher_number = "B011.2024.01.0181".to_owned();
         let voucher_date = "31 / 01 / 2024".to_owned();
+        let received = Money::from(54_470_000).to_rupiah();
+
+        let items: Vec<Item> = vec![Item {
+            no: 1,
+            nilai: Money::from(57_470_250),
+            keterangan: "spr0050".to_string(),
+            deskripsi: "Piutang Usaha - Pihak Berelasi".to_string(),
+            kode_ac: "1.1.201.100".to_string(),
+        }];
+        let total: Money = items.iter().map(|item| item.nilai).sum();
+        let rows = items
+            .iter()
+            .map(|item| {
+                format!(
+                    "[{}.], [{}], [{}], [{}], cell(align: right)[{}],",
+                    item.no,
+                    item.keterangan,
+                    item.kode_ac,
+                    item.deskripsi,
+                    item.nilai.to_rupiah()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let total_display = total.to_rupiah();
+        let terbilang = total.terbilang();
         
         // ? https://typst.app/docs/guides/table-guide/
         let content = format!(
@@ -115,7 +145,7 @@ impl PdfService {
                         cell(text({detail_font_size})[Count Print]),
                         cell(text({detail_font_size})[: 2 Kali]),
                         cell(text({detail_font_size})[Dengan ini diterima dana sebesar]),
-                        cell(text({detail_font_size})[: Rp 54.470.000]),
+                        cell(text({detail_font_size})[: {received}]),
                         cell(text({detail_font_size})[Keterangan]),
                         cell(text({detail_font_size})[: spr0050]),
                     )
@@ -135,7 +165,7 @@ impl PdfService {
                 ),
                 vline(x: 0, start: 1, stroke: rgb(141, 153, 179)),
                 vline(x: 5, start: 1, stroke: rgb(141, 153, 179)),
-                [1.], [spr0050], [1.    1.1.201.100], [Piutang Usaha - Pihak Berelasi], cell(align: right)[Rp 57.470.250],
+                {rows}
                 hline(stroke: rgb(141, 153, 179))
             )
             #v(0.7cm)
@@ -145,7 +175,7 @@ impl PdfService {
                 text(8pt)[Diterima dengan],
                 align(right)[#text(8pt)[Total Nilai :]],
                 align(right)[#h(0.85cm)],
-                align(right)[#text(8pt)[Rp 57.470.250]],
+                align(right)[#text(8pt)[{total_display}]],
                 align(right)[#h(0.5cm)],
             )
             
@@ -165,7 +195,7 @@ impl PdfService {
                     width: 6.84cm,
                     breakable: false,
                     text(8pt)[Terbilang, \ 
-                    Lima Puluh Tujuh Juta Empat Ratus Tujuh Puluh Ribu Dua Ratus Lima Puluh]
+                    {terbilang}]
                 )
             )
 
@@ -258,6 +288,10 @@ impl PdfService {
             detail_font_size = detail_font_size,
             voucher_number = voucher_number,
             voucher_date = voucher_date,
+            received = received,
+            rows = rows,
+            total_display = total_display,
+            terbilang = terbilang,
         )
         .to_owned();
         // Create world with content.
@@ -275,6 +309,113 @@ impl PdfService {
         ()
     }
 
+    /// Compile typst markup into PDF bytes.
+    fn compile_typst(content: String) -> Result<Vec<u8>, String> {
+        let world = TypstWrapperWorld::new("./".to_owned(), content);
+
+        let mut tracer = Tracer::default();
+        let document = typst::compile(&world, &mut tracer).map_err(|diagnostics| {
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.message.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        })?;
+
+        Ok(typst_pdf::pdf(&document, Smart::Auto, None))
+    }
+
+    pub fn inventory_valuation_pdf(
+        &self,
+        organization_name: &str,
+        valuation: &InventoryValuationDto,
+    ) -> Result<Vec<u8>, String> {
+        let period = match (valuation.from, valuation.to) {
+            (Some(from), Some(to)) => {
+                format!("{} - {}", from.format("%d/%m/%Y"), to.format("%d/%m/%Y"))
+            }
+            (Some(from), None) => format!("Sejak {}", from.format("%d/%m/%Y")),
+            (None, Some(to)) => format!("s.d. {}", to.format("%d/%m/%Y")),
+            (None, None) => "Semua periode".to_string(),
+        };
+        let method = match valuation.method {
+            ValuationMethod::Fifo => "FIFO",
+            ValuationMethod::Average => "Rata-rata Bergerak",
+        };
+
+        let rows = valuation
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                format!(
+                    "[{}.], [{}], cell(align: right)[{}], cell(align: right)[{}], cell(align: right)[{}], cell(align: right)[{}],",
+                    i + 1,
+                    typst_escape(&item.name),
+                    item.quantity,
+                    item.unit_cost.to_rupiah(),
+                    item.value_display,
+                    item.cogs_display
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // ? https://typst.app/docs/guides/table-guide/
+        let content = format!(
+            r#"
+            #import table: cell, header, hline
+
+            #set page(
+                paper: "a4",
+                flipped: true,
+                margin: (x: 1.4cm, y: 1.5cm),
+                numbering: "1 of 1",
+            )
+            #set text(size: 8pt)
+
+            #stack(
+                dir: ltr,
+                text(15pt, weight: "bold")[LAPORAN NILAI PERSEDIAAN],
+                h(1fr),
+                align(top + right, image("assets/images/Powered by Codein.jpg", height: 0.64cm)),
+            )
+            #v(0.4cm)
+            #table(
+                columns: 2,
+                stroke: none,
+                [Organisasi], [: {organization}],
+                [Periode], [: {period}],
+                [Metode], [: {method}],
+            )
+            #v(0.4cm)
+
+            #table(
+                stroke: none,
+                fill: (x, y) => if y == 0 {{ rgb(46, 164, 73) }},
+                columns: (auto, 3fr, 1fr, 2fr, 2fr, 2fr),
+                inset: (y: 0.25cm, x: 0.3cm),
+                header(
+                    repeat: true,
+                    text(white)[No.], text(white)[Barang], cell(align: right)[#text(white)[Stok]], cell(align: right)[#text(white)[Harga Pokok]], cell(align: right)[#text(white)[Nilai Persediaan]], cell(align: right)[#text(white)[HPP]],
+                ),
+                {rows}
+                hline(stroke: rgb(141, 153, 179)),
+                [], text(weight: "bold")[Total], cell(align: right)[#text(weight: "bold")[{total_quantity}]], [], cell(align: right)[#text(weight: "bold")[{total_value}]], cell(align: right)[#text(weight: "bold")[{total_cogs}]],
+            )
+        "#,
+            organization = typst_escape(organization_name),
+            period = period,
+            method = method,
+            rows = rows,
+            total_quantity = valuation.total_quantity,
+            total_value = valuation.total_value_display,
+            total_cogs = valuation.total_cogs_display,
+        );
+
+        Self::compile_typst(content)
+    }
+
     pub async fn generate_pdf_service(
         &self,
         mut payload: Multipart,
@@ -284,35 +425,35 @@ impl PdfService {
         let dummies: Vec<Item> = vec![
             Item {
                 no: 1,
-                nilai: 57000000,
+                nilai: Money::from(57000000),
                 keterangan: "spr0050".to_string(),
                 deskripsi: "Piutang Usaha - Pihak Berelasi".to_string(),
                 kode_ac: "1.1.201.100".to_string(),
             },
             Item {
                 no: 2,
-                nilai: 300000000,
+                nilai: Money::from(300000000),
                 keterangan: "2311-274".to_string(),
                 deskripsi: "Hutang Usaha - Pihak Berelasi".to_string(),
                 kode_ac: "2.2.201.200".to_string(),
             },
             Item {
                 no: 3,
-                nilai: 57000000,
+                nilai: Money::from(57000000),
                 keterangan: "spr0050".to_string(),
                 deskripsi: "Piutang Usaha - Pihak Berelasi".to_string(),
                 kode_ac: "1.1.201.100".to_string(),
             },
             Item {
                 no: 4,
-                nilai: 300000000,
+                nilai: Money::from(300000000),
                 keterangan: "2311-274".to_string(),
                 deskripsi: "Hutang Usaha - Pihak Berelasi".to_string(),
                 kode_ac: "2.2.201.200".to_string(),
             },
             Item {
                 no: 5,
-                nilai: 57000000,
+                nilai: Money::from(57000000),
                 keterangan: "spr0050".to_string(),
                 deskripsi: "Piutang Usaha - Pihak Berelasi".to_string(),
                 kode_ac: "1.1.201.100".to_string(),
@@ -544,8 +685,8 @@ impl PdfService {
                 )
                 .element(
                     Paragraph::new(format!(
-                        "Dengan ini diterima dana sebesar       : Rp {}",
-                        "54.470.000"
+                        "Dengan ini diterima dana sebesar       : {}",
+                        Money::from(54_470_000).to_rupiah()
                     ))
                     .styled(Style::new().with_font_size(8)),
                 )
@@ -1378,3 +1519,15 @@ impl PdfService {
     //     );
     // }
 }
+
+/// Escape text placed inside typst content brackets.
+fn typst_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '[' | ']' | '#' | '*' | '_' | '`' | '$' | '<' | '>' | '@' | '~') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,211 @@
+use sqlx::MySqlPool;
+
+use crate::{
+    models::{
+        permission::{self, RoleModel},
+        user::{UserModel, UserRole},
+    },
+    repositories::permission_repository,
+    schemas::role::CreateRoleSchema,
+};
+
+#[derive(Debug)]
+pub enum RoleChange {
+    Done,
+    NotFound,
+    /// Creating a role whose name is taken.
+    Exists,
+    /// Deleting a built-in role or editing the admin role, which must keep
+    /// every permission so someone can still manage roles.
+    Protected,
+    /// Assigning a role that does not exist.
+    UnknownRole(String),
+    /// Granting a permission the acting user does not hold.
+    NotHeld(String),
+}
+
+#[derive(Debug)]
+pub struct PermissionService {
+    pool: MySqlPool,
+}
+
+impl PermissionService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Everything `user` may do, sorted. Contains [`permission::ALL`] for
+    /// users holding every permission.
+    pub async fn user_permissions(&self, user: &UserModel) -> Result<Vec<String>, String> {
+        let mut permissions: Vec<String> = permission_repository::get_user_permissions(
+            &user.id,
+            user.role.to_str(),
+            self.pool.clone(),
+        )
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|row| row.permission)
+        .collect();
+
+        permissions.sort();
+        permissions.dedup();
+
+        Ok(permissions)
+    }
+
+    pub async fn has_permission(&self, user: &UserModel, permission: &str) -> Result<bool, String> {
+        let permissions = self.user_permissions(user).await?;
+
+        Ok(permissions
+            .iter()
+            .any(|granted| granted == permission || granted == permission::ALL))
+    }
+
+    /// Roles of the user on top of their base role.
+    pub async fn user_roles(&self, user_id: &str) -> Result<Vec<String>, String> {
+        permission_repository::get_user_roles(user_id, self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Every role with its permissions.
+    pub async fn list_roles(&self) -> Result<Vec<(RoleModel, Vec<String>)>, String> {
+        let roles = permission_repository::get_roles(self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        let permissions = permission_repository::get_role_permissions(self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(roles
+            .into_iter()
+            .map(|role| {
+                let granted = permissions
+                    .iter()
+                    .filter(|row| row.role == role.name)
+                    .map(|row| row.permission.clone())
+                    .collect();
+                (role, granted)
+            })
+            .collect())
+    }
+
+    pub async fn get_role(&self, name: &str) -> Result<Option<(RoleModel, Vec<String>)>, String> {
+        Ok(self
+            .list_roles()
+            .await?
+            .into_iter()
+            .find(|(role, _)| role.name == name))
+    }
+
+    /// First of `permissions` that `actor` does not hold. Nobody may hand out
+    /// more than they have, `*` in particular needs `*`.
+    async fn not_held(
+        &self,
+        actor: &UserModel,
+        permissions: &[String],
+    ) -> Result<Option<String>, String> {
+        let held = self.user_permissions(actor).await?;
+        if held.iter().any(|granted| granted == permission::ALL) {
+            return Ok(None);
+        }
+
+        Ok(permissions
+            .iter()
+            .find(|permission| !held.contains(permission))
+            .cloned())
+    }
+
+    pub async fn create_role(
+        &self,
+        actor: &UserModel,
+        body: &CreateRoleSchema,
+    ) -> Result<RoleChange, String> {
+        if let Some(permission) = self.not_held(actor, &body.permissions).await? {
+            return Ok(RoleChange::NotHeld(permission));
+        }
+
+        if self.get_role(&body.name).await?.is_some() {
+            return Ok(RoleChange::Exists);
+        }
+
+        permission_repository::insert_role(
+            &body.name,
+            body.description.as_deref(),
+            &body.permissions,
+            self.pool.clone(),
+        )
+        .await?;
+
+        Ok(RoleChange::Done)
+    }
+
+    pub async fn set_role_permissions(
+        &self,
+        actor: &UserModel,
+        name: &str,
+        permissions: &[String],
+    ) -> Result<RoleChange, String> {
+        if name == UserRole::Admin.to_str() {
+            return Ok(RoleChange::Protected);
+        }
+
+        if let Some(permission) = self.not_held(actor, permissions).await? {
+            return Ok(RoleChange::NotHeld(permission));
+        }
+
+        if self.get_role(name).await?.is_none() {
+            return Ok(RoleChange::NotFound);
+        }
+
+        permission_repository::set_role_permissions(name, permissions, self.pool.clone()).await?;
+
+        Ok(RoleChange::Done)
+    }
+
+    pub async fn delete_role(&self, name: &str) -> Result<RoleChange, String> {
+        match self.get_role(name).await? {
+            None => Ok(RoleChange::NotFound),
+            Some((role, _)) if role.is_built_in() => Ok(RoleChange::Protected),
+            Some(_) => {
+                permission_repository::delete_role(name, self.pool.clone()).await?;
+                Ok(RoleChange::Done)
+            }
+        }
+    }
+
+    /// Replace the additional roles of a user. `actor` must hold every
+    /// permission the roles grant.
+    pub async fn set_user_roles(
+        &self,
+        actor: &UserModel,
+        user_id: &str,
+        roles: &[String],
+    ) -> Result<RoleChange, String> {
+        let known = self.list_roles().await?;
+        if let Some(unknown) = roles
+            .iter()
+            .find(|name| !known.iter().any(|(role, _)| &role.name == *name))
+        {
+            return Ok(RoleChange::UnknownRole(unknown.clone()));
+        }
+
+        let granted: Vec<String> = known
+            .into_iter()
+            .filter(|(role, _)| roles.contains(&role.name))
+            .flat_map(|(_, permissions)| permissions)
+            .collect();
+        if let Some(permission) = self.not_held(actor, &granted).await? {
+            return Ok(RoleChange::NotHeld(permission));
+        }
+
+        let mut roles = roles.to_vec();
+        roles.sort();
+        roles.dedup();
+
+        permission_repository::set_user_roles(user_id, &roles, self.pool.clone()).await?;
+
+        Ok(RoleChange::Done)
+    }
+}
@@ -0,0 +1,375 @@
+use std::{collections::HashMap, net::IpAddr};
+
+use actix_web::{http::header, web, HttpRequest};
+use redis::{aio::ConnectionManager, AsyncCommands};
+
+use crate::{models::session::SessionModel, AppState};
+
+/// Header the apps send to name the device, e.g. `Pixel 8`.
+pub const DEVICE_NAME_HEADER: &str = "X-Device-Name";
+
+/// Redis set of the session ids of a user.
+fn sessions_key(user_id: &str) -> String {
+    format!("sessions:{}", user_id)
+}
+
+/// Redis hash describing one session, see [`SessionModel`].
+fn session_key(session_id: &str) -> String {
+    format!("session:{}", session_id)
+}
+
+/// Redis set of every token uuid issued within a session. The token keys
+/// themselves still map token uuid to user id.
+fn session_tokens_key(session_id: &str) -> String {
+    format!("session:{}:tokens", session_id)
+}
+
+/// Address of the client. `X-Forwarded-For` is only followed while the hop
+/// that added an entry is one of `TRUSTED_PROXIES`, otherwise any client
+/// could name its own IP and dodge the per-IP login limit.
+fn client_ip(req: &HttpRequest, trusted_proxies: &[IpAddr]) -> String {
+    let peer = match req.peer_addr() {
+        Some(peer) => peer.ip(),
+        None => return String::new(),
+    };
+
+    if !trusted_proxies.contains(&peer) {
+        return peer.to_string();
+    }
+
+    let forwarded_for = req
+        .headers()
+        .get_all("X-Forwarded-For")
+        .filter_map(|h| h.to_str().ok())
+        .flat_map(|h| h.split(','))
+        .map(|ip| ip.trim().parse::<IpAddr>())
+        .collect::<Vec<_>>();
+
+    // Proxies append, so the nearest hop is last. Walk back until the first
+    // address that no trusted proxy vouches for.
+    let mut client = peer;
+    for ip in forwarded_for.into_iter().rev() {
+        match ip {
+            Ok(ip) => {
+                client = ip;
+                if !trusted_proxies.contains(&ip) {
+                    break;
+                }
+            }
+            Err(_) => break,
+        }
+    }
+
+    client.to_string()
+}
+
+/// Where a login comes from, recorded on the session.
+#[derive(Debug, Clone)]
+pub struct SessionMeta {
+    pub device_name: String,
+    pub ip_address: String,
+    pub user_agent: String,
+}
+
+impl SessionMeta {
+    pub fn from_request(req: &HttpRequest) -> Self {
+        let header_value = |name| {
+            req.headers()
+                .get(name)
+                .and_then(|h| h.to_str().ok())
+                .map(|h| h.trim().to_string())
+                .filter(|h| !h.is_empty())
+        };
+
+        let user_agent = header_value(header::USER_AGENT.as_str()).unwrap_or_default();
+        let device_name = header_value(DEVICE_NAME_HEADER).unwrap_or_else(|| {
+            user_agent
+                .split_whitespace()
+                .next()
+                .unwrap_or("Unknown device")
+                .to_string()
+        });
+
+        SessionMeta {
+            device_name,
+            ip_address: client_ip(
+                req,
+                req.app_data::<web::Data<AppState>>()
+                    .map(|data| data.config.trusted_proxies.as_slice())
+                    .unwrap_or_default(),
+            ),
+            user_agent,
+        }
+    }
+}
+
+/// Open a session for `user_id` and return its id. Sessions live as long as
+/// the refresh token issued with them.
+pub async fn create_session(
+    redis: &mut ConnectionManager,
+    user_id: &str,
+    meta: &SessionMeta,
+    max_age: u64,
+) -> redis::RedisResult<String> {
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().timestamp();
+    let key = session_key(&session_id);
+
+    redis::pipe()
+        .hset_multiple(
+            &key,
+            &[
+                ("user_id", user_id.to_string()),
+                ("device_name", meta.device_name.to_owned()),
+                ("ip_address", meta.ip_address.to_owned()),
+                ("user_agent", meta.user_agent.to_owned()),
+                ("created_at", now.to_string()),
+                ("last_used_at", now.to_string()),
+            ],
+        )
+        .ignore()
+        .expire(&key, max_age as i64)
+        .ignore()
+        .sadd(sessions_key(user_id), &session_id)
+        .ignore()
+        .expire(sessions_key(user_id), max_age as i64)
+        .ignore()
+        .query_async(redis)
+        .await?;
+
+    Ok(session_id)
+}
+
+/// Remember freshly issued token uuids of a session so revoking the session
+/// also kills them, and extend the session to `max_age`.
+pub async fn track_tokens(
+    redis: &mut ConnectionManager,
+    user_id: &str,
+    session_id: &str,
+    token_uuids: &[String],
+    max_age: u64,
+) -> redis::RedisResult<()> {
+    let tokens_key = session_tokens_key(session_id);
+
+    redis::pipe()
+        .sadd(&tokens_key, token_uuids)
+        .ignore()
+        .expire(&tokens_key, max_age as i64)
+        .ignore()
+        .expire(session_key(session_id), max_age as i64)
+        .ignore()
+        .expire(sessions_key(user_id), max_age as i64)
+        .ignore()
+        .query_async(redis)
+        .await
+}
+
+/// Make `token_uuid` the only refresh token of the session that can be
+/// redeemed, see [`claim_refresh_token`].
+pub async fn set_refresh_token(
+    redis: &mut ConnectionManager,
+    session_id: &str,
+    token_uuid: &str,
+) -> redis::RedisResult<()> {
+    redis
+        .hset(session_key(session_id), "refresh_token_uuid", token_uuid)
+        .await
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RefreshClaim {
+    /// The token was the current one and is now spent.
+    Claimed,
+    /// The token was rotated out before, it is being replayed.
+    Reused,
+    /// The session no longer exists.
+    Expired,
+}
+
+/// Spend the refresh token `token_uuid` of a session. Runs as one script so
+/// two requests racing with the same token can not both succeed.
+pub async fn claim_refresh_token(
+    redis: &mut ConnectionManager,
+    session_id: &str,
+    token_uuid: &str,
+) -> redis::RedisResult<RefreshClaim> {
+    let script = redis::Script::new(
+        r#"
+            local current = redis.call('HGET', KEYS[1], 'refresh_token_uuid')
+            if not current then
+                return 0
+            end
+            if current ~= ARGV[1] then
+                return -1
+            end
+            redis.call('HSET', KEYS[1], 'refresh_token_uuid', '')
+            return 1
+        "#,
+    );
+
+    let claimed: i32 = script
+        .key(session_key(session_id))
+        .arg(token_uuid)
+        .invoke_async(redis)
+        .await?;
+
+    Ok(match claimed {
+        1 => RefreshClaim::Claimed,
+        -1 => RefreshClaim::Reused,
+        _ => RefreshClaim::Expired,
+    })
+}
+
+/// Record that the session was just used. Best effort, the caller already
+/// checked the token itself. A session that expired or was revoked meanwhile
+/// is left alone, writing to it would recreate the hash without a TTL.
+pub async fn touch_session(redis: &ConnectionManager, session_id: &str) {
+    if session_id.is_empty() {
+        return;
+    }
+
+    let script = redis::Script::new(
+        r#"
+            if redis.call('EXISTS', KEYS[1]) == 0 then
+                return 0
+            end
+            redis.call('HSET', KEYS[1], 'last_used_at', ARGV[1])
+            return 1
+        "#,
+    );
+
+    let result: redis::RedisResult<i32> = script
+        .key(session_key(session_id))
+        .arg(chrono::Utc::now().timestamp())
+        .invoke_async(&mut redis.clone())
+        .await;
+
+    if let Err(e) = result {
+        eprintln!("🔥 Failed to update session: {}", e);
+    }
+}
+
+/// Active sessions of `user_id`, most recently used first. Ids of sessions
+/// that already expired are dropped from the index on the way.
+pub async fn list_sessions(
+    redis: &ConnectionManager,
+    user_id: &str,
+) -> Result<Vec<SessionModel>, String> {
+    let mut redis = redis.clone();
+
+    let session_ids: Vec<String> = redis
+        .smembers(sessions_key(user_id))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut sessions = vec![];
+    let mut expired = vec![];
+
+    for session_id in session_ids {
+        let fields: HashMap<String, String> = redis
+            .hgetall(session_key(&session_id))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        match SessionModel::from_fields(session_id.clone(), &fields) {
+            Some(session) => sessions.push(session),
+            None => expired.push(session_id),
+        }
+    }
+
+    if !expired.is_empty() {
+        let result: redis::RedisResult<usize> = redis.srem(sessions_key(user_id), &expired).await;
+        result.map_err(|e| e.to_string())?;
+    }
+
+    sessions.sort_by(|a, b| b.last_used_at.cmp(&a.last_used_at));
+
+    Ok(sessions)
+}
+
+/// Revoke one session of `user_id` with all its tokens. Returns `false` when
+/// the session does not exist or belongs to someone else.
+pub async fn revoke_session(
+    redis: &ConnectionManager,
+    user_id: &str,
+    session_id: &str,
+) -> Result<bool, String> {
+    let mut redis = redis.clone();
+
+    let is_member: bool = redis
+        .sismember(sessions_key(user_id), session_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !is_member {
+        return Ok(false);
+    }
+
+    delete_sessions(&mut redis, user_id, &[session_id.to_string()])
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(true)
+}
+
+/// Delete a single token key.
+pub async fn revoke_token(redis: &ConnectionManager, token_uuid: &str) -> Result<(), String> {
+    let mut redis = redis.clone();
+
+    let result: redis::RedisResult<usize> = redis.del(token_uuid).await;
+    result.map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Revoke every session of `user_id`, logging them out on all devices.
+pub async fn revoke_all_sessions(redis: &ConnectionManager, user_id: &str) -> Result<(), String> {
+    revoke_other_sessions(redis, user_id, None).await
+}
+
+/// Revoke every session of `user_id` except `keep`, logging out the other
+/// devices while the current one stays signed in.
+pub async fn revoke_other_sessions(
+    redis: &ConnectionManager,
+    user_id: &str,
+    keep: Option<&str>,
+) -> Result<(), String> {
+    let mut redis = redis.clone();
+
+    let session_ids: Vec<String> = redis
+        .smembers(sessions_key(user_id))
+        .await
+        .map_err(|e| e.to_string())?;
+    let revoked: Vec<String> = session_ids
+        .into_iter()
+        .filter(|session_id| Some(session_id.as_str()) != keep)
+        .collect();
+
+    delete_sessions(&mut redis, user_id, &revoked)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn delete_sessions(
+    redis: &mut ConnectionManager,
+    user_id: &str,
+    session_ids: &[String],
+) -> redis::RedisResult<()> {
+    if session_ids.is_empty() {
+        return Ok(());
+    }
+
+    let mut keys = vec![];
+    for session_id in session_ids {
+        let tokens: Vec<String> = redis.smembers(session_tokens_key(session_id)).await?;
+        keys.extend(tokens);
+        keys.push(session_tokens_key(session_id));
+        keys.push(session_key(session_id));
+    }
+
+    redis::pipe()
+        .del(keys)
+        .ignore()
+        .srem(sessions_key(user_id), session_ids)
+        .ignore()
+        .query_async(redis)
+        .await
+}
@@ -0,0 +1,362 @@
+use redis::{aio::ConnectionManager, AsyncCommands};
+use sqlx::MySqlPool;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use crate::{
+    models::{two_factor::TwoFactorModel, user::UserModel},
+    repositories::{two_factor_repository, user_repository},
+    utils::{config::Config, password},
+};
+
+const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Wrong codes a login challenge accepts before it is thrown away.
+const MAX_CHALLENGE_ATTEMPTS: i64 = 5;
+
+/// Redis hash of a pending two-step login: the user and the attempts so far.
+fn challenge_key(challenge: &str) -> String {
+    format!("two_factor:challenge:{}", challenge)
+}
+
+/// Marks a TOTP code as spent so it can not be replayed within its window.
+fn used_code_key(user_id: &str, code: &str) -> String {
+    format!("two_factor:used:{}:{}", user_id, code)
+}
+
+/// Secret and `otpauth://` URI to show as QR code while enrolling.
+#[derive(Debug)]
+pub struct TwoFactorSetup {
+    pub secret: String,
+    pub otpauth_url: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ChallengeOutcome {
+    /// The code was right, the login can go on.
+    Passed,
+    /// Wrong code, the challenge can be retried.
+    Failed,
+    /// The challenge expired or ran out of attempts.
+    Expired,
+}
+
+/// Which second factor a code matched, recovery codes are not spent yet.
+enum SecondFactor {
+    Wrong,
+    Totp,
+    RecoveryCode(String),
+}
+
+pub struct TwoFactorService {
+    pool: MySqlPool,
+    redis: ConnectionManager,
+}
+
+impl TwoFactorService {
+    pub fn new(pool: MySqlPool, redis: ConnectionManager) -> Self {
+        Self { pool, redis }
+    }
+
+    pub async fn get(&self, user_id: &str) -> Result<Option<TwoFactorModel>, String> {
+        two_factor_repository::get_two_factor(user_id, self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn is_enabled(&self, user_id: &str) -> Result<bool, String> {
+        Ok(self
+            .get(user_id)
+            .await?
+            .is_some_and(|two_factor| two_factor.is_enabled()))
+    }
+
+    pub async fn recovery_codes_left(&self, user_id: &str) -> Result<usize, String> {
+        two_factor_repository::get_unused_recovery_codes(user_id, self.pool.clone())
+            .await
+            .map(|codes| codes.len())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Generate a fresh secret for `user`. It stays pending until
+    /// [`TwoFactorService::enable`] confirms a code from the authenticator.
+    pub async fn begin_setup(
+        &self,
+        user: &UserModel,
+        config: &Config,
+    ) -> Result<TwoFactorSetup, String> {
+        let secret = Secret::generate_secret()
+            .to_bytes()
+            .map_err(|e| format!("{:?}", e))?;
+        let totp = build_totp(secret, &user.email, config)?;
+        let encoded = totp.get_secret_base32();
+
+        two_factor_repository::upsert_two_factor_secret(&user.id, &encoded, self.pool.clone())
+            .await?;
+
+        Ok(TwoFactorSetup {
+            secret: encoded,
+            otpauth_url: totp.get_url(),
+        })
+    }
+
+    /// Enable the pending secret when `code` matches it. Returns the recovery
+    /// codes in plain text, the only time they are shown, or `None` for a
+    /// wrong code or when no setup was started.
+    pub async fn enable(
+        &self,
+        user: &UserModel,
+        code: &str,
+        config: &Config,
+    ) -> Result<Option<Vec<String>>, String> {
+        let two_factor = match self.get(&user.id).await? {
+            Some(two_factor) if !two_factor.is_enabled() => two_factor,
+            _ => return Ok(None),
+        };
+
+        if !self.check_totp(user, &two_factor, code, config).await? {
+            return Ok(None);
+        }
+
+        let (codes, code_hashes) = generate_recovery_codes(config)?;
+        two_factor_repository::enable_two_factor(&user.id, &code_hashes, self.pool.clone()).await?;
+
+        Ok(Some(codes))
+    }
+
+    pub async fn disable(&self, user_id: &str) -> Result<(), String> {
+        two_factor_repository::delete_two_factor(user_id, self.pool.clone())
+            .await
+            .map(|_| ())
+    }
+
+    /// Replace the recovery codes of `user_id`, invalidating the old ones.
+    pub async fn regenerate_recovery_codes(
+        &self,
+        user_id: &str,
+        config: &Config,
+    ) -> Result<Vec<String>, String> {
+        let (codes, code_hashes) = generate_recovery_codes(config)?;
+        two_factor_repository::set_recovery_codes(user_id, &code_hashes, self.pool.clone()).await?;
+
+        Ok(codes)
+    }
+
+    /// Check a second factor of `user`: a current TOTP code or an unused
+    /// recovery code, which is spent on success.
+    pub async fn verify(
+        &self,
+        user: &UserModel,
+        code: &str,
+        config: &Config,
+    ) -> Result<bool, String> {
+        match self.match_code(user, code, config).await? {
+            SecondFactor::Wrong => Ok(false),
+            SecondFactor::Totp => Ok(true),
+            SecondFactor::RecoveryCode(code_id) => {
+                two_factor_repository::use_recovery_code(&code_id, self.pool.clone()).await
+            }
+        }
+    }
+
+    async fn match_code(
+        &self,
+        user: &UserModel,
+        code: &str,
+        config: &Config,
+    ) -> Result<SecondFactor, String> {
+        let two_factor = match self.get(&user.id).await? {
+            Some(two_factor) if two_factor.is_enabled() => two_factor,
+            _ => return Ok(SecondFactor::Wrong),
+        };
+
+        let code = code.trim();
+        if code.len() == 6 && code.chars().all(|c| c.is_ascii_digit()) {
+            let valid = self.check_totp(user, &two_factor, code, config).await?;
+            return Ok(if valid {
+                SecondFactor::Totp
+            } else {
+                SecondFactor::Wrong
+            });
+        }
+
+        let code = code.to_lowercase();
+        let recovery_codes =
+            two_factor_repository::get_unused_recovery_codes(&user.id, self.pool.clone())
+                .await
+                .map_err(|e| e.to_string())?;
+
+        for recovery_code in recovery_codes {
+            if password::compare(&code, &recovery_code.code_hash)? {
+                return Ok(SecondFactor::RecoveryCode(recovery_code.id));
+            }
+        }
+
+        Ok(SecondFactor::Wrong)
+    }
+
+    /// Park a login that passed the password check until the second factor
+    /// arrives. Returns the challenge the client sends back with the code.
+    pub async fn create_challenge(&self, user_id: &str, config: &Config) -> Result<String, String> {
+        let mut redis = self.redis.clone();
+
+        let challenge = uuid::Uuid::new_v4().simple().to_string();
+        let key = challenge_key(&challenge);
+
+        let result: redis::RedisResult<()> = redis::pipe()
+            .hset_multiple(&key, &[("user_id", user_id), ("attempts", "0")])
+            .ignore()
+            .expire(&key, config.two_factor_challenge_max_age as i64)
+            .ignore()
+            .query_async(&mut redis)
+            .await;
+        result.map_err(|e| e.to_string())?;
+
+        Ok(challenge)
+    }
+
+    /// User waiting on `challenge`, `None` when it expired.
+    pub async fn challenge_user(&self, challenge: &str) -> Result<Option<UserModel>, String> {
+        let mut redis = self.redis.clone();
+
+        let user_id: Option<String> = redis
+            .hget(challenge_key(challenge), "user_id")
+            .await
+            .map_err(|e| e.to_string())?;
+
+        match user_id {
+            Some(user_id) => {
+                user_repository::get_user(Some(&user_id), None, None, self.pool.clone())
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Answer the login challenge of `user` with `code`. A passed challenge
+    /// is deleted so it can not be used twice, and a recovery code is only
+    /// spent once the challenge was claimed.
+    pub async fn answer_challenge(
+        &self,
+        challenge: &str,
+        user: &UserModel,
+        code: &str,
+        config: &Config,
+    ) -> Result<ChallengeOutcome, String> {
+        let mut redis = self.redis.clone();
+
+        let key = challenge_key(challenge);
+
+        let second_factor = self.match_code(user, code, config).await?;
+
+        if !matches!(second_factor, SecondFactor::Wrong) {
+            let deleted: usize = redis.del(&key).await.map_err(|e| e.to_string())?;
+            // A concurrent request with the same challenge already won.
+            if deleted != 1 {
+                return Ok(ChallengeOutcome::Expired);
+            }
+
+            if let SecondFactor::RecoveryCode(code_id) = second_factor {
+                // Spent meanwhile by another login, this one has to start over.
+                if !two_factor_repository::use_recovery_code(&code_id, self.pool.clone()).await? {
+                    return Ok(ChallengeOutcome::Expired);
+                }
+            }
+
+            return Ok(ChallengeOutcome::Passed);
+        }
+
+        // Only count on a challenge that still exists, HINCRBY on an expired
+        // one would create a key without TTL.
+        let script = redis::Script::new(
+            r#"
+                if redis.call('EXISTS', KEYS[1]) == 0 then
+                    return -1
+                end
+                return redis.call('HINCRBY', KEYS[1], 'attempts', 1)
+            "#,
+        );
+
+        let attempts: i64 = script
+            .key(&key)
+            .invoke_async(&mut redis)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if attempts < 0 {
+            return Ok(ChallengeOutcome::Expired);
+        }
+
+        if attempts >= MAX_CHALLENGE_ATTEMPTS {
+            let result: redis::RedisResult<usize> = redis.del(&key).await;
+            result.map_err(|e| e.to_string())?;
+            return Ok(ChallengeOutcome::Expired);
+        }
+
+        Ok(ChallengeOutcome::Failed)
+    }
+
+    async fn check_totp(
+        &self,
+        user: &UserModel,
+        two_factor: &TwoFactorModel,
+        code: &str,
+        config: &Config,
+    ) -> Result<bool, String> {
+        let secret = Secret::Encoded(two_factor.secret.to_owned())
+            .to_bytes()
+            .map_err(|e| format!("{:?}", e))?;
+        let totp = build_totp(secret, &user.email, config)?;
+
+        if !totp.check_current(code.trim()).map_err(|e| e.to_string())? {
+            return Ok(false);
+        }
+
+        let mut redis = self.redis.clone();
+
+        // A code stays valid for one step either side, keep it spent as long.
+        let fresh: Option<String> = redis::cmd("SET")
+            .arg(used_code_key(&user.id, code.trim()))
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(totp.step * 3)
+            .query_async(&mut redis)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(fresh.is_some())
+    }
+}
+
+/// RFC 6238 defaults every authenticator app understands: SHA-1, 6 digits,
+/// 30 second steps, one step of clock skew.
+fn build_totp(secret: Vec<u8>, account_name: &str, config: &Config) -> Result<TOTP, String> {
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        1,
+        30,
+        secret,
+        Some(config.two_factor_issuer.to_owned()),
+        account_name.to_string(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Plain recovery codes like `4f9c2-a81d0` together with their hashes.
+fn generate_recovery_codes(config: &Config) -> Result<(Vec<String>, Vec<String>), String> {
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let random = uuid::Uuid::new_v4().simple().to_string();
+            format!("{}-{}", &random[..5], &random[5..10])
+        })
+        .collect();
+
+    let code_hashes = codes
+        .iter()
+        .map(|code| password::hash(code, config))
+        .collect::<Result<Vec<String>, String>>()?;
+
+    Ok((codes, code_hashes))
+}
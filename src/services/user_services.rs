@@ -1,6 +1,11 @@
 use crate::{
-    models::user::UserModel, repositories::user_repository, schemas::user::UpdatePhotoUserSchema,
-    utils::config::Config,
+    models::user::{UserModel, UserRole},
+    repositories::user_repository,
+    schemas::{
+        admin::ListUsersSchema,
+        user::{UpdatePhotoUserSchema, UpdateProfileSchema},
+    },
+    utils::{config::Config, password},
 };
 use actix_multipart::Multipart;
 use futures_util::{StreamExt, TryStreamExt};
@@ -83,10 +88,118 @@ impl UserService {
             }
         }
 
-        let query_result =
-            user_repository::update_user(user_id.unwrap(), Some(&saved_name), self.pool.clone())
-                .await;
+        let query_result = user_repository::update_user_photo(
+            user_id.unwrap(),
+            Some(&saved_name),
+            self.pool.clone(),
+        )
+        .await;
 
         Ok(query_result?)
     }
+
+    pub async fn update_profile(
+        &self,
+        user_id: &str,
+        profile: &UpdateProfileSchema,
+    ) -> Result<(), String> {
+        user_repository::update_user_profile(user_id, profile, self.pool.clone()).await?;
+
+        Ok(())
+    }
+
+    /// Replace the password of `user` after checking `current_password`.
+    /// Returns `false` when the current password is wrong.
+    pub async fn change_password(
+        &self,
+        user: &UserModel,
+        current_password: &str,
+        new_password: &str,
+        config: &Config,
+    ) -> Result<bool, String> {
+        if !password::compare(current_password, &user.password)? {
+            return Ok(false);
+        }
+
+        let hashed_password = password::hash(new_password, config)?;
+        user_repository::update_user_password(&user.id, &hashed_password, self.pool.clone())
+            .await?;
+
+        Ok(true)
+    }
+
+    /// Move `user` to a new, unverified email after checking
+    /// `current_password`. Returns `false` when the current password is wrong.
+    pub async fn change_email(
+        &self,
+        user: &UserModel,
+        current_password: &str,
+        email: &str,
+    ) -> Result<bool, String> {
+        if !password::compare(current_password, &user.password)? {
+            return Ok(false);
+        }
+
+        user_repository::update_user_email(&user.id, email, self.pool.clone()).await?;
+
+        Ok(true)
+    }
+
+    pub async fn list_users(
+        &self,
+        filter: &ListUsersSchema,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<UserModel>, i64), String> {
+        user_repository::list_users(filter, page, per_page, self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn set_role(&self, user_id: &str, role: UserRole) -> Result<(), String> {
+        user_repository::set_user_role(user_id, role, self.pool.clone())
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn set_verified(&self, user_id: &str, verified: bool) -> Result<(), String> {
+        user_repository::set_user_verified(user_id, verified, self.pool.clone())
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn set_disabled(&self, user_id: &str, disabled: bool) -> Result<(), String> {
+        user_repository::set_user_disabled(user_id, disabled, self.pool.clone())
+            .await
+            .map(|_| ())
+    }
+
+    /// Replace the password of `user_id` with a random one nobody knows, so
+    /// only a reset link gets the account back.
+    pub async fn scramble_password(&self, user_id: &str, config: &Config) -> Result<(), String> {
+        let hashed_password = password::hash(uuid::Uuid::new_v4().simple().to_string(), config)?;
+        user_repository::update_user_password(user_id, &hashed_password, self.pool.clone())
+            .await
+            .map(|_| ())
+    }
+
+    /// Store `plain_password` hashed with the current Argon2 settings when the
+    /// stored hash of `user` was made with other ones. The caller has just
+    /// verified `plain_password`.
+    pub async fn rehash_password_if_needed(
+        &self,
+        user: &UserModel,
+        plain_password: &str,
+        config: &Config,
+    ) -> Result<bool, String> {
+        if !password::needs_rehash(&user.password, config) {
+            return Ok(false);
+        }
+
+        let hashed_password = password::hash(plain_password, config)?;
+        user_repository::update_user_password(&user.id, &hashed_password, self.pool.clone())
+            .await?;
+
+        Ok(true)
+    }
 }
@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use redis::{aio::ConnectionManager, AsyncCommands};
+use sqlx::MySqlPool;
+
+use crate::{
+    models::user::UserModel,
+    repositories::user_repository,
+    utils::{
+        config::Config,
+        mailer::{Mail, Mailer},
+    },
+};
+
+fn token_key(token: &str) -> String {
+    format!("email_verification:token:{}", token)
+}
+
+/// Latest token of a user, so a resend invalidates the previous link.
+fn user_key(user_id: &str) -> String {
+    format!("email_verification:user:{}", user_id)
+}
+
+fn resend_key(email: &str) -> String {
+    format!("email_verification:resend:{}", email.to_lowercase())
+}
+
+#[derive(Debug)]
+pub enum ResendOutcome {
+    Sent,
+    /// Asked again too soon, carries the seconds left before the next try.
+    Throttled(u64),
+}
+
+pub struct VerificationService {
+    pool: MySqlPool,
+    redis: ConnectionManager,
+    mailer: Arc<dyn Mailer>,
+}
+
+impl VerificationService {
+    pub fn new(pool: MySqlPool, redis: ConnectionManager, mailer: Arc<dyn Mailer>) -> Self {
+        Self {
+            pool,
+            redis,
+            mailer,
+        }
+    }
+
+    /// Issue a new verification token for `user` and email its link.
+    pub async fn send_verification(&self, user: &UserModel, config: &Config) -> Result<(), String> {
+        let mut redis = self.redis.clone();
+
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let max_age = (config.email_verification_max_age * 60) as u64;
+
+        let previous: Option<String> = redis
+            .get(user_key(&user.id))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut pipe = redis::pipe();
+        if let Some(previous) = previous {
+            pipe.del(token_key(&previous)).ignore();
+        }
+        pipe.set_ex(token_key(&token), &user.id, max_age)
+            .ignore()
+            .set_ex(user_key(&user.id), &token, max_age)
+            .ignore()
+            .set_ex(resend_key(&user.email), 1, config.email_resend_interval)
+            .ignore();
+
+        let result: redis::RedisResult<()> = pipe.query_async(&mut redis).await;
+        result.map_err(|e| e.to_string())?;
+
+        // The link opens a confirm page that posts the token back, so a mail
+        // scanner prefetching the link does not verify the account.
+        let link = format!("{}/auth/verify-email?token={}", config.app_url, token);
+
+        self.mailer
+            .send(&Mail {
+                to: user.email.to_owned(),
+                subject: "Verify your email".to_string(),
+                body: format!(
+                    "Hi {},\n\nPlease verify your email by opening the link below:\n\n{}\n\nThe link expires in {} minutes. If you did not create an account, ignore this email.\n",
+                    user.name, link, config.email_verification_max_age
+                ),
+            })
+            .await
+    }
+
+    /// Send a new link to `email` unless one was sent within
+    /// `EMAIL_RESEND_INTERVAL`. Unknown or already verified emails report
+    /// `Sent` too, so the endpoint does not reveal which accounts exist.
+    pub async fn resend(&self, email: &str, config: &Config) -> Result<ResendOutcome, String> {
+        let mut redis = self.redis.clone();
+
+        let key = resend_key(email);
+        let acquired: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(config.email_resend_interval)
+            .query_async(&mut redis)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if acquired.is_none() {
+            let ttl: i64 = redis.ttl(&key).await.map_err(|e| e.to_string())?;
+            return Ok(ResendOutcome::Throttled(ttl.max(1) as u64));
+        }
+
+        let user = user_repository::get_user(None, None, Some(email), self.pool.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        match user {
+            Some(user) if user.verified == 0 => {
+                self.send_verification(&user, config).await?;
+            }
+            _ => {}
+        }
+
+        Ok(ResendOutcome::Sent)
+    }
+
+    /// Mark the owner of `token` as verified. Returns `false` for an unknown
+    /// or expired token. Tokens are single use.
+    pub async fn verify(&self, token: &str) -> Result<bool, String> {
+        let mut redis = self.redis.clone();
+
+        let user_id: Option<String> = redis
+            .get(token_key(token))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let user_id = match user_id {
+            Some(user_id) => user_id,
+            None => return Ok(false),
+        };
+
+        user_repository::set_user_verified(&user_id, true, self.pool.clone()).await?;
+
+        let result: redis::RedisResult<usize> =
+            redis.del(&[token_key(token), user_key(&user_id)]).await;
+        result.map_err(|e| e.to_string())?;
+
+        Ok(true)
+    }
+}
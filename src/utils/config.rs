@@ -1,25 +1,123 @@
+use std::{collections::HashSet, net::IpAddr, sync::Arc};
+
+use crate::models::inventory::ValuationMethod;
+
 fn get_env_var(var_name: &str) -> String {
     std::env::var(var_name).unwrap_or_else(|_| panic!("{} must be set", var_name))
 }
 
+fn get_env_var_or(var_name: &str, default: &str) -> String {
+    std::env::var(var_name).unwrap_or_else(|_| default.to_string())
+}
+
+/// Comma separated list, empty entries are skipped.
+fn get_env_list(var_name: &str) -> Vec<String> {
+    get_env_var_or(var_name, "")
+        .split(',')
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Common passwords from `PASSWORD_BLOCKLIST_FILE`, one per line, lowercased
+/// for a case-insensitive match. Lines starting with `#` are comments.
+fn load_password_blocklist(path: &str) -> HashSet<String> {
+    if path.is_empty() {
+        return HashSet::new();
+    }
+
+    std::fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("PASSWORD_BLOCKLIST_FILE {} can not be read: {}", path, e))
+        .lines()
+        .map(|line| line.trim().to_lowercase())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+/// `scheme://host[:port]` of a URL, the form browsers send in `Origin`.
+fn origin_of(url: &str) -> String {
+    url.trim()
+        .splitn(4, '/')
+        .take(3)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub port: u16,
     pub storage_dir: String,
     pub client_origin: String,
+    pub app_url: String,
+    /// Web apps besides `client_origin` allowed to call the API with cookies.
+    pub cors_allowed_origins: Vec<String>,
+    /// Reverse proxies whose `X-Forwarded-For` is believed.
+    pub trusted_proxies: Vec<IpAddr>,
 
     pub database_url: String,
     pub redis_url: String,
 
     pub access_token_private_key: String,
     pub access_token_public_key: String,
+    /// Keys tokens are still accepted with but not signed with, e.g. the
+    /// previous or upcoming key of a rollover.
+    pub access_token_extra_public_keys: Vec<String>,
     pub access_token_expires_in: String,
     pub access_token_max_age: i64,
 
     pub refresh_token_private_key: String,
     pub refresh_token_public_key: String,
+    pub refresh_token_extra_public_keys: Vec<String>,
     pub refresh_token_expires_in: String,
     pub refresh_token_max_age: i64,
+
+    pub inventory_valuation_method: ValuationMethod,
+    pub low_stock_threshold: i32,
+    pub expiring_soon_days: i32,
+    pub dashboard_cache_ttl: u64,
+
+    pub mailer: String,
+    pub mail_from: String,
+    pub mail_outbox_dir: String,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_username: String,
+    pub smtp_password: String,
+
+    pub email_verification_max_age: i64,
+    pub email_resend_interval: u64,
+    pub require_verified_email: bool,
+    pub password_reset_max_age: i64,
+
+    pub argon2_memory_kib: u32,
+    pub argon2_iterations: u32,
+    pub argon2_parallelism: u32,
+    pub password_min_length: usize,
+    pub password_max_length: usize,
+    pub password_require_lowercase: bool,
+    pub password_require_uppercase: bool,
+    pub password_require_digit: bool,
+    pub password_require_symbol: bool,
+    pub password_blocklist: Arc<HashSet<String>>,
+
+    pub login_max_attempts: u64,
+    pub login_max_attempts_per_ip: u64,
+    pub login_backoff_base_seconds: u64,
+    pub login_lockout_seconds: u64,
+
+    pub two_factor_issuer: String,
+    pub two_factor_challenge_max_age: u64,
+    pub require_admin_two_factor: bool,
+
+    /// Empty when OpenID Connect login is off.
+    pub oidc_issuer: String,
+    pub oidc_client_id: String,
+    pub oidc_client_secret: String,
+    pub oidc_redirect_url: String,
+    pub oidc_scopes: String,
+    pub oidc_link_by_email: bool,
+    pub oidc_create_users: bool,
+    pub oidc_state_max_age: u64,
 }
 
 impl Config {
@@ -27,37 +125,181 @@ impl Config {
         let port = get_env_var("PORT");
         let storage_dir = get_env_var("STORAGE_DIR");
         let client_origin = get_env_var("CLIENT_ORIGIN");
+        let app_url = get_env_var_or("APP_URL", &format!("http://localhost:{}", port));
+        let cors_allowed_origins = get_env_list("CORS_ALLOWED_ORIGINS");
+        let trusted_proxies = get_env_list("TRUSTED_PROXIES");
 
         let database_url = get_env_var("DATABASE_URL");
         let redis_url = get_env_var("REDIS_URL");
 
         let access_token_private_key = get_env_var("ACCESS_TOKEN_PRIVATE_KEY");
         let access_token_public_key = get_env_var("ACCESS_TOKEN_PUBLIC_KEY");
+        let access_token_extra_public_keys = get_env_list("ACCESS_TOKEN_EXTRA_PUBLIC_KEYS");
         let access_token_expires_in = get_env_var("ACCESS_TOKEN_EXPIRED_IN");
         let access_token_max_age = get_env_var("ACCESS_TOKEN_MAXAGE");
 
         let refresh_token_private_key = get_env_var("REFRESH_TOKEN_PRIVATE_KEY");
         let refresh_token_public_key = get_env_var("REFRESH_TOKEN_PUBLIC_KEY");
+        let refresh_token_extra_public_keys = get_env_list("REFRESH_TOKEN_EXTRA_PUBLIC_KEYS");
         let refresh_token_expires_in = get_env_var("REFRESH_TOKEN_EXPIRED_IN");
         let refresh_token_max_age = get_env_var("REFRESH_TOKEN_MAXAGE");
 
+        let inventory_valuation_method = get_env_var_or("INVENTORY_VALUATION_METHOD", "fifo");
+        let low_stock_threshold = get_env_var_or("LOW_STOCK_THRESHOLD", "5");
+        let expiring_soon_days = get_env_var_or("EXPIRING_SOON_DAYS", "30");
+        let dashboard_cache_ttl = get_env_var_or("DASHBOARD_CACHE_TTL", "60");
+
+        let mailer = get_env_var_or("MAILER", "file");
+        let mail_from = get_env_var_or("MAIL_FROM", "no-reply@localhost");
+        let mail_outbox_dir = get_env_var_or("MAIL_OUTBOX_DIR", "outbox/");
+        let smtp_host = get_env_var_or("SMTP_HOST", "localhost");
+        let smtp_port = get_env_var_or("SMTP_PORT", "587");
+        let smtp_username = get_env_var_or("SMTP_USERNAME", "");
+        let smtp_password = get_env_var_or("SMTP_PASSWORD", "");
+
+        let email_verification_max_age = get_env_var_or("EMAIL_VERIFICATION_MAXAGE", "1440");
+        let email_resend_interval = get_env_var_or("EMAIL_RESEND_INTERVAL", "60");
+        let require_verified_email = get_env_var_or("REQUIRE_VERIFIED_EMAIL", "false");
+        let password_reset_max_age = get_env_var_or("PASSWORD_RESET_MAXAGE", "30");
+
+        // Defaults are the ones of `Argon2::default()`, existing hashes stay as they are.
+        let argon2_memory_kib = get_env_var_or("ARGON2_MEMORY_KIB", "19456");
+        let argon2_iterations = get_env_var_or("ARGON2_ITERATIONS", "2");
+        let argon2_parallelism = get_env_var_or("ARGON2_PARALLELISM", "1");
+        let password_min_length = get_env_var_or("PASSWORD_MIN_LENGTH", "6");
+        let password_max_length = get_env_var_or("PASSWORD_MAX_LENGTH", "64");
+        let password_require_lowercase = get_env_var_or("PASSWORD_REQUIRE_LOWERCASE", "false");
+        let password_require_uppercase = get_env_var_or("PASSWORD_REQUIRE_UPPERCASE", "false");
+        let password_require_digit = get_env_var_or("PASSWORD_REQUIRE_DIGIT", "false");
+        let password_require_symbol = get_env_var_or("PASSWORD_REQUIRE_SYMBOL", "false");
+        let password_blocklist_file = get_env_var_or("PASSWORD_BLOCKLIST_FILE", "");
+
+        let login_max_attempts = get_env_var_or("LOGIN_MAX_ATTEMPTS", "5");
+        let login_max_attempts_per_ip = get_env_var_or("LOGIN_MAX_ATTEMPTS_PER_IP", "20");
+        let login_backoff_base_seconds = get_env_var_or("LOGIN_BACKOFF_BASE_SECONDS", "1");
+        let login_lockout_seconds = get_env_var_or("LOGIN_LOCKOUT_SECONDS", "900");
+
+        let two_factor_issuer = get_env_var_or("TWO_FACTOR_ISSUER", "Rust Flutter Application");
+        let two_factor_challenge_max_age = get_env_var_or("TWO_FACTOR_CHALLENGE_MAXAGE", "300");
+        let require_admin_two_factor = get_env_var_or("REQUIRE_ADMIN_TWO_FACTOR", "false");
+
+        let oidc_issuer = get_env_var_or("OIDC_ISSUER", "");
+        let oidc_client_id = get_env_var_or("OIDC_CLIENT_ID", "");
+        let oidc_client_secret = get_env_var_or("OIDC_CLIENT_SECRET", "");
+        let oidc_redirect_url = get_env_var_or(
+            "OIDC_REDIRECT_URL",
+            &format!("{}/auth/oidc/callback", app_url.trim_end_matches('/')),
+        );
+        let oidc_scopes = get_env_var_or("OIDC_SCOPES", "openid email profile");
+        let oidc_link_by_email = get_env_var_or("OIDC_LINK_BY_EMAIL", "true");
+        let oidc_create_users = get_env_var_or("OIDC_CREATE_USERS", "true");
+        let oidc_state_max_age = get_env_var_or("OIDC_STATE_MAXAGE", "600");
+
         Config {
             port: port.parse::<u16>().unwrap(),
             storage_dir,
             client_origin,
+            app_url: app_url.trim_end_matches('/').to_string(),
+            cors_allowed_origins,
+            trusted_proxies: trusted_proxies
+                .iter()
+                .map(|ip| ip.parse::<IpAddr>().unwrap())
+                .collect(),
 
             database_url,
             redis_url,
 
             access_token_private_key,
             access_token_public_key,
+            access_token_extra_public_keys,
             access_token_expires_in,
             access_token_max_age: access_token_max_age.parse::<i64>().unwrap(),
 
             refresh_token_private_key,
             refresh_token_public_key,
+            refresh_token_extra_public_keys,
             refresh_token_expires_in,
             refresh_token_max_age: refresh_token_max_age.parse::<i64>().unwrap(),
+
+            inventory_valuation_method: ValuationMethod::from(inventory_valuation_method),
+            low_stock_threshold: low_stock_threshold.parse::<i32>().unwrap(),
+            expiring_soon_days: expiring_soon_days.parse::<i32>().unwrap(),
+            dashboard_cache_ttl: dashboard_cache_ttl.parse::<u64>().unwrap(),
+
+            mailer,
+            mail_from,
+            mail_outbox_dir,
+            smtp_host,
+            smtp_port: smtp_port.parse::<u16>().unwrap(),
+            smtp_username,
+            smtp_password,
+
+            email_verification_max_age: email_verification_max_age.parse::<i64>().unwrap(),
+            email_resend_interval: email_resend_interval.parse::<u64>().unwrap(),
+            require_verified_email: require_verified_email.parse::<bool>().unwrap(),
+            password_reset_max_age: password_reset_max_age.parse::<i64>().unwrap(),
+
+            argon2_memory_kib: argon2_memory_kib.parse::<u32>().unwrap(),
+            argon2_iterations: argon2_iterations.parse::<u32>().unwrap(),
+            argon2_parallelism: argon2_parallelism.parse::<u32>().unwrap(),
+            password_min_length: password_min_length.parse::<usize>().unwrap(),
+            password_max_length: password_max_length.parse::<usize>().unwrap(),
+            password_require_lowercase: password_require_lowercase.parse::<bool>().unwrap(),
+            password_require_uppercase: password_require_uppercase.parse::<bool>().unwrap(),
+            password_require_digit: password_require_digit.parse::<bool>().unwrap(),
+            password_require_symbol: password_require_symbol.parse::<bool>().unwrap(),
+            password_blocklist: Arc::new(load_password_blocklist(&password_blocklist_file)),
+
+            login_max_attempts: login_max_attempts.parse::<u64>().unwrap(),
+            login_max_attempts_per_ip: login_max_attempts_per_ip.parse::<u64>().unwrap(),
+            login_backoff_base_seconds: login_backoff_base_seconds.parse::<u64>().unwrap(),
+            login_lockout_seconds: login_lockout_seconds.parse::<u64>().unwrap(),
+
+            two_factor_issuer,
+            two_factor_challenge_max_age: two_factor_challenge_max_age.parse::<u64>().unwrap(),
+            require_admin_two_factor: require_admin_two_factor.parse::<bool>().unwrap(),
+
+            oidc_issuer: oidc_issuer.trim_end_matches('/').to_string(),
+            oidc_client_id,
+            oidc_client_secret,
+            oidc_redirect_url,
+            oidc_scopes,
+            oidc_link_by_email: oidc_link_by_email.parse::<bool>().unwrap(),
+            oidc_create_users: oidc_create_users.parse::<bool>().unwrap(),
+            oidc_state_max_age: oidc_state_max_age.parse::<u64>().unwrap(),
         }
     }
+
+    /// Origins of the web apps that may call the API with cookies. Empty when
+    /// neither `CLIENT_ORIGIN` nor `CORS_ALLOWED_ORIGINS` is set.
+    pub fn allowed_origins(&self) -> Vec<String> {
+        std::iter::once(&self.client_origin)
+            .chain(self.cors_allowed_origins.iter())
+            .filter(|url| !url.trim().is_empty())
+            .map(|url| origin_of(url))
+            .collect()
+    }
+
+    /// An allowed web app or the API itself, e.g. the Swagger UI.
+    pub fn is_trusted_origin(&self, origin: &str) -> bool {
+        let origin = origin_of(origin);
+        origin == origin_of(&self.app_url) || self.allowed_origins().contains(&origin)
+    }
+
+    pub fn oidc_enabled(&self) -> bool {
+        !self.oidc_issuer.is_empty() && !self.oidc_client_id.is_empty()
+    }
+
+    /// Every key an access token may be signed with, the signing key first.
+    pub fn access_token_public_keys(&self) -> Vec<String> {
+        let mut keys = vec![self.access_token_public_key.clone()];
+        keys.extend(self.access_token_extra_public_keys.iter().cloned());
+        keys
+    }
+
+    pub fn refresh_token_public_keys(&self) -> Vec<String> {
+        let mut keys = vec![self.refresh_token_public_key.clone()];
+        keys.extend(self.refresh_token_extra_public_keys.iter().cloned());
+        keys
+    }
 }
@@ -0,0 +1,95 @@
+use actix_web::{
+    cookie::{time::Duration as ActixWebDuration, Cookie, SameSite},
+    http::{header, Method},
+    HttpRequest,
+};
+use sha2::{Digest, Sha256};
+
+use super::{config::Config, error::ErrorMessage};
+
+/// Cookie holding the token of the double-submit check. Scripts of allowed
+/// origins read it from the `X-CSRF-Token` response header instead.
+pub const CSRF_COOKIE: &str = "csrf_token";
+/// Header state-changing requests authenticated by cookie must repeat the
+/// token in.
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+pub fn generate_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Lives as long as the refresh token, a refreshed session keeps its token.
+pub fn csrf_cookie(token: &str, config: &Config) -> Cookie<'static> {
+    Cookie::build(CSRF_COOKIE, token.to_string())
+        .path("/")
+        .max_age(ActixWebDuration::new(config.refresh_token_max_age * 60, 0))
+        .same_site(SameSite::Lax)
+        .http_only(false)
+        .finish()
+}
+
+pub fn expired_csrf_cookie() -> Cookie<'static> {
+    Cookie::build(CSRF_COOKIE, "")
+        .path("/")
+        .max_age(ActixWebDuration::new(-1, 0))
+        .finish()
+}
+
+/// Origin of the request, falling back to the one of the `Referer` header.
+fn request_origin(req: &HttpRequest) -> Option<String> {
+    let header_value = |name| {
+        req.headers()
+            .get(name)
+            .and_then(|h| h.to_str().ok())
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty() && h != "null")
+    };
+
+    header_value(header::ORIGIN).or_else(|| {
+        header_value(header::REFERER).map(|referer| {
+            // scheme://host[:port] is everything before the third slash.
+            referer.splitn(4, '/').take(3).collect::<Vec<_>>().join("/")
+        })
+    })
+}
+
+fn digest(value: &str) -> [u8; 32] {
+    Sha256::digest(value.as_bytes()).into()
+}
+
+/// Guard a request that is authenticated by the `access_token` cookie.
+/// Browsers attach that cookie to forged cross-site requests too, so
+/// state-changing methods must come from a trusted origin when the browser
+/// names one, and must echo the `csrf_token` cookie in `X-CSRF-Token`, which
+/// other sites can neither read nor set. Bearer and API key requests never
+/// get here.
+pub fn verify(req: &HttpRequest, config: &Config) -> Result<(), ErrorMessage> {
+    if matches!(
+        *req.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    ) {
+        return Ok(());
+    }
+
+    if let Some(origin) = request_origin(req) {
+        if !config.is_trusted_origin(&origin) {
+            return Err(ErrorMessage::CsrfOriginDenied);
+        }
+    }
+
+    let cookie_token = req.cookie(CSRF_COOKIE).map(|c| c.value().to_string());
+    let header_token = req
+        .headers()
+        .get(CSRF_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(|h| h.trim().to_string());
+
+    match (cookie_token, header_token) {
+        (Some(cookie_token), Some(header_token))
+            if !cookie_token.is_empty() && digest(&cookie_token) == digest(&header_token) =>
+        {
+            Ok(())
+        }
+        _ => Err(ErrorMessage::CsrfTokenInvalid),
+    }
+}
@@ -34,6 +34,17 @@ pub enum ErrorMessage {
     UserNoLongerExist,
     TokenNotProvided,
     PermissionDenied,
+    OrganizationNotSelected,
+    NoOrganization,
+    NotOrganizationMember,
+    OrganizationManagerRequired,
+    StoreNotFound,
+    TwoFactorRequired,
+    ApiKeyScopeDenied,
+    ApiKeyNotAllowed,
+    AccountDisabled,
+    CsrfOriginDenied,
+    CsrfTokenInvalid,
 }
 
 impl ToString for ErrorMessage {
@@ -70,6 +81,35 @@ impl ErrorMessage {
             ErrorMessage::PermissionDenied => {
                 "You are not allowed to perform this action".to_string()
             }
+            ErrorMessage::OrganizationNotSelected => {
+                "Please select an organization with the X-Organization-Id header".to_string()
+            }
+            ErrorMessage::NoOrganization => {
+                "You do not belong to an organization yet, create one with POST /api/organizations or ask an owner to add you".to_string()
+            }
+            ErrorMessage::NotOrganizationMember => {
+                "You are not a member of this organization".to_string()
+            }
+            ErrorMessage::OrganizationManagerRequired => {
+                "Only owners and admins of the organization can do this".to_string()
+            }
+            ErrorMessage::StoreNotFound => "Store does not exist in this organization".to_string(),
+            ErrorMessage::TwoFactorRequired => {
+                "Admins must enable two-factor authentication first".to_string()
+            }
+            ErrorMessage::ApiKeyScopeDenied => {
+                "This API key is not allowed to perform this action".to_string()
+            }
+            ErrorMessage::ApiKeyNotAllowed => {
+                "This action needs a signed in session, API keys can not be used".to_string()
+            }
+            ErrorMessage::AccountDisabled => "This account has been disabled".to_string(),
+            ErrorMessage::CsrfOriginDenied => {
+                "Requests from this origin may not use the session cookie".to_string()
+            }
+            ErrorMessage::CsrfTokenInvalid => {
+                "Missing or invalid X-CSRF-Token header for the session cookie".to_string()
+            }
         }
     }
 }
@@ -5,149 +5,356 @@ use std::{
 
 use actix_web::{
     dev::{Payload, Service, ServiceRequest, ServiceResponse, Transform},
-    error::{ErrorForbidden, ErrorInternalServerError, ErrorUnauthorized},
+    error::{
+        ErrorBadRequest, ErrorConflict, ErrorForbidden, ErrorInternalServerError, ErrorNotFound,
+        ErrorUnauthorized,
+    },
     http, web, FromRequest, HttpMessage, HttpRequest,
 };
-use futures::executor::block_on;
 use futures_util::{
     future::{ready, LocalBoxFuture, Ready},
     FutureExt,
 };
-use redis::Commands;
+use redis::AsyncCommands;
 
 use crate::{
-    models::user::{UserModel, UserRole},
-    services::user_services::UserService,
+    models::{
+        api_key::ApiKeyModel,
+        organization::OrganizationRole,
+        user::{UserModel, UserRole},
+    },
+    services::{
+        api_key_service::{self, ApiKeyCheck, ApiKeyService},
+        organization_service::OrganizationService,
+        permission_service::PermissionService,
+        session_service,
+        two_factor_service::TwoFactorService,
+        user_services::UserService,
+    },
     AppState,
 };
 
 use super::{
+    config::Config,
+    csrf,
     error::{ErrorMessage, ErrorResponse, HttpError},
     token,
 };
 
+#[derive(Clone)]
 pub struct Authenticated {
     pub user: UserModel,
     pub access_token_uuid: uuid::Uuid,
+    pub session_id: String,
+    /// Set when the request is authenticated with an API key instead of a
+    /// session, the token uuid is nil and the session id empty then.
+    pub api_key_id: Option<String>,
+}
+
+/// Access token from the `Authorization` header or the `access_token`
+/// cookie. The header wins, browsers never add it to forged requests, while
+/// cookie requests have to pass the CSRF check, see [`csrf::verify`].
+fn request_token(req: &HttpRequest, config: &Config) -> Result<Option<String>, actix_web::Error> {
+    let header_token = req
+        .headers()
+        .get(http::header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.get(7..))
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty());
+
+    if header_token.is_some() {
+        return Ok(header_token);
+    }
+
+    match req.cookie("access_token") {
+        Some(cookie) => {
+            csrf::verify(req, config).map_err(|e| {
+                ErrorForbidden(ErrorResponse {
+                    status: "fail".to_string(),
+                    message: e.to_string(),
+                })
+            })?;
+            Ok(Some(cookie.value().to_string()))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Authenticate a request made with an API key, see [`ApiKeyService`].
+async fn authenticate_api_key(
+    data: &AppState,
+    key: &str,
+    method: &http::Method,
+) -> Result<(ApiKeyModel, UserModel), actix_web::Error> {
+    match ApiKeyService::new(data.db.clone())
+        .authenticate(key, method)
+        .await
+    {
+        Ok(ApiKeyCheck::Valid { api_key, user }) => Ok((api_key, user)),
+        Ok(ApiKeyCheck::Invalid) => Err(ErrorUnauthorized(ErrorResponse {
+            status: "fail".to_string(),
+            message: ErrorMessage::InvalidToken.to_string(),
+        })),
+        Ok(ApiKeyCheck::OutOfScope) => Err(ErrorForbidden(ErrorResponse {
+            status: "fail".to_string(),
+            message: ErrorMessage::ApiKeyScopeDenied.to_string(),
+        })),
+        Err(e) => Err(ErrorInternalServerError(HttpError::server_error(e))),
+    }
+}
+
+/// Check an access token or API key the same way for [`Authenticated`] and
+/// [`RequireAuth`]: the token's session must still exist in Redis and the
+/// account must not be disabled.
+async fn authenticate(
+    data: &AppState,
+    token: Option<String>,
+    method: &http::Method,
+) -> Result<Authenticated, actix_web::Error> {
+    let token = token.ok_or(ErrorUnauthorized(ErrorResponse {
+        status: "fail".to_string(),
+        message: ErrorMessage::TokenNotProvided.to_string(),
+    }))?;
+
+    if api_key_service::is_api_key(&token) {
+        let (api_key, user) = authenticate_api_key(data, &token, method).await?;
+
+        return Ok(Authenticated {
+            user,
+            access_token_uuid: uuid::Uuid::nil(),
+            session_id: String::new(),
+            api_key_id: Some(api_key.id),
+        });
+    }
+
+    let access_token_details =
+        token::verify_jwt_token(&data.config.access_token_public_keys(), &token).map_err(|e| {
+            ErrorUnauthorized(ErrorResponse {
+                status: "fail".to_string(),
+                message: format!("{:?}", e),
+            })
+        })?;
+
+    // The shared connection multiplexes every request, cloning it is cheap.
+    let mut redis = data.redis.clone();
+    let user_id: Option<String> = redis
+        .get(access_token_details.token_uuid.to_string())
+        .await
+        .map_err(|e| {
+            ErrorInternalServerError(ErrorResponse {
+                status: "fail".to_string(),
+                message: format!("Could not connect to Redis: {}", e),
+            })
+        })?;
+
+    // Logging out or revoking the session deletes the token key.
+    let user_id = user_id.ok_or(ErrorUnauthorized(ErrorResponse {
+        status: "fail".to_string(),
+        message: ErrorMessage::InvalidToken.to_string(),
+    }))?;
+
+    let user = UserService::new(data.db.clone())
+        .get_user(Some(&user_id), None, None)
+        .await
+        .map_err(|e| ErrorInternalServerError(HttpError::server_error(e.to_string())))?
+        .ok_or(ErrorUnauthorized(ErrorResponse {
+            status: "fail".to_string(),
+            message: ErrorMessage::UserNoLongerExist.to_string(),
+        }))?;
+
+    if user.is_disabled() {
+        return Err(ErrorForbidden(ErrorResponse {
+            status: "fail".to_string(),
+            message: ErrorMessage::AccountDisabled.to_string(),
+        }));
+    }
+
+    let session_id = access_token_details.session_id;
+    let touched_session_id = session_id.clone();
+    actix_web::rt::spawn(async move {
+        session_service::touch_session(&redis, &touched_session_id).await;
+    });
+
+    Ok(Authenticated {
+        user,
+        access_token_uuid: access_token_details.token_uuid,
+        session_id,
+        api_key_id: None,
+    })
 }
 
 impl FromRequest for Authenticated {
     type Error = actix_web::Error;
-    type Future = Ready<Result<Self, Self::Error>>;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
 
     fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
-        let data = req.app_data::<web::Data<AppState>>().unwrap();
-
-        let access_token = req
-            .cookie("access_token")
-            .map(|c| c.value().to_string())
-            .or_else(|| {
-                req.headers()
-                    .get(http::header::AUTHORIZATION)
-                    .map(|h| h.to_str().unwrap().split_at(7).1.to_string())
-            });
-
-        if access_token.is_none() {
-            let json_error = ErrorResponse {
-                status: "fail".to_string(),
-                message: "You are not logged in, please provide token".to_string(),
-            };
-            return ready(Err(ErrorUnauthorized(json_error)));
+        // Behind `RequireAuth` the request was authenticated already.
+        if let Some(authenticated) = req.extensions().get::<Authenticated>() {
+            return ready(Ok(authenticated.clone())).boxed_local();
         }
 
-        let access_token_details = match token::verify_jwt_token(
-            data.config.access_token_public_key.to_owned(),
-            &access_token.unwrap(),
-        ) {
-            Ok(token_details) => token_details,
-            Err(e) => {
-                let json_error = ErrorResponse {
-                    status: "fail".to_string(),
-                    message: format!("{:?}", e),
-                };
-                return ready(Err(ErrorUnauthorized(json_error)));
-            }
+        let data = req.app_data::<web::Data<AppState>>().unwrap().clone();
+        let token = match request_token(req, &data.config) {
+            Ok(token) => token,
+            Err(e) => return ready(Err(e)).boxed_local(),
         };
+        let method = req.method().clone();
 
-        let access_token_uuid =
-            uuid::Uuid::parse_str(&access_token_details.token_uuid.to_string()).unwrap();
-
-        let user_id_redis_result = async move {
-            let mut redis_client = match data.redis_client.get_connection() {
-                Ok(redis_client) => redis_client,
-                Err(e) => {
-                    return Err(ErrorInternalServerError(ErrorResponse {
-                        status: "fail".to_string(),
-                        message: format!("Could not connect to Redis: {}", e),
-                    }));
-                }
-            };
+        async move { authenticate(&data, token, &method).await }.boxed_local()
+    }
+}
 
-            let redis_result = redis_client.get::<_, String>(access_token_uuid.clone().to_string());
+impl std::ops::Deref for Authenticated {
+    type Target = UserModel;
 
-            match redis_result {
-                Ok(value) => Ok(value),
-                Err(_) => Err(ErrorUnauthorized(ErrorResponse {
-                    status: "fail".to_string(),
-                    message: "Token is invalid or session has expired".to_string(),
-                })),
-            }
-        };
+    fn deref(&self) -> &Self::Target {
+        &self.user
+    }
+}
 
-        let user_exists_result = async move {
-            let user_id = user_id_redis_result.await?;
+/// Organization (and optionally store) the request is scoped to.
+///
+/// The organization is taken from the `X-Organization-Id` header, falling back
+/// to the only organization of the user when they belong to exactly one.
+/// Users without any organization get a 409 telling them to create one. An
+/// optional `X-Store-Id` header narrows the scope to a single store.
+pub struct Tenant {
+    pub user: UserModel,
+    pub organization_id: String,
+    pub store_id: Option<String>,
+    pub role: OrganizationRole,
+}
 
-            let user_service = UserService::new(data.db.clone());
+pub const ORGANIZATION_HEADER: &str = "X-Organization-Id";
+pub const STORE_HEADER: &str = "X-Store-Id";
 
-            let query_result = user_service.get_user(Some(&user_id), None, None).await;
+impl FromRequest for Tenant {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let authenticated = Authenticated::from_request(req, payload);
+        let data = req.app_data::<web::Data<AppState>>().unwrap().clone();
+
+        let header_value = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|h| h.to_str().ok())
+                .map(|h| h.trim().to_string())
+                .filter(|h| !h.is_empty())
+        };
+        let organization_id = header_value(ORGANIZATION_HEADER);
+        let store_id = header_value(STORE_HEADER);
 
-            match query_result {
-                Ok(Some(user)) => Ok(user),
-                Ok(None) => {
-                    let json_error = ErrorResponse {
-                        status: "fail".to_string(),
-                        message: "the user belonging to this token no logger exists".to_string(),
-                    };
-                    Err(ErrorUnauthorized(json_error))
+        async move {
+            let Authenticated { user, .. } = authenticated.await?;
+
+            let organization_service = OrganizationService::new(data.db.clone());
+
+            let (organization_id, role) = match organization_id {
+                Some(organization_id) => {
+                    let member = organization_service
+                        .get_member(&organization_id, &user.id)
+                        .await
+                        .map_err(|e| {
+                            ErrorInternalServerError(HttpError::server_error(e.to_string()))
+                        })?
+                        .ok_or(ErrorForbidden(ErrorResponse {
+                            status: "fail".to_string(),
+                            message: ErrorMessage::NotOrganizationMember.to_string(),
+                        }))?;
+
+                    (member.organization_id, member.role)
                 }
-                Err(_) => {
-                    let json_error = ErrorResponse {
-                        status: "error".to_string(),
-                        message: "Faled to check user existence".to_string(),
-                    };
-                    Err(ErrorInternalServerError(json_error))
+                None => {
+                    let mut organizations = organization_service
+                        .get_user_organizations(&user.id)
+                        .await
+                        .map_err(|e| {
+                            ErrorInternalServerError(HttpError::server_error(e.to_string()))
+                        })?;
+
+                    if organizations.is_empty() {
+                        return Err(ErrorConflict(ErrorResponse {
+                            status: "fail".to_string(),
+                            message: ErrorMessage::NoOrganization.to_string(),
+                        }));
+                    }
+
+                    if organizations.len() != 1 {
+                        return Err(ErrorBadRequest(ErrorResponse {
+                            status: "fail".to_string(),
+                            message: ErrorMessage::OrganizationNotSelected.to_string(),
+                        }));
+                    }
+
+                    let organization = organizations.remove(0);
+                    (organization.id, organization.role)
                 }
+            };
+
+            if let Some(store_id) = &store_id {
+                organization_service
+                    .get_store_by_id(&organization_id, store_id)
+                    .await
+                    .map_err(|e| ErrorInternalServerError(HttpError::server_error(e.to_string())))?
+                    .ok_or(ErrorNotFound(ErrorResponse {
+                        status: "fail".to_string(),
+                        message: ErrorMessage::StoreNotFound.to_string(),
+                    }))?;
             }
-        };
 
-        match block_on(user_exists_result) {
-            Ok(user) => ready(Ok(Authenticated {
-                access_token_uuid,
+            Ok(Tenant {
                 user,
-            })),
-            Err(error) => ready(Err(error)),
+                organization_id,
+                store_id,
+                role,
+            })
         }
+        .boxed_local()
     }
 }
 
-impl std::ops::Deref for Authenticated {
-    type Target = UserModel;
-
-    fn deref(&self) -> &Self::Target {
-        &self.user
-    }
+/// What a route asks of the authenticated user.
+enum Access {
+    Roles(Vec<UserRole>),
+    Permission(&'static str),
 }
 
 pub struct RequireAuth {
-    pub allowed_roles: Rc<Vec<UserRole>>,
+    access: Rc<Access>,
+    pub two_factor_exempt: bool,
+    pub session_only: bool,
 }
 
 impl RequireAuth {
     pub fn allowed_roles(allowed_roles: Vec<UserRole>) -> Self {
         RequireAuth {
-            allowed_roles: Rc::new(allowed_roles),
+            access: Rc::new(Access::Roles(allowed_roles)),
+            two_factor_exempt: false,
+            session_only: false,
         }
     }
+
+    /// Any logged in user, for routes about the user's own account.
+    pub fn authenticated() -> Self {
+        Self::allowed_roles(vec![UserRole::User, UserRole::Moderator, UserRole::Admin])
+    }
+
+    /// Keep the route reachable for admins who still have to enroll while
+    /// `REQUIRE_ADMIN_TWO_FACTOR` is on, e.g. the two-factor setup itself.
+    pub fn two_factor_exempt(mut self) -> Self {
+        self.two_factor_exempt = true;
+        self
+    }
+
+    /// Refuse API keys, for routes guarding the account itself: a leaked key
+    /// must not be able to enroll two-factor, end sessions or mint keys.
+    pub fn session_only(mut self) -> Self {
+        self.session_only = true;
+        self
+    }
 }
 
 impl<S> Transform<S, ServiceRequest> for RequireAuth
@@ -167,14 +374,54 @@ where
     fn new_transform(&self, service: S) -> Self::Future {
         ready(Ok(AuthMiddleware {
             service: Rc::new(service),
-            allowed_roles: self.allowed_roles.clone(),
+            access: self.access.clone(),
+            two_factor_exempt: self.two_factor_exempt,
+            session_only: self.session_only,
+        }))
+    }
+}
+
+/// Authenticate like [`RequireAuth`], then require a permission granted by
+/// one of the user's roles, see `models::permission`.
+pub struct RequirePermission {
+    permission: &'static str,
+}
+
+impl RequirePermission {
+    pub fn new(permission: &'static str) -> Self {
+        RequirePermission { permission }
+    }
+}
+
+impl<S> Transform<S, ServiceRequest> for RequirePermission
+where
+    S: Service<
+            ServiceRequest,
+            Response = ServiceResponse<actix_web::body::BoxBody>,
+            Error = actix_web::Error,
+        > + 'static,
+{
+    type Response = ServiceResponse<actix_web::body::BoxBody>;
+    type Error = actix_web::Error;
+    type Transform = AuthMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AuthMiddleware {
+            service: Rc::new(service),
+            access: Rc::new(Access::Permission(self.permission)),
+            two_factor_exempt: false,
+            session_only: false,
         }))
     }
 }
 
 pub struct AuthMiddleware<S> {
     service: Rc<S>,
-    allowed_roles: Rc<Vec<UserRole>>,
+    access: Rc<Access>,
+    two_factor_exempt: bool,
+    session_only: bool,
 }
 
 impl<S> Service<ServiceRequest> for AuthMiddleware<S>
@@ -194,57 +441,61 @@ where
     }
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let token = req
-            .cookie("access_token")
-            .map(|c| c.value().to_string())
-            .or_else(|| {
-                req.headers()
-                    .get(http::header::AUTHORIZATION)
-                    .map(|h| h.to_str().unwrap().split_at(7).1.to_string())
-            });
-
-        if token.is_none() {
-            let json_error = ErrorResponse {
-                status: "fail".to_string(),
-                message: ErrorMessage::TokenNotProvided.to_string(),
-            };
-            return Box::pin(ready(Err(ErrorUnauthorized(json_error))));
-        }
-
-        let app_state = req.app_data::<web::Data<AppState>>().unwrap();
-        let user_id = match token::verify_jwt_token(
-            app_state.config.access_token_public_key.clone(),
-            &token.unwrap(),
-        ) {
-            Ok(token_detail) => token_detail.user_id,
-            Err(e) => {
-                return Box::pin(ready(Err(ErrorUnauthorized(ErrorResponse {
-                    status: "fail".to_string(),
-                    message: format!("{:?}", e),
-                }))))
-            }
+        let cloned_app_state = req.app_data::<web::Data<AppState>>().unwrap().clone();
+        let token = match request_token(req.request(), &cloned_app_state.config) {
+            Ok(token) => token,
+            Err(e) => return ready(Err(e)).boxed_local(),
         };
+        let method = req.method().clone();
 
-        let cloned_app_state = app_state.clone();
-        let allowed_roles = self.allowed_roles.clone();
+        let access = self.access.clone();
+        let two_factor_exempt = self.two_factor_exempt;
+        let session_only = self.session_only;
         let srv = Rc::clone(&self.service);
 
         async move {
-            let user_id = uuid::Uuid::parse_str(user_id.as_str()).unwrap();
+            let authenticated = authenticate(&cloned_app_state, token, &method).await?;
+            let user = &authenticated.user;
 
-            let result = UserService::new(cloned_app_state.db.clone())
-                .get_user(Some(&user_id.to_string()), None, None)
+            if session_only && authenticated.api_key_id.is_some() {
+                return Err(ErrorForbidden(ErrorResponse {
+                    status: "fail".to_string(),
+                    message: ErrorMessage::ApiKeyNotAllowed.to_string(),
+                }));
+            }
+
+            if cloned_app_state.config.require_admin_two_factor
+                && user.role == UserRole::Admin
+                && !two_factor_exempt
+            {
+                let enrolled = TwoFactorService::new(
+                    cloned_app_state.db.clone(),
+                    cloned_app_state.redis.clone(),
+                )
+                .is_enabled(&user.id)
                 .await
-                .map_err(|e| ErrorInternalServerError(HttpError::server_error(e.to_string())))?;
+                .map_err(|e| ErrorInternalServerError(HttpError::server_error(e)))?;
 
-            let user = result.ok_or(ErrorUnauthorized(ErrorResponse {
-                status: "fail".to_string(),
-                message: ErrorMessage::UserNoLongerExist.to_string(),
-            }))?;
+                if !enrolled {
+                    return Err(ErrorForbidden(ErrorResponse {
+                        status: "fail".to_string(),
+                        message: ErrorMessage::TwoFactorRequired.to_string(),
+                    }));
+                }
+            }
+
+            let allowed = match access.as_ref() {
+                Access::Roles(allowed_roles) => allowed_roles.contains(&user.role),
+                Access::Permission(permission) => {
+                    PermissionService::new(cloned_app_state.db.clone())
+                        .has_permission(user, permission)
+                        .await
+                        .map_err(|e| ErrorInternalServerError(HttpError::server_error(e)))?
+                }
+            };
 
-            // Check if user's role matches the required role
-            if allowed_roles.contains(&user.role) {
-                req.extensions_mut().insert::<UserModel>(user);
+            if allowed {
+                req.extensions_mut().insert::<Authenticated>(authenticated);
                 let res = srv.call(req).await?;
                 Ok(res)
             } else {
@@ -0,0 +1,117 @@
+use std::{path::PathBuf, sync::Arc};
+
+use async_trait::async_trait;
+use lettre::{
+    message::header::ContentType, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+
+use crate::utils::config::Config;
+
+/// A plain text email.
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, mail: &Mail) -> Result<(), String>;
+}
+
+/// Build the mailer selected by `MAILER`, `smtp` or `file`.
+pub fn from_config(config: &Config) -> Arc<dyn Mailer> {
+    match config.mailer.as_str() {
+        "smtp" => Arc::new(SmtpMailer::new(config)),
+        _ => Arc::new(FileMailer::new(config)),
+    }
+}
+
+pub struct SmtpMailer {
+    from: String,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailer {
+    pub fn new(config: &Config) -> Self {
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
+            .unwrap_or_else(|e| panic!("Invalid SMTP_HOST {}: {}", config.smtp_host, e))
+            .port(config.smtp_port);
+
+        if !config.smtp_username.is_empty() {
+            builder = builder.credentials(Credentials::new(
+                config.smtp_username.to_owned(),
+                config.smtp_password.to_owned(),
+            ));
+        }
+
+        Self {
+            from: config.mail_from.to_owned(),
+            transport: builder.build(),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, mail: &Mail) -> Result<(), String> {
+        let message = build_message(&self.from, mail)?;
+
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Writes every mail as an `.eml` file into `MAIL_OUTBOX_DIR`, for local
+/// development and tests without an SMTP server.
+pub struct FileMailer {
+    from: String,
+    outbox_dir: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            from: config.mail_from.to_owned(),
+            outbox_dir: PathBuf::from(&config.mail_outbox_dir),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, mail: &Mail) -> Result<(), String> {
+        let message = build_message(&self.from, mail)?;
+
+        let file_name = format!(
+            "{}-{}.eml",
+            chrono::Utc::now().format("%Y%m%d%H%M%S"),
+            uuid::Uuid::new_v4()
+        );
+
+        std::fs::create_dir_all(&self.outbox_dir).map_err(|e| e.to_string())?;
+        std::fs::write(self.outbox_dir.join(file_name), message.formatted())
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn build_message(from: &str, mail: &Mail) -> Result<Message, String> {
+    Message::builder()
+        .from(
+            from.parse()
+                .map_err(|e| format!("Invalid MAIL_FROM: {}", e))?,
+        )
+        .to(mail
+            .to
+            .parse()
+            .map_err(|e| format!("Invalid recipient: {}", e))?)
+        .subject(mail.subject.to_owned())
+        .header(ContentType::TEXT_PLAIN)
+        .body(mail.body.to_owned())
+        .map_err(|e| e.to_string())
+}
@@ -1,6 +1,9 @@
 pub mod config;
+pub mod csrf;
 pub mod error;
 pub mod extractor;
+pub mod mailer;
+pub mod money;
 pub mod password;
 pub mod token;
-pub mod typst_wrapper_world;
\ No newline at end of file
+pub mod typst_wrapper_world;
@@ -0,0 +1,315 @@
+use std::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, Sub},
+};
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use validator::ValidationError;
+
+/// Number of fraction digits kept for money, matches `DECIMAL(19, 2)` columns.
+pub const MONEY_SCALE: u32 = 2;
+
+/// Largest amount a `DECIMAL(19, 2)` column holds.
+pub fn max_amount() -> Decimal {
+    Decimal::from_i128_with_scale(9_999_999_999_999_999_999, MONEY_SCALE)
+}
+
+/// Rupiah amount.
+///
+/// Stored as `DECIMAL(19, 2)` in MySQL and serialized in JSON as a string with
+/// two fraction digits (`"57470250.00"`) so clients never lose precision.
+/// Deserialization accepts both strings and plain JSON numbers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(Decimal);
+
+impl Money {
+    pub fn new(amount: Decimal) -> Self {
+        Money(amount.round_dp_with_strategy(MONEY_SCALE, RoundingStrategy::MidpointAwayFromZero))
+    }
+
+    pub fn zero() -> Self {
+        Money(Decimal::ZERO)
+    }
+
+    pub fn amount(&self) -> Decimal {
+        self.0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0.is_sign_negative() && !self.0.is_zero()
+    }
+
+    /// Indonesian currency notation, e.g. `Rp 57.470.250` or `Rp 1.500,50`.
+    pub fn to_rupiah(&self) -> String {
+        let amount = self.0.abs();
+        let whole = amount.trunc();
+        let cents = ((amount - whole) * Decimal::ONE_HUNDRED)
+            .trunc()
+            .to_string()
+            .parse::<u32>()
+            .unwrap_or(0);
+
+        let digits = whole.to_string();
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                grouped.push('.');
+            }
+            grouped.push(digit);
+        }
+
+        let sign = if self.is_negative() { "-" } else { "" };
+
+        if cents == 0 {
+            format!("{}Rp {}", sign, grouped)
+        } else {
+            format!("{}Rp {},{:02}", sign, grouped, cents)
+        }
+    }
+
+    /// The whole Rupiah part spelled out in Indonesian, as printed on vouchers
+    /// ("Lima Puluh Tujuh Juta Empat Ratus Tujuh Puluh Ribu Dua Ratus Lima Puluh").
+    pub fn terbilang(&self) -> String {
+        let whole = self.0.abs().trunc();
+        let value = whole.to_string().parse::<u64>().unwrap_or(0);
+
+        if value == 0 {
+            return "Nol".to_string();
+        }
+
+        let words = spell(value)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if self.is_negative() {
+            format!("Minus {}", words)
+        } else {
+            words
+        }
+    }
+}
+
+fn spell(value: u64) -> String {
+    const UNITS: [&str; 12] = [
+        "", "Satu", "Dua", "Tiga", "Empat", "Lima", "Enam", "Tujuh", "Delapan", "Sembilan",
+        "Sepuluh", "Sebelas",
+    ];
+
+    match value {
+        0..=11 => UNITS[value as usize].to_string(),
+        12..=19 => format!("{} Belas", spell(value - 10)),
+        20..=99 => format!("{} Puluh {}", spell(value / 10), spell(value % 10)),
+        100..=199 => format!("Seratus {}", spell(value - 100)),
+        200..=999 => format!("{} Ratus {}", spell(value / 100), spell(value % 100)),
+        1_000..=1_999 => format!("Seribu {}", spell(value - 1_000)),
+        2_000..=999_999 => format!("{} Ribu {}", spell(value / 1_000), spell(value % 1_000)),
+        1_000_000..=999_999_999 => format!(
+            "{} Juta {}",
+            spell(value / 1_000_000),
+            spell(value % 1_000_000)
+        ),
+        1_000_000_000..=999_999_999_999 => format!(
+            "{} Miliar {}",
+            spell(value / 1_000_000_000),
+            spell(value % 1_000_000_000)
+        ),
+        _ => format!(
+            "{} Triliun {}",
+            spell(value / 1_000_000_000_000),
+            spell(value % 1_000_000_000_000)
+        ),
+    }
+}
+
+impl From<Decimal> for Money {
+    fn from(amount: Decimal) -> Self {
+        Money::new(amount)
+    }
+}
+
+impl From<i64> for Money {
+    fn from(amount: i64) -> Self {
+        Money::new(Decimal::from(amount))
+    }
+}
+
+impl From<Money> for Decimal {
+    fn from(money: Money) -> Self {
+        money.0
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, rhs: Money) -> Money {
+        Money::new(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, rhs: Money) -> Money {
+        Money::new(self.0 - rhs.0)
+    }
+}
+
+impl Mul<Decimal> for Money {
+    type Output = Money;
+
+    fn mul(self, rhs: Decimal) -> Money {
+        Money::new(self.0 * rhs)
+    }
+}
+
+impl Mul<i32> for Money {
+    type Output = Money;
+
+    fn mul(self, rhs: i32) -> Money {
+        Money::new(self.0 * Decimal::from(rhs))
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::zero(), |acc, money| acc + money)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2}", self.0)
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Decimal::deserialize(deserializer).map(Money::new)
+    }
+}
+
+/// `validator` hook rejecting negative amounts.
+pub fn validate_non_negative(money: &Money) -> Result<(), ValidationError> {
+    if money.is_negative() {
+        return Err(ValidationError::new("Amount must not be negative"));
+    }
+
+    Ok(())
+}
+
+/// `validator` hook rejecting amounts a money column can not hold, either sign.
+pub fn validate_amount_range(value: &Decimal) -> Result<(), ValidationError> {
+    if value.abs() > max_amount() {
+        return Err(ValidationError::new("Value is too large"));
+    }
+
+    Ok(())
+}
+
+/// `validator` hook for rounding steps: at least one cent, at most the
+/// largest amount.
+pub fn validate_money_step(value: &Decimal) -> Result<(), ValidationError> {
+    if *value < Decimal::new(1, MONEY_SCALE) {
+        return Err(ValidationError::new("Step must be at least 0.01"));
+    }
+
+    validate_amount_range(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(mantissa: i64, scale: u32) -> Money {
+        Money::new(Decimal::new(mantissa, scale))
+    }
+
+    #[test]
+    fn rounds_to_two_digits_away_from_zero() {
+        assert_eq!(money(10005, 3).amount(), Decimal::new(1001, 2));
+        assert_eq!(money(-10005, 3).amount(), Decimal::new(-1001, 2));
+        assert_eq!(money(10004, 3).amount(), Decimal::new(1000, 2));
+    }
+
+    #[test]
+    fn zero_is_not_negative() {
+        assert!(!Money::zero().is_negative());
+        assert!(Money::from(-1).is_negative());
+    }
+
+    #[test]
+    fn to_rupiah_groups_thousands() {
+        assert_eq!(Money::zero().to_rupiah(), "Rp 0");
+        assert_eq!(Money::from(999).to_rupiah(), "Rp 999");
+        assert_eq!(Money::from(1_000).to_rupiah(), "Rp 1.000");
+        assert_eq!(Money::from(57_470_250).to_rupiah(), "Rp 57.470.250");
+    }
+
+    #[test]
+    fn to_rupiah_keeps_cents_and_sign() {
+        assert_eq!(money(150050, 2).to_rupiah(), "Rp 1.500,50");
+        assert_eq!(money(100005, 2).to_rupiah(), "Rp 1.000,05");
+        assert_eq!(Money::from(-1_500).to_rupiah(), "-Rp 1.500");
+    }
+
+    #[test]
+    fn terbilang_spells_whole_rupiah() {
+        assert_eq!(Money::zero().terbilang(), "Nol");
+        assert_eq!(Money::from(10).terbilang(), "Sepuluh");
+        assert_eq!(Money::from(11).terbilang(), "Sebelas");
+        assert_eq!(Money::from(12).terbilang(), "Dua Belas");
+        assert_eq!(Money::from(19).terbilang(), "Sembilan Belas");
+        assert_eq!(Money::from(100).terbilang(), "Seratus");
+        assert_eq!(Money::from(115).terbilang(), "Seratus Lima Belas");
+        assert_eq!(Money::from(250).terbilang(), "Dua Ratus Lima Puluh");
+        assert_eq!(Money::from(1_000).terbilang(), "Seribu");
+        assert_eq!(Money::from(1_500).terbilang(), "Seribu Lima Ratus");
+        assert_eq!(Money::from(21_000).terbilang(), "Dua Puluh Satu Ribu");
+        assert_eq!(Money::from(1_000_000).terbilang(), "Satu Juta");
+        assert_eq!(
+            Money::from(57_470_250).terbilang(),
+            "Lima Puluh Tujuh Juta Empat Ratus Tujuh Puluh Ribu Dua Ratus Lima Puluh"
+        );
+    }
+
+    #[test]
+    fn terbilang_ignores_cents_and_keeps_sign() {
+        assert_eq!(money(150075, 2).terbilang(), "Seribu Lima Ratus");
+        assert_eq!(Money::from(-2_500).terbilang(), "Minus Dua Ribu Lima Ratus");
+    }
+
+    #[test]
+    fn serializes_as_string_with_two_digits() {
+        assert_eq!(serde_json::to_string(&Money::from(5)).unwrap(), "\"5.00\"");
+        assert_eq!(
+            serde_json::from_str::<Money>("11000").unwrap(),
+            Money::from(11_000)
+        );
+        assert_eq!(
+            serde_json::from_str::<Money>("\"11000.505\"").unwrap(),
+            money(1100051, 2)
+        );
+    }
+
+    #[test]
+    fn validate_non_negative_rejects_negative_amounts() {
+        assert!(validate_non_negative(&Money::zero()).is_ok());
+        assert!(validate_non_negative(&Money::from(-1)).is_err());
+    }
+}
@@ -1,11 +1,28 @@
 use argon2::{
     password_hash::{rand_core::OsRng, SaltString},
-    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version,
 };
 
-const MAX_PASSWORD_LENGTH: usize = 64;
+use super::config::Config;
 
-pub fn hash(password: impl Into<String>) -> Result<String, String> {
+/// Upper bound in bytes no password policy can raise, hashing longer input
+/// is only a way to burn CPU.
+const MAX_PASSWORD_LENGTH: usize = 256;
+
+/// Argon2id with the memory and time costs of `ARGON2_*`.
+fn argon2(config: &Config) -> Result<Argon2<'static>, String> {
+    let params = Params::new(
+        config.argon2_memory_kib,
+        config.argon2_iterations,
+        config.argon2_parallelism,
+        None,
+    )
+    .map_err(|e| format!("Invalid Argon2 parameters: {}", e))?;
+
+    Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+}
+
+pub fn hash(password: impl Into<String>, config: &Config) -> Result<String, String> {
     let password = password.into();
 
     if password.is_empty() {
@@ -14,13 +31,13 @@ pub fn hash(password: impl Into<String>) -> Result<String, String> {
 
     if password.len() > MAX_PASSWORD_LENGTH {
         return Err(format!(
-            "Password must not be more than {} characters",
+            "Password must not be more than {} bytes",
             MAX_PASSWORD_LENGTH
         ));
     }
 
     let salt = SaltString::generate(&mut OsRng);
-    let hashed_password = Argon2::default()
+    let hashed_password = argon2(config)?
         .hash_password(password.as_bytes(), &salt)
         .map_err(|_| "Error while hashing password")?
         .to_string();
@@ -28,6 +45,8 @@ pub fn hash(password: impl Into<String>) -> Result<String, String> {
     Ok(hashed_password)
 }
 
+/// The parameters are read from the hash, so hashes made with older
+/// settings keep working.
 pub fn compare(password: &str, hashed_password: &str) -> Result<bool, String> {
     if password.is_empty() {
         return Err("Password cannot be empty".to_string());
@@ -35,7 +54,7 @@ pub fn compare(password: &str, hashed_password: &str) -> Result<bool, String> {
 
     if password.len() > MAX_PASSWORD_LENGTH {
         return Err(format!(
-            "Password must not be more than {} characters",
+            "Password must not be more than {} bytes",
             MAX_PASSWORD_LENGTH
         ));
     }
@@ -49,3 +68,69 @@ pub fn compare(password: &str, hashed_password: &str) -> Result<bool, String> {
 
     Ok(password_matches)
 }
+
+/// Whether `hashed_password` was made with other settings than the current
+/// ones and should be replaced the next time the plain password is known.
+pub fn needs_rehash(hashed_password: &str, config: &Config) -> bool {
+    let parsed_hash = match PasswordHash::new(hashed_password) {
+        Ok(parsed_hash) => parsed_hash,
+        Err(_) => return false,
+    };
+
+    let params = match Params::try_from(&parsed_hash) {
+        Ok(params) => params,
+        Err(_) => return true,
+    };
+
+    parsed_hash.algorithm != Algorithm::Argon2id.ident()
+        || parsed_hash.version != Some(Version::V0x13.into())
+        || params.m_cost() != config.argon2_memory_kib
+        || params.t_cost() != config.argon2_iterations
+        || params.p_cost() != config.argon2_parallelism
+}
+
+/// Check a new password against the `PASSWORD_*` policy. Returns the first
+/// rule it breaks. The minimum counts characters, the maximum counts bytes
+/// like [`hash`] does, so whatever passes here can be hashed.
+pub fn check_policy(password: &str, config: &Config) -> Result<(), String> {
+    let max_length = config.password_max_length.min(MAX_PASSWORD_LENGTH);
+
+    if password.chars().count() < config.password_min_length {
+        return Err(format!(
+            "Password must be at least {} characters",
+            config.password_min_length
+        ));
+    }
+
+    if password.len() > max_length {
+        return Err(format!(
+            "Password must not be more than {} bytes",
+            max_length
+        ));
+    }
+
+    if config.password_require_lowercase && !password.chars().any(char::is_lowercase) {
+        return Err("Password must contain a lowercase letter".to_string());
+    }
+
+    if config.password_require_uppercase && !password.chars().any(char::is_uppercase) {
+        return Err("Password must contain an uppercase letter".to_string());
+    }
+
+    if config.password_require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        return Err("Password must contain a digit".to_string());
+    }
+
+    if config.password_require_symbol && password.chars().all(char::is_alphanumeric) {
+        return Err("Password must contain a symbol".to_string());
+    }
+
+    if config
+        .password_blocklist
+        .contains(&password.trim().to_lowercase())
+    {
+        return Err("This password is too common, please choose another one".to_string());
+    }
+
+    Ok(())
+}
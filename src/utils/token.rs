@@ -1,21 +1,43 @@
 use base64::{engine::general_purpose, Engine};
 use chrono;
 use jsonwebtoken;
+use rsa::{
+    pkcs1::DecodeRsaPublicKey, pkcs8::DecodePublicKey, traits::PublicKeyParts, RsaPublicKey,
+};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
-use crate::models::token::{TokenClaims, TokenDetails};
+use crate::{
+    dtos::token::JwkDto,
+    models::token::{TokenClaims, TokenDetails},
+};
 
+/// Identifies a key in the `kid` header and in the JWKS. It is derived from
+/// the public key, so every instance agrees on it without extra settings.
+pub fn key_id(public_key: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(public_key.trim().as_bytes()));
+    digest[..16].to_string()
+}
+
+fn decode_pem(key: &str) -> String {
+    let bytes_key = general_purpose::STANDARD.decode(key.trim()).unwrap();
+    String::from_utf8(bytes_key).unwrap()
+}
+
+/// Sign with `private_key`, naming its `public_key` in the `kid` header.
 pub fn generate_jwt_token(
     user_id: String,
+    session_id: String,
     ttl: i64,
     private_key: String,
+    public_key: String,
 ) -> Result<TokenDetails, jsonwebtoken::errors::Error> {
-    let bytes_private_key = general_purpose::STANDARD.decode(private_key).unwrap();
-    let decoded_private_key = String::from_utf8(bytes_private_key).unwrap();
+    let decoded_private_key = decode_pem(&private_key);
 
     let now = chrono::Utc::now();
     let mut token_details = TokenDetails {
         user_id,
+        session_id,
         token_uuid: Uuid::new_v4(),
         expires_in: Some((now + chrono::Duration::minutes(ttl)).timestamp()),
         token: None,
@@ -24,12 +46,14 @@ pub fn generate_jwt_token(
     let claims = TokenClaims {
         sub: token_details.user_id.to_string(),
         token_uuid: token_details.token_uuid.to_string(),
+        sid: token_details.session_id.to_string(),
         exp: token_details.expires_in.unwrap(),
         iat: now.timestamp(),
         nbf: now.timestamp(),
     };
 
-    let header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256);
+    let mut header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256);
+    header.kid = Some(key_id(&public_key));
     let token = jsonwebtoken::encode(
         &header,
         &claims,
@@ -39,12 +63,39 @@ pub fn generate_jwt_token(
     Ok(token_details)
 }
 
+/// Verify against the key named by the `kid` header among `public_keys`.
+/// Tokens signed before key ids were added are tried against all of them.
 pub fn verify_jwt_token(
-    public_key: String,
+    public_keys: &[String],
+    token: &str,
+) -> Result<TokenDetails, jsonwebtoken::errors::Error> {
+    let header = jsonwebtoken::decode_header(token)?;
+
+    let candidates: Vec<&String> = match &header.kid {
+        Some(kid) => public_keys
+            .iter()
+            .filter(|public_key| &key_id(public_key) == kid)
+            .collect(),
+        None => public_keys.iter().collect(),
+    };
+
+    let mut error: jsonwebtoken::errors::Error =
+        jsonwebtoken::errors::ErrorKind::InvalidToken.into();
+    for public_key in candidates {
+        match decode_claims(public_key, token) {
+            Ok(token_details) => return Ok(token_details),
+            Err(e) => error = e,
+        }
+    }
+
+    Err(error)
+}
+
+fn decode_claims(
+    public_key: &str,
     token: &str,
 ) -> Result<TokenDetails, jsonwebtoken::errors::Error> {
-    let bytes_public_key = general_purpose::STANDARD.decode(public_key).unwrap();
-    let decoded_public_key = String::from_utf8(bytes_public_key).unwrap();
+    let decoded_public_key = decode_pem(public_key);
 
     let validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::RS256);
 
@@ -61,6 +112,24 @@ pub fn verify_jwt_token(
         token: None,
         token_uuid,
         user_id,
-        expires_in: None,
+        session_id: decoded.claims.sid,
+        expires_in: Some(decoded.claims.exp),
+    })
+}
+
+/// Describe a base64 encoded RSA public key as a JSON Web Key.
+pub fn public_jwk(public_key: &str) -> Result<JwkDto, String> {
+    let pem = decode_pem(public_key);
+    let rsa_key = RsaPublicKey::from_public_key_pem(&pem)
+        .or_else(|_| RsaPublicKey::from_pkcs1_pem(&pem))
+        .map_err(|e| e.to_string())?;
+
+    Ok(JwkDto {
+        kty: "RSA".to_string(),
+        key_use: "sig".to_string(),
+        alg: "RS256".to_string(),
+        kid: key_id(public_key),
+        n: general_purpose::URL_SAFE_NO_PAD.encode(rsa_key.n().to_bytes_be()),
+        e: general_purpose::URL_SAFE_NO_PAD.encode(rsa_key.e().to_bytes_be()),
     })
 }